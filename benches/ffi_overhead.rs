@@ -0,0 +1,181 @@
+//! How much does crossing the FFI boundary cost?
+//!
+//! Compares:
+//!
+//! - `multiply()` in C, called directly through `raw`, through the safe
+//!   wrapper and through `safe::checked_multiply()`, which checks for
+//!   overflow in Rust first, with the same multiplication in Rust, with and
+//!   without `checked_mul`, for operands of a few magnitudes. And with
+//!   `multiply_batch()`, which multiplies a whole array of pairs in one call.
+//! - `sum_array()` in C with a Rust sum over slices of growing length.
+//! - `mat_mul()` in C with the same triple loop in Rust.
+//!
+//! Run with:
+//!
+//!     cargo bench --bench ffi_overhead
+//!
+//! The C code is the same library the demo links, through the same
+//! `build.rs`.
+//!
+//! The inputs go through `black_box`, so the compiler can't compute the
+//! results ahead of time. Every `multiply` case reports the time per
+//! multiplication, batched or not, so the numbers can be compared directly.
+//!
+//! Expect the single call to be a few nanoseconds slower through C: the Rust
+//! version is inlined and the C one can't be, since the compiler can't see
+//! into the C object. The per-call difference is the cost of the call
+//! itself. For slices and batches, that cost is paid once per call, not once
+//! per element, so it disappears as they grow. `raw` and `ffi` should be
+//! about the same: the safe wrapper's checks compile to almost nothing.
+//!
+//! The magnitude of the operands shouldn't matter: a multiplication takes
+//! the same time for any `i32`. If it does, something other than the
+//! multiplication is being measured.
+//!
+//! Medians per multiplication on x86-64 Linux (a single-core Xeon VM, so
+//! expect some noise; run it on your own machine before drawing
+//! conclusions):
+//!
+//! ```text
+//!                   1e1       1e3       1e9
+//! raw            10.1 ns   10.5 ns   12.4 ns
+//! ffi            12.1 ns   10.5 ns   10.9 ns
+//! checked        12.1 ns   13.7 ns   10.4 ns
+//! rust            0.8 ns    0.7 ns    0.8 ns
+//! rust_checked    0.9 ns    1.1 ns    1.4 ns
+//!
+//! batch/16 0.8 ns, batch/1024 0.5 ns, batch/65536 0.5 ns
+//! ```
+//!
+//! The C `multiply()` does more than multiply: it bumps an atomic call
+//! counter and checks the verbose flag, which is part of the 10 ns. The
+//! differences between the C rows, and between the magnitudes, are within
+//! the noise. So is the overflow check in `checked`, which costs about as
+//! much in Rust as `rust_checked` does over `rust`.
+//!
+//! `mat_mul` compares the compilers more than the boundary: the loops are
+//! the same, and which is faster depends on how well each optimizes them.
+//! The Rust version also checks every index against the slice bounds, which
+//! C doesn't.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_ffi_to_c::{arrays, ffi, matrix, raw, safe};
+
+#[inline]
+fn multiply_rust(a: i32, b: i32) -> i32 {
+    a.wrapping_mul(b)
+}
+
+fn sum_rust(values: &[i32]) -> i64 {
+    values.iter().map(|&value| i64::from(value)).sum()
+}
+
+fn bench_multiply(c: &mut Criterion) {
+    // Otherwise the benchmark measures printf.
+    ffi::set_verbose(false);
+
+    let mut group = c.benchmark_group("multiply");
+
+    // Every product fits in an `i32`, so `checked` never returns early and
+    // measures the same call as the others.
+    for (magnitude, (x, y)) in [("1e1", (7, 6)), ("1e3", (5000, 5)), ("1e9", (46340, 46340))] {
+        group.bench_function(BenchmarkId::new("raw", magnitude), |b| {
+            // SAFETY: `multiply` takes two integers by value.
+            b.iter(|| unsafe { raw::multiply(black_box(x), black_box(y)) })
+        });
+        group.bench_function(BenchmarkId::new("ffi", magnitude), |b| {
+            b.iter(|| ffi::multiply(black_box(x), black_box(y)))
+        });
+        group.bench_function(BenchmarkId::new("checked", magnitude), |b| {
+            b.iter(|| safe::checked_multiply(black_box(x), black_box(y)))
+        });
+        group.bench_function(BenchmarkId::new("rust", magnitude), |b| {
+            b.iter(|| multiply_rust(black_box(x), black_box(y)))
+        });
+        group.bench_function(BenchmarkId::new("rust_checked", magnitude), |b| {
+            b.iter(|| black_box(x).checked_mul(black_box(y)).unwrap_or(0))
+        });
+    }
+
+    for len in [16, 1024, 65536] {
+        let a: Vec<i32> = (0..len).collect();
+        let b: Vec<i32> = (0..len).rev().collect();
+        let mut out = vec![0; a.len()];
+
+        // Criterion divides the time by the number of iterations; dividing
+        // it by `len` as well gives the time per multiplication.
+        group.bench_function(BenchmarkId::new("batch", len), |bench| {
+            bench.iter_custom(|iters| {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    ffi::multiply_batch(black_box(&a), black_box(&b), black_box(&mut out));
+                }
+                per_element(start.elapsed(), len)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn per_element(elapsed: Duration, len: i32) -> Duration {
+    elapsed / len as u32
+}
+
+fn bench_sum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sum_array");
+
+    for len in [16, 1024, 65536] {
+        let values: Vec<i32> = (0..len).collect();
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("ffi", len), &values, |b, values| {
+            b.iter(|| arrays::sum(black_box(values)))
+        });
+        group.bench_with_input(BenchmarkId::new("rust", len), &values, |b, values| {
+            b.iter(|| sum_rust(black_box(values)))
+        });
+    }
+
+    group.finish();
+}
+
+fn mat_mul_rust(a: &[f64], b: &[f64], n: usize) -> Vec<f64> {
+    let mut c = vec![0.0; n * n];
+
+    for i in 0..n {
+        for j in 0..n {
+            let mut sum = 0.0;
+            for k in 0..n {
+                sum += a[i * n + k] * b[k * n + j];
+            }
+            c[i * n + j] = sum;
+        }
+    }
+
+    c
+}
+
+fn bench_mat_mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mat_mul");
+
+    for n in [4, 32, 128] {
+        let a: Vec<f64> = (0..n * n).map(|x| x as f64).collect();
+        let b: Vec<f64> = (0..n * n).map(|x| (x % 7) as f64).collect();
+
+        group.bench_with_input(BenchmarkId::new("ffi", n), &n, |bench, &n| {
+            bench.iter(|| matrix::mat_mul(black_box(&a), black_box(&b), n))
+        });
+        group.bench_with_input(BenchmarkId::new("rust", n), &n, |bench, &n| {
+            bench.iter(|| mat_mul_rust(black_box(&a), black_box(&b), n))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_multiply, bench_sum, bench_mat_mul);
+criterion_main!(benches);
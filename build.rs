@@ -1,5 +1,438 @@
 extern crate cc;
 
+use std::env;
+#[cfg(feature = "bindgen")]
+use std::panic;
+use std::path::Path;
+use std::process;
+
+const C_SOURCES: &[&str] = &[
+    "c_src/logging.c",
+    "c_src/allocator.c",
+    "c_src/multiply.c",
+    "c_src/divide.c",
+    "c_src/strings.c",
+    "c_src/geometry.c",
+    "c_src/callbacks.c",
+    "c_src/arrays.c",
+    "c_src/context.c",
+    "c_src/calculator.c",
+    "c_src/status.c",
+    "c_src/compute.c",
+    "c_src/outparam.c",
+    "c_src/counter.c",
+    "c_src/union_demo.c",
+    "c_src/range.c",
+    "c_src/varargs.c",
+    "c_src/calculate.c",
+    "c_src/counter_object.c",
+    "c_src/cstr_ownership.c",
+    "c_src/numeric.c",
+    "c_src/matrix.c",
+    "c_src/linked_list.c",
+    "c_src/color.c",
+    "c_src/sort.c",
+    "c_src/nullable.c",
+    "c_src/global_state.c",
+    "c_src/progress.c",
+    "c_src/record.c",
+    "c_src/library.c",
+    "c_src/booleans.c",
+    "c_src/alloc_string.c",
+    "c_src/c_result.c",
+    "c_src/vtable.c",
+    "c_src/ring_buffer.c",
+];
+
+// C code only built for Windows targets, since it uses Windows' calling
+// conventions.
+const WINDOWS_SOURCES: &[&str] = &["c_src/stdcall.c"];
+
+// C code that calls back into Rust by name. With the `dynamic-link` feature
+// it stays in a static archive: a shared library can only find `rust_add`
+// among the symbols the executable exports, and Rust executables export
+// none.
+const CALLS_RUST: &[&str] = &["c_src/compute.c"];
+
 fn main() {
-    cc::Build::new().file("src/multiply.c").compile("multiply");
+    println!("cargo:rerun-if-env-changed=MULTIPLY_LIB_DIR");
+
+    warn_if_cross_compiling();
+
+    #[cfg(feature = "bindgen")]
+    generate_bindings();
+
+    // A prebuilt library can be substituted for the bundled C source by
+    // pointing MULTIPLY_LIB_DIR at the directory containing it. Cargo picks
+    // the right file name for the target: `libmultiply.a` for GNU-style
+    // toolchains (Linux, macOS, MinGW) and `multiply.lib` for MSVC. With
+    // `dynamic-link`, it looks for `libmultiply.so` (`.dylib` on macOS, or
+    // the `multiply.lib` import library for `multiply.dll` on MSVC) and
+    // `libcompute.a` instead.
+    if let Ok(dir) = env::var("MULTIPLY_LIB_DIR") {
+        link_multiply(&dir);
+        return;
+    }
+
+    if cfg!(feature = "system-lib") {
+        #[cfg(feature = "system-lib")]
+        link_system_lib();
+    } else if cfg!(feature = "bundled") {
+        compile_bundled();
+    } else {
+        // Without the `bundled` feature we follow the manual flow from the
+        // README (or `make shared` with `dynamic-link`) and expect the
+        // library next to Cargo.toml. A shared library is then found at
+        // runtime through LD_LIBRARY_PATH (DYLD_LIBRARY_PATH on macOS, PATH
+        // on Windows).
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let lib_name = if is_msvc() {
+            "multiply.lib".to_string()
+        } else if cfg!(feature = "dynamic-link") {
+            shared_lib_name("multiply")
+        } else {
+            "libmultiply.a".to_string()
+        };
+        println!("cargo:rerun-if-changed={}", lib_name);
+        link_multiply(&manifest_dir);
+    }
+}
+
+// Links the libmultiply installed on the system, as described by its
+// multiply.pc file, instead of compiling c_src/. pkg-config prints the
+// `cargo:rustc-link-*` lines itself. c_src/compute.c is still compiled here:
+// it calls back into this crate's `rust_add`, so an installed library can't
+// contain it (see CALLS_RUST).
+#[cfg(feature = "system-lib")]
+fn link_system_lib() {
+    let library = pkg_config::Config::new()
+        .statik(!cfg!(feature = "dynamic-link"))
+        .probe("multiply");
+
+    if let Err(err) = library {
+        eprintln!("error: the `system-lib` feature couldn't find libmultiply with pkg-config:");
+        eprintln!();
+        eprintln!("{}", err);
+        eprintln!();
+        eprintln!("Install it with `make install` (PREFIX=... to choose where), and if");
+        eprintln!("that isn't a directory pkg-config searches, point PKG_CONFIG_PATH at");
+        eprintln!("PREFIX/lib/pkgconfig. Or build without `system-lib` to compile the");
+        eprintln!("bundled C sources instead.");
+        process::exit(1);
+    }
+
+    for source in CALLS_RUST {
+        println!("cargo:rerun-if-changed={}", source);
+    }
+    let mut build = cc::Build::new();
+    // include/rust_exports.h includes c_src/multiply.h.
+    build.files(CALLS_RUST).include("include").include("c_src");
+    for flag in target_flags() {
+        build.flag(flag);
+    }
+    if let Err(err) = build.try_compile("compute") {
+        eprintln!(
+            "error: failed to compile {}: {}",
+            CALLS_RUST.join(", "),
+            err
+        );
+        process::exit(1);
+    }
+}
+
+// Generates the `extern "C"` declarations for `c_src/multiply.h` into
+// OUT_DIR/bindings.rs, which `src/bindings.rs` includes.
+#[cfg(feature = "bindgen")]
+fn generate_bindings() {
+    println!("cargo:rerun-if-changed=c_src/multiply.h");
+
+    let builder = bindgen::Builder::default()
+        .header("c_src/multiply.h")
+        // Only emit what multiply.h itself declares, not everything from the
+        // system headers it might include.
+        .allowlist_file(".*multiply\\.h")
+        // Spell C's `int` as `core::ffi::c_int` rather than `std::os::raw`,
+        // like the hand-written declarations do.
+        .use_core()
+        .ctypes_prefix("core::ffi");
+
+    // bindgen panics with a long, cryptic message when it can't load
+    // libclang. Catch that and say what to do about it instead.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| builder.generate()));
+    panic::set_hook(default_hook);
+
+    let bindings = match result {
+        Ok(Ok(bindings)) => bindings,
+        Ok(Err(err)) => {
+            eprintln!(
+                "error: failed to generate bindings for c_src/multiply.h: {}",
+                err
+            );
+            process::exit(1);
+        }
+        Err(_) => {
+            eprintln!("error: the `bindgen` feature needs libclang, which wasn't found.");
+            eprintln!();
+            eprintln!("Install it (e.g. `apt install libclang-dev`, `brew install llvm`, or");
+            eprintln!("the LLVM installer on Windows), or point LIBCLANG_PATH at the");
+            eprintln!("directory containing it. Or build without `--features bindgen`");
+            eprintln!("to use the hand-written declarations.");
+            process::exit(1);
+        }
+    };
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    bindings
+        .write_to_file(Path::new(&out_dir).join("bindings.rs"))
+        .expect("failed to write bindings.rs");
+}
+
+fn compile_bundled() {
+    // Only rerun the build script when the C sources change, instead of on
+    // every change anywhere in the package.
+    for source in C_SOURCES.iter().chain(WINDOWS_SOURCES) {
+        println!("cargo:rerun-if-changed={}", source);
+    }
+    println!("cargo:rerun-if-changed=c_src/multiply.h");
+    // Generated from src/exported.rs by `cargo xtask gen-header` and
+    // committed, so the C code can be built without cbindgen.
+    println!("cargo:rerun-if-changed=include/rust_exports.h");
+
+    // The cc crate finds the right compiler for the target (cc/gcc/clang on
+    // Unix and MinGW, cl.exe on MSVC). The archive goes to OUT_DIR so the
+    // source tree stays clean.
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let mut build = cc::Build::new();
+    build
+        .out_dir(&out_dir)
+        .cargo_metadata(false)
+        // For include/rust_exports.h, which declares the Rust functions
+        // c_src/compute.c calls, and includes c_src/multiply.h itself.
+        .include("include")
+        .include("c_src");
+
+    // cl.exe only accepts C11 `_Atomic` (used in c_src/counter.c) behind a
+    // flag. The cc crate takes care of everything else that differs on MSVC,
+    // like producing `multiply.lib` with lib.exe instead of `libmultiply.a`
+    // with ar.
+    if is_msvc() {
+        build.flag("/std:c11").flag("/experimental:c11atomics");
+    }
+    for flag in target_flags() {
+        build.flag(flag);
+    }
+
+    let result = if cfg!(feature = "dynamic-link") {
+        compile_bundled_shared(build, &out_dir)
+    } else {
+        build
+            .files(C_SOURCES)
+            .files(platform_sources())
+            .try_compile("multiply")
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: failed to compile the C sources in c_src/: {}", err);
+        eprintln!();
+        eprintln!("A C compiler is required to build this crate. Install gcc or clang");
+        eprintln!("on Linux/macOS, or the Visual Studio Build Tools on Windows, or");
+        eprintln!("set CC to the compiler you want to use.");
+        eprintln!();
+        eprintln!("If the error is about a function from include/rust_exports.h, the");
+        eprintln!("header is out of date with src/exported.rs. Regenerate it with");
+        eprintln!("`cargo xtask gen-header`.");
+        eprintln!();
+        eprintln!("To use a prebuilt library instead, set MULTIPLY_LIB_DIR to the");
+        eprintln!("directory containing libmultiply.a (or multiply.lib on MSVC).");
+        process::exit(1);
+    }
+
+    compile_shared(&out_dir);
+
+    if cfg!(feature = "dynamic-link") {
+        link_multiply(&Path::new(&out_dir).join("dylib").display().to_string());
+    } else {
+        link_multiply(&out_dir);
+    }
+}
+
+// Builds the C sources as OUT_DIR/dylib/libmultiply.so (or .dylib) for the
+// `dynamic-link` feature, except for CALLS_RUST, which goes into a static
+// `libcompute.a` next to it. The directory keeps it apart from the
+// single-file shared library `compile_shared` puts in OUT_DIR.
+fn compile_bundled_shared(build: cc::Build, out_dir: &str) -> Result<(), cc::Error> {
+    if is_msvc() {
+        eprintln!("error: the `dynamic-link` feature isn't supported with MSVC.");
+        eprintln!();
+        eprintln!("The C sources don't mark their functions `__declspec(dllexport)`, so");
+        eprintln!("a DLL built from them would export nothing. Use the default");
+        eprintln!("`static-link` feature instead.");
+        process::exit(1);
+    }
+
+    let dir = Path::new(out_dir).join("dylib");
+    std::fs::create_dir_all(&dir).expect("failed to create OUT_DIR/dylib");
+
+    // Compiling and linking in one go, like `compile_shared`, since the cc
+    // crate only builds static archives.
+    let path = dir.join(shared_lib_name("multiply"));
+    let status = build
+        .get_compiler()
+        .to_command()
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(&path)
+        .args(
+            C_SOURCES
+                .iter()
+                .filter(|source| !CALLS_RUST.contains(source)),
+        )
+        .args(platform_sources())
+        // For `sqrt` in c_src/outparam.c.
+        .arg("-lm")
+        .status();
+    if !matches!(status, Ok(status) if status.success()) {
+        eprintln!("error: failed to build {}", path.display());
+        process::exit(1);
+    }
+
+    // The shared library is found at runtime through the rpath rather than
+    // LD_LIBRARY_PATH. Cargo only passes this to the targets of this
+    // package; a crate depending on it gets the library from the
+    // LD_LIBRARY_PATH `cargo run` and `cargo test` set up instead. On macOS
+    // the library is recorded by its full path, so no rpath is needed.
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("macos") {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+    }
+
+    let mut compute = build;
+    compute.files(CALLS_RUST).out_dir(&dir);
+    compute.try_compile("compute")?;
+
+    Ok(())
+}
+
+// Also builds c_src/multiply.c, with the logging.c it logs through and the
+// counter.c whose counter it increments, as a shared library in OUT_DIR, for
+// examples/dynamic.rs to load at runtime. The crate itself never links it,
+// so a failure here only warns.
+fn compile_shared(out_dir: &str) {
+    let mut build = cc::Build::new();
+    build.cargo_metadata(false);
+    for flag in target_flags() {
+        build.flag(flag);
+    }
+    let compiler = build.get_compiler();
+    let path = Path::new(out_dir).join(shared_lib_name("multiply"));
+
+    let mut command = compiler.to_command();
+    if compiler.is_like_msvc() {
+        // Functions aren't exported from a DLL unless asked for, and
+        // multiply.c has no `__declspec(dllexport)`.
+        command
+            .args(["/LD", "/std:c11", "/experimental:c11atomics"])
+            .arg(format!("/Fo{}\\", out_dir))
+            .arg(format!("/Fe{}", path.display()))
+            .args(["c_src/multiply.c", "c_src/logging.c", "c_src/counter.c"])
+            .args(["/link", "/EXPORT:multiply", "/EXPORT:lib_version"]);
+    } else {
+        command.args(["-shared", "-fPIC", "-o"]).arg(&path).args([
+            "c_src/multiply.c",
+            "c_src/logging.c",
+            "c_src/counter.c",
+        ]);
+    }
+
+    match command.status() {
+        Ok(status) if status.success() => {}
+        _ => println!(
+            "cargo:warning=failed to build {}, examples/dynamic.rs won't be able to load it",
+            path.display()
+        ),
+    }
+}
+
+// The file name of a shared library for the target, e.g. `libmultiply.so`.
+fn shared_lib_name(name: &str) -> String {
+    match env::var("CARGO_CFG_TARGET_OS").as_deref() {
+        Ok("windows") => format!("{}.dll", name),
+        Ok("macos") | Ok("ios") => format!("lib{}.dylib", name),
+        _ => format!("lib{}.so", name),
+    }
+}
+
+// Links libmultiply from `dir` as a static archive, or as a shared library
+// with the `dynamic-link` feature, together with the static libcompute that
+// goes with it (see CALLS_RUST). src/lib.rs refuses to build with both
+// features on.
+fn link_multiply(dir: &str) {
+    println!("cargo:rustc-link-search=native={}", dir);
+
+    if cfg!(feature = "dynamic-link") {
+        println!("cargo:rustc-link-lib=dylib=multiply");
+        println!("cargo:rustc-link-lib=static=compute");
+    } else {
+        println!("cargo:rustc-link-lib=static=multiply");
+    }
+}
+
+// Cross-compiling needs a C compiler for the target, not just a Rust target.
+// The cc crate looks for one by the usual name, such as
+// `aarch64-linux-gnu-gcc` for `aarch64-unknown-linux-gnu`, or whatever
+// CC_<target> says, but fails with a confusing error if there is none.
+fn warn_if_cross_compiling() {
+    let host = env::var("HOST").unwrap();
+    let target = env::var("TARGET").unwrap();
+
+    if host != target {
+        println!(
+            "cargo:warning=cross-compiling from {} to {}: the C code needs a C compiler for {} \
+             (set CC_{} or build with `cross`)",
+            host,
+            target,
+            target,
+            target.replace('-', "_")
+        );
+    }
+}
+
+// Extra flags for the C compiler, by target. The cc crate already picks the
+// right compiler and some flags for the target; these make the choices this
+// crate depends on explicit, so they don't change with the toolchain's
+// defaults.
+fn target_flags() -> Vec<&'static str> {
+    if is_msvc() {
+        return Vec::new();
+    }
+
+    let target = env::var("TARGET").unwrap();
+    match env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+        // The baseline every 64-bit ARM target supports.
+        Ok("aarch64") => vec!["-march=armv8-a"],
+        // i686 and friends: 32-bit code, even from a 64-bit compiler.
+        Ok("x86") => vec!["-m32"],
+        // Pass floats in floating-point registers, as the Rust side does
+        // on `eabihf` targets. Mixing that with soft-float C code breaks
+        // every function taking or returning a float or a double.
+        Ok("arm") if target.ends_with("eabihf") => vec!["-mfloat-abi=hard"],
+        _ => Vec::new(),
+    }
+}
+
+// The sources built on top of C_SOURCES for the target OS.
+fn platform_sources() -> &'static [&'static str] {
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        WINDOWS_SOURCES
+    } else {
+        &[]
+    }
+}
+
+// Whether we are building for the MSVC toolchain. The build script itself is
+// compiled for the host, so `cfg!(target_env = "msvc")` would describe the
+// machine running the build rather than the target; Cargo passes the
+// target's configuration in CARGO_CFG_* variables instead.
+fn is_msvc() -> bool {
+    env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("msvc")
 }
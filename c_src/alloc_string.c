@@ -0,0 +1,26 @@
+#include <string.h>
+
+#include "multiply.h"
+
+// Allocates a message and stores the pointer in *out. The caller owns it and
+// must release it with free_message(). *out is only written on success.
+int get_message(char **out) {
+    if (out == NULL) {
+        return GET_MESSAGE_NULL_POINTER;
+    }
+
+    static const char text[] = "hello from C";
+    char *msg = lib_alloc(sizeof text);
+    if (msg == NULL) {
+        return GET_MESSAGE_NO_MEMORY;
+    }
+    memcpy(msg, text, sizeof text);
+
+    *out = msg;
+    return GET_MESSAGE_OK;
+}
+
+// Releases a message from get_message(). NULL is ignored, like free().
+void free_message(char *msg) {
+    lib_free(msg);
+}
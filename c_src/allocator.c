@@ -0,0 +1,74 @@
+#include <stdatomic.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#include "multiply.h"
+
+// Written before any memory is allocated with them, and never again (see
+// set_allocator()). Atomic, so a thread allocating while another installs
+// the hooks reads one pair or the other, not a mix of both.
+struct hooks {
+    lib_alloc_fn alloc;
+    lib_dealloc_fn dealloc;
+};
+static _Atomic(const struct hooks *) hooks = NULL;
+static struct hooks installed;
+static atomic_flag installing = ATOMIC_FLAG_INIT;
+
+// Every allocation starts with one of these. It remembers the size the
+// caller asked for, so lib_free() doesn't need to be told, and which
+// dealloc to free it with, so memory allocated with malloc() before the
+// hooks were installed is still freed with free() afterwards. The union
+// keeps the memory after it aligned like malloc()'s.
+typedef union {
+    struct {
+        size_t size;
+        lib_dealloc_fn dealloc;
+    } info;
+    max_align_t align;
+} header;
+
+int set_allocator(lib_alloc_fn alloc, lib_dealloc_fn dealloc) {
+    if (alloc == NULL || dealloc == NULL) {
+        return SET_ALLOCATOR_NULL_POINTER;
+    }
+    if (atomic_flag_test_and_set(&installing)) {
+        return SET_ALLOCATOR_ALREADY_SET;
+    }
+
+    installed.alloc = alloc;
+    installed.dealloc = dealloc;
+    atomic_store_explicit(&hooks, &installed, memory_order_release);
+    return SET_ALLOCATOR_OK;
+}
+
+void *lib_alloc(size_t size) {
+    if (size > SIZE_MAX - sizeof(header)) {
+        return NULL;
+    }
+    size_t total = sizeof(header) + size;
+
+    const struct hooks *h = atomic_load_explicit(&hooks, memory_order_acquire);
+    header *block = h != NULL ? h->alloc(total) : malloc(total);
+    if (block == NULL) {
+        return NULL;
+    }
+
+    block->info.size = total;
+    block->info.dealloc = h != NULL ? h->dealloc : NULL;
+    return block + 1;
+}
+
+void lib_free(void *ptr) {
+    if (ptr == NULL) {
+        return;
+    }
+
+    header *block = (header *)ptr - 1;
+    if (block->info.dealloc != NULL) {
+        block->info.dealloc(block, block->info.size);
+    } else {
+        free(block);
+    }
+}
@@ -0,0 +1,36 @@
+#include <stddef.h>
+#include <stdint.h>
+
+#include "multiply.h"
+
+// Rust passes slice lengths as usize, which is pointer-sized. That only
+// matches size_t on targets where size_t is pointer-sized too.
+_Static_assert(sizeof(size_t) == sizeof(uintptr_t), "size_t must be pointer-sized");
+
+// Returns the sum of the len integers starting at data. data is never read
+// when len is 0. Summing into a long long means no realistic input overflows.
+long long sum_array(const int *data, size_t len) {
+    long long sum = 0;
+
+    for (size_t i = 0; i < len; i++) {
+        sum += data[i];
+    }
+
+    return sum;
+}
+
+// Reverses the len integers starting at arr in place. arr is never read
+// when len is 0.
+void reverse_array(int *arr, size_t len) {
+    // Otherwise len - 1 wraps around to SIZE_MAX, and the loop reads and
+    // writes far past the end of the array.
+    if (len == 0) {
+        return;
+    }
+
+    for (size_t i = 0, j = len - 1; i < j; i++, j--) {
+        int tmp = arr[i];
+        arr[i] = arr[j];
+        arr[j] = tmp;
+    }
+}
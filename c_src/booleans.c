@@ -0,0 +1,23 @@
+#include <stdbool.h>
+
+#include "multiply.h"
+
+// Whether every one of the len integers starting at arr is positive. True
+// for an empty array.
+_Bool all_positive(const int *arr, size_t len) {
+    for (size_t i = 0; i < len; i++) {
+        if (arr[i] <= 0) {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+// Sets each of the len flags starting at flags to value. Assigning to a
+// _Bool always stores 0 or 1, whatever the right-hand side was.
+void set_flags(_Bool *flags, size_t len, _Bool value) {
+    for (size_t i = 0; i < len; i++) {
+        flags[i] = value;
+    }
+}
@@ -0,0 +1,26 @@
+#include <limits.h>
+#include <stddef.h>
+
+#include "multiply.h"
+
+_Static_assert(sizeof(CResult) == 3 * sizeof(int), "CResult must have no padding");
+
+// Multiplies a and b. On overflow, returns CRESULT_ERR with errcode set to
+// CALC_ERR_OVERFLOW instead, and value left at 0.
+CResult try_multiply(int a, int b) {
+    CResult result = {CRESULT_OK, 0, CALC_OK};
+    long long product = (long long)a * b;
+
+    if (product < INT_MIN || product > INT_MAX) {
+        result.tag = CRESULT_ERR;
+        result.errcode = CALC_ERR_OVERFLOW;
+        return result;
+    }
+
+    result.value = (int)product;
+    return result;
+}
+
+size_t c_result_size(void) {
+    return sizeof(CResult);
+}
@@ -0,0 +1,52 @@
+#include <limits.h>
+
+#include "multiply.h"
+
+// The intermediate result is computed as a long long, which can hold the
+// result of any of the operations on two ints.
+int calculate(Op op, int a, int b, int *out) {
+    long long result;
+
+    switch (op) {
+    case OP_ADD:
+        result = (long long)a + b;
+        break;
+    case OP_SUB:
+        result = (long long)a - b;
+        break;
+    case OP_MUL:
+        result = (long long)a * b;
+        break;
+    case OP_DIV:
+        if (b == 0) {
+            return CALC_ERR_DIVIDE_BY_ZERO;
+        }
+        result = (long long)a / b;
+        break;
+    default:
+        return CALC_ERR_UNKNOWN_OP;
+    }
+
+    if (result < INT_MIN || result > INT_MAX) {
+        return CALC_ERR_OVERFLOW;
+    }
+
+    *out = (int)result;
+    return CALC_OK;
+}
+
+// Returns the Op for '+', '-', '*' or '/', or -1 for anything else.
+int op_from_symbol(char symbol) {
+    switch (symbol) {
+    case '+':
+        return OP_ADD;
+    case '-':
+        return OP_SUB;
+    case '*':
+        return OP_MUL;
+    case '/':
+        return OP_DIV;
+    default:
+        return -1;
+    }
+}
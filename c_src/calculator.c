@@ -0,0 +1,30 @@
+#include "multiply.h"
+
+struct Calculator {
+    int total;
+};
+
+// Returns a new calculator with a total of 0, or NULL if allocation fails.
+// Release it with calculator_free().
+Calculator *calculator_new(void) {
+    Calculator *calc = lib_alloc(sizeof(Calculator));
+    if (calc == NULL) {
+        return NULL;
+    }
+
+    calc->total = 0;
+    return calc;
+}
+
+// Adds value to the running total. The total wraps around on overflow.
+void calculator_accumulate(Calculator *calc, int value) {
+    calc->total = (int)((unsigned)calc->total + (unsigned)value);
+}
+
+int calculator_result(const Calculator *calc) {
+    return calc->total;
+}
+
+void calculator_free(Calculator *calc) {
+    lib_free(calc);
+}
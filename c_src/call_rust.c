@@ -0,0 +1,45 @@
+// A C program calling the functions Rust exports from src/exported.rs.
+//
+// Unlike the other files in c_src/, this isn't part of the library the build
+// script compiles: it has its own main() and links against the Rust library
+// instead. Build and run it with:
+//
+//     make run-call-rust
+
+#include <stdio.h>
+
+#include "rust_exports.h"
+
+int main(void) {
+    printf("[C] rust_add(40, 2) = %d\n", rust_add(40, 2));
+
+    int cases[][2] = {{6, 7}, {2147483647, 2}};
+
+    for (int i = 0; i < 2; i++) {
+        int a = cases[i][0];
+        int b = cases[i][1];
+        int product;
+
+        int status = rust_multiply_checked(a, b, &product);
+
+        if (status == RUST_MULTIPLY_OK) {
+            printf("[C] rust_multiply_checked(%d, %d) = %d\n", a, b, product);
+        } else if (status == RUST_MULTIPLY_OVERFLOW) {
+            printf("[C] rust_multiply_checked(%d, %d) overflowed\n", a, b);
+        } else {
+            printf("[C] rust_multiply_checked(%d, %d) failed with %d\n", a, b, status);
+        }
+    }
+
+    if (rust_multiply_checked(1, 1, NULL) == RUST_MULTIPLY_NULL_POINTER) {
+        printf("[C] rust_multiply_checked refused a NULL out pointer\n");
+    }
+
+    int quotient;
+
+    if (rust_divide(1, 0, &quotient) == RUST_DIVIDE_PANICKED) {
+        printf("[C] rust_divide(1, 0) panicked in Rust and returned an error code\n");
+    }
+
+    return 0;
+}
@@ -0,0 +1,32 @@
+#include <stddef.h>
+
+#include "multiply.h"
+
+// Calls callback(value) and writes the result to *out.
+// Returns 0 on success, or -1 without touching *out if callback is NULL.
+int call_callback(int_callback callback, int value, int *out) {
+    if (callback == NULL) {
+        return -1;
+    }
+
+    *out = callback(value);
+    return 0;
+}
+
+// Returns fn(fn(value)), or value unchanged if fn is NULL.
+int apply_twice(int_callback fn, int value) {
+    if (fn == NULL) {
+        return value;
+    }
+
+    return fn(fn(value));
+}
+
+// Calls cb once for each of the len integers starting at data, passing user
+// along untouched. C never looks at user: it is there so the caller can get
+// its own state back inside the callback.
+void for_each(const int *data, size_t len, for_each_callback cb, void *user) {
+    for (size_t i = 0; i < len; i++) {
+        cb(data[i], user);
+    }
+}
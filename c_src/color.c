@@ -0,0 +1,16 @@
+#include "multiply.h"
+
+// Returns the color after color, wrapping from BLUE back to RED. A value
+// outside the enum is returned as is.
+enum Color next_color(enum Color color) {
+    switch (color) {
+    case RED:
+        return GREEN;
+    case GREEN:
+        return BLUE;
+    case BLUE:
+        return RED;
+    }
+
+    return color;
+}
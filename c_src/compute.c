@@ -0,0 +1,12 @@
+#include "multiply.h"
+
+// rust_add() is implemented in Rust (src/exported.rs). C only needs its
+// declaration, which comes from the header cbindgen generates from the Rust
+// code, so a call that doesn't match the Rust signature fails to compile.
+// The linker finds the definition in the Rust object files.
+#include "rust_exports.h"
+
+// Calls back into Rust, so a call from Rust goes Rust -> C -> Rust.
+int compute_with_rust(int a, int b) {
+    return rust_add(a, b);
+}
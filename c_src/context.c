@@ -0,0 +1,31 @@
+#include "multiply.h"
+
+// The layout of Context is private to this file. Callers only ever see a
+// pointer to it.
+struct Context {
+    unsigned state;
+};
+
+// Returns a new context, or NULL if allocation fails. Release it with
+// ctx_destroy().
+Context *ctx_create(int seed) {
+    Context *ctx = lib_alloc(sizeof(Context));
+    if (ctx == NULL) {
+        return NULL;
+    }
+
+    ctx->state = (unsigned)seed;
+    return ctx;
+}
+
+// Advances the context's pseudo-random generator and returns its next value.
+// This mutates the context without any locking, so a context must not be
+// used from two threads at the same time.
+int ctx_get_value(Context *ctx) {
+    ctx->state = ctx->state * 1103515245u + 12345u;
+    return (int)((ctx->state >> 16) & 0x7fff);
+}
+
+void ctx_destroy(Context *ctx) {
+    lib_free(ctx);
+}
@@ -0,0 +1,25 @@
+#include <stdatomic.h>
+
+#include "multiply.h"
+
+// Not `static`: Rust reads this variable directly, so it needs external
+// linkage. A `static` global would only be visible inside this file, and
+// only reachable through the functions below. Atomic, because multiply()
+// increments it and may be called from any number of threads.
+_Atomic int call_count = 0;
+
+// Another atomic counter, which Rust declares as an AtomicI32 rather than a
+// c_int.
+_Atomic int atomic_call_count = 0;
+
+void increment(void) {
+    atomic_fetch_add(&call_count, 1);
+}
+
+int get_call_count(void) {
+    return atomic_load(&call_count);
+}
+
+void increment_atomic(void) {
+    atomic_fetch_add(&atomic_call_count, 1);
+}
@@ -0,0 +1,30 @@
+#include "multiply.h"
+
+struct Counter {
+    int value;
+};
+
+// Returns a new counter starting at 0, or NULL if allocation fails. Release
+// it with counter_free().
+Counter *counter_new(void) {
+    Counter *counter = lib_alloc(sizeof(Counter));
+    if (counter == NULL) {
+        return NULL;
+    }
+
+    counter->value = 0;
+    return counter;
+}
+
+// Wraps around to INT_MIN after INT_MAX.
+void counter_increment(Counter *counter) {
+    counter->value = (int)((unsigned)counter->value + 1u);
+}
+
+int counter_value(const Counter *counter) {
+    return counter->value;
+}
+
+void counter_free(Counter *counter) {
+    lib_free(counter);
+}
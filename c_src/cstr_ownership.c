@@ -0,0 +1,20 @@
+#include <string.h>
+
+#include "multiply.h"
+
+#ifdef _MSC_VER
+// MSVC spells the POSIX function with an underscore.
+#define strdup _strdup
+#endif
+
+// Returns a pointer to a string with static storage duration. It lives for
+// the whole program and must not be freed.
+const char *static_string(void) {
+    return "I live in C's static memory";
+}
+
+// Returns a copy of in allocated with malloc (through strdup), or NULL if
+// allocation fails. The caller owns it and must release it with free().
+char *heap_string(const char *in) {
+    return strdup(in);
+}
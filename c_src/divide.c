@@ -0,0 +1,70 @@
+#include <errno.h>
+#include <limits.h>
+
+#include "multiply.h"
+
+#define DIVIDE_OK 0
+#define DIVIDE_BY_ZERO 1
+#define DIVIDE_OVERFLOW 2
+
+// Divides a by b and writes the quotient to *out.
+// Returns DIVIDE_OK on success and leaves *out untouched on error.
+int divide(int a, int b, int *out) {
+    c_log(LOG_LEVEL_DEBUG, "divide(%d, %d)", a, b);
+
+    if (b == 0) {
+        return DIVIDE_BY_ZERO;
+    }
+
+    // INT_MIN / -1 doesn't fit in an int, which is undefined behavior.
+    if (a == INT_MIN && b == -1) {
+        return DIVIDE_OVERFLOW;
+    }
+
+    *out = a / b;
+    return DIVIDE_OK;
+}
+
+// Divides a by b using the errno convention instead of a status code.
+// On error, sets errno and returns INT_MIN: EDOM if b is 0, ERANGE if the
+// quotient doesn't fit in an int. INT_MIN is also a valid quotient
+// (INT_MIN / 1), so callers must clear errno before the call and check it
+// afterwards to tell the two apart.
+int divide_errno(int a, int b) {
+    if (b == 0) {
+        errno = EDOM;
+        return INT_MIN;
+    }
+
+    if (a == INT_MIN && b == -1) {
+        errno = ERANGE;
+        return INT_MIN;
+    }
+
+    return a / b;
+}
+
+// errno is a macro that expands to something different on every C library
+// (a thread-local variable on glibc, a function call on macOS and Windows),
+// so Rust can't simply assign to it. This gives it a portable way to.
+void clear_errno(void) {
+    errno = 0;
+}
+
+// Divides a by b and writes the quotient to *out, following the POSIX
+// convention: returns 0 on success, or -1 with errno set on failure (EDOM if
+// b is 0, ERANGE if the quotient doesn't fit in an int).
+int safe_divide(int a, int b, int *out) {
+    if (b == 0) {
+        errno = EDOM;
+        return -1;
+    }
+
+    if (a == INT_MIN && b == -1) {
+        errno = ERANGE;
+        return -1;
+    }
+
+    *out = a / b;
+    return 0;
+}
@@ -0,0 +1,31 @@
+#include <math.h>
+
+#include "multiply.h"
+
+double distance(Point a, Point b) {
+    double dx = b.x - a.x;
+    double dy = b.y - a.y;
+
+    return sqrt(dx * dx + dy * dy);
+}
+
+// Multiplying as unsigned wraps around on overflow instead of being
+// undefined behavior like signed overflow.
+static int scale_int(int value, int factor) {
+    return (int)((unsigned)value * (unsigned)factor);
+}
+
+IntPoint point_scale(IntPoint p, int factor) {
+    IntPoint scaled = { scale_int(p.x, factor), scale_int(p.y, factor) };
+    return scaled;
+}
+
+void point_scale_inplace(IntPoint *p, int factor) {
+    p->x = scale_int(p->x, factor);
+    p->y = scale_int(p->y, factor);
+}
+
+IntPoint make_point(int x, int y) {
+    IntPoint p = { x, y };
+    return p;
+}
@@ -0,0 +1,15 @@
+#include "multiply.h"
+
+// Hidden state, like many C libraries keep: `static` gives it internal
+// linkage, so nothing outside this file can name it, and the functions
+// below are the only way in. Nothing guards it against being used from two
+// threads at once.
+static int global_counter = 0;
+
+void bump_global(void) {
+    global_counter++;
+}
+
+int read_global(void) {
+    return global_counter;
+}
@@ -0,0 +1,46 @@
+#include <stdatomic.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "multiply.h"
+
+// Library-wide state, set up by library_init() and torn down by
+// library_deinit(), like OpenSSL, libcurl or SDL have. Atomic, so the
+// functions below can be called from several threads at once.
+static _Atomic int initialized = 0;
+static _Atomic int next_id = 0;
+
+// Returns 0, or -1 if the library is already initialized. Every successful
+// call must be paired with a call to library_deinit().
+int library_init(void) {
+    if (atomic_exchange(&initialized, 1)) {
+        return -1;
+    }
+
+    atomic_store(&next_id, 1);
+    return 0;
+}
+
+void library_deinit(void) {
+    atomic_store(&initialized, 0);
+}
+
+int library_is_initialized(void) {
+    return atomic_load(&initialized);
+}
+
+// What many C libraries do when used before being initialized, in the best
+// case. In the worst case they carry on with garbage.
+static void require_init(const char *function) {
+    if (!atomic_load(&initialized)) {
+        fprintf(stderr, "[C] %s() called before library_init()\n", function);
+        abort();
+    }
+}
+
+// Returns a new ID, unique until the next library_init(). Aborts if the
+// library isn't initialized.
+int library_next_id(void) {
+    require_init("library_next_id");
+    return atomic_fetch_add(&next_id, 1);
+}
@@ -0,0 +1,52 @@
+#include "multiply.h"
+
+// Returns a newly allocated list holding the len values in order, or NULL
+// if len is 0 or allocation fails. The caller owns the list and must
+// release it with free_list().
+Node *make_list(const int *values, size_t len) {
+    Node *head = NULL;
+
+    // Built back to front, so each node can point at the one after it.
+    for (size_t i = len; i > 0; i--) {
+        Node *node = lib_alloc(sizeof(Node));
+        if (node == NULL) {
+            free_list(head);
+            return NULL;
+        }
+
+        node->value = values[i - 1];
+        node->next = head;
+        head = node;
+    }
+
+    return head;
+}
+
+// Returns a newly allocated list holding 0, 1, ..., n - 1, or NULL if n is
+// 0 or less or allocation fails. Release it with free_list().
+Node *make_counting_list(int n) {
+    Node *head = NULL;
+
+    for (int i = n; i > 0; i--) {
+        Node *node = lib_alloc(sizeof(Node));
+        if (node == NULL) {
+            free_list(head);
+            return NULL;
+        }
+
+        node->value = i - 1;
+        node->next = head;
+        head = node;
+    }
+
+    return head;
+}
+
+// Frees every node of the list starting at head. head may be NULL.
+void free_list(Node *head) {
+    while (head != NULL) {
+        Node *next = head->next;
+        lib_free(head);
+        head = next;
+    }
+}
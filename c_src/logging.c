@@ -0,0 +1,44 @@
+#include <stdarg.h>
+#include <stdatomic.h>
+#include <stdio.h>
+
+#include "multiply.h"
+
+// C code on any thread may log while another thread registers a callback.
+// The callback and its user pointer have to be read as a pair, or a thread
+// could call the new callback with the old user pointer, so both are only
+// touched with this spinlock held. It is held for two loads or two stores,
+// never while the callback runs.
+static atomic_flag lock = ATOMIC_FLAG_INIT;
+static log_callback callback = NULL;
+static void *callback_user = NULL;
+
+void set_log_callback(log_callback cb, void *user) {
+    while (atomic_flag_test_and_set_explicit(&lock, memory_order_acquire)) {
+    }
+    callback = cb;
+    callback_user = user;
+    atomic_flag_clear_explicit(&lock, memory_order_release);
+}
+
+void c_log(int level, const char *fmt, ...) {
+    while (atomic_flag_test_and_set_explicit(&lock, memory_order_acquire)) {
+    }
+    log_callback cb = callback;
+    void *user = callback_user;
+    atomic_flag_clear_explicit(&lock, memory_order_release);
+
+    if (cb == NULL) {
+        return;
+    }
+
+    // Longer messages are cut off. The callback gets a pointer to this
+    // buffer, which must not be kept after it returns.
+    char msg[256];
+    va_list args;
+    va_start(args, fmt);
+    vsnprintf(msg, sizeof msg, fmt, args);
+    va_end(args);
+
+    cb(level, msg, user);
+}
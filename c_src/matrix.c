@@ -0,0 +1,20 @@
+#include <stddef.h>
+
+#include "multiply.h"
+
+// Multiplies the n x n matrices a and b into c. All three are stored in
+// row-major order: element (i, j) is at index i * n + j. c must not overlap
+// a or b, and every element of c is written.
+void mat_mul(const double *a, const double *b, double *c, size_t n) {
+    for (size_t i = 0; i < n; i++) {
+        for (size_t j = 0; j < n; j++) {
+            double sum = 0.0;
+
+            for (size_t k = 0; k < n; k++) {
+                sum += a[i * n + k] * b[k * n + j];
+            }
+
+            c[i * n + j] = sum;
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#include <limits.h>
+#include <stdatomic.h>
+#include <stdio.h>
+
+#include "multiply.h"
+
+// Atomic, like call_count, so multiply() can be called from any number of
+// threads while another one turns the printing on or off.
+static _Atomic int verbose = 1;
+static _Atomic int call_count = 0;
+
+void multiply_set_verbose(int enabled) {
+    verbose = enabled;
+}
+
+int multiply(int a, int b) {
+    atomic_fetch_add(&call_count, 1);
+    increment();
+    c_log(LOG_LEVEL_DEBUG, "multiply(%d, %d)", a, b);
+
+    if (verbose) {
+        printf("[C] Hello from C!\n");
+        printf("[C] Input a is: %i \n", a);
+        printf("[C] Input b is: %i \n", b);
+        printf("[C] Multiplying and returning result to Rust..\n");
+    }
+
+    // Unsigned overflow wraps around, signed overflow is undefined.
+    return (int)((unsigned)a * (unsigned)b);
+}
+
+const char *lib_version(void) {
+    return MULTIPLY_VERSION;
+}
+
+int multiply_call_count(void) {
+    return atomic_load(&call_count);
+}
+
+double multiply_f64(double a, double b) {
+    return a * b;
+}
+
+float multiply_f32(float a, float b) {
+    return a * b;
+}
+
+void multiply_batch(const int *a, const int *b, int *out, size_t n) {
+    for (size_t i = 0; i < n; i++) {
+        // Unsigned overflow wraps around, signed overflow is undefined.
+        out[i] = (int)((unsigned)a[i] * (unsigned)b[i]);
+    }
+}
+
+int multiply_checked(int a, int b, int *out) {
+#if defined(__GNUC__) || defined(__clang__)
+    // Computes the product as if with infinite precision and reports whether
+    // it fit, without ever performing an overflowing multiplication.
+    if (__builtin_mul_overflow(a, b, out)) {
+        return MULTIPLY_OVERFLOW;
+    }
+#else
+    // MSVC has no __builtin_mul_overflow, so widen instead. long long is at
+    // least 64 bits, which holds the product of any two 32-bit ints.
+    long long product = (long long)a * (long long)b;
+
+    if (product > INT_MAX || product < INT_MIN) {
+        return MULTIPLY_OVERFLOW;
+    }
+
+    *out = (int)product;
+#endif
+
+    return MULTIPLY_OK;
+}
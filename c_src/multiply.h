@@ -0,0 +1,404 @@
+#ifndef MULTIPLY_H
+#define MULTIPLY_H
+
+// Declarations for every function in c_src/, so the C files can check their
+// definitions against them, and bindgen can generate Rust declarations for
+// all of them from this one file.
+
+#include <stddef.h>
+
+// logging.c. Declared first, since every other file may log.
+
+// The same numbers as Rust's log::Level.
+#define LOG_LEVEL_ERROR 1
+#define LOG_LEVEL_WARN 2
+#define LOG_LEVEL_INFO 3
+#define LOG_LEVEL_DEBUG 4
+#define LOG_LEVEL_TRACE 5
+
+// Receives every message logged by the C code. msg is only valid during the
+// call. It may be called from any thread that calls into the library, so
+// user must be safe to share between threads.
+typedef void (*log_callback)(int level, const char *msg, void *user);
+
+// Registers cb, or turns logging off if cb is NULL (the default).
+void set_log_callback(log_callback cb, void *user);
+
+// Formats a message like printf and passes it to the callback, if any.
+void c_log(int level, const char *fmt, ...);
+
+// allocator.c. Every allocation the caller frees through another function
+// in this file goes through lib_alloc() and lib_free().
+
+typedef void *(*lib_alloc_fn)(size_t size);
+typedef void (*lib_dealloc_fn)(void *ptr, size_t size);
+
+#define SET_ALLOCATOR_OK 0
+#define SET_ALLOCATOR_NULL_POINTER 1
+#define SET_ALLOCATOR_ALREADY_SET 2
+
+// Makes lib_alloc() use alloc instead of malloc(). dealloc is called with
+// a pointer alloc returned and the size it was asked for. Both must be
+// safe to call from any thread. They can only be set once, and memory
+// allocated before stays with malloc() and free().
+int set_allocator(lib_alloc_fn alloc, lib_dealloc_fn dealloc);
+
+// Allocates size bytes aligned like malloc(), or returns NULL.
+void *lib_alloc(size_t size);
+
+// Frees memory from lib_alloc(), with the allocator it came from. NULL is
+// ignored, like free().
+void lib_free(void *ptr);
+
+// multiply.c
+
+// Returns a * b, wrapping around on overflow.
+int multiply(int a, int b);
+
+// Turns multiply()'s printing on (the default) or off.
+void multiply_set_verbose(int enabled);
+
+// How many times multiply() has been called.
+int multiply_call_count(void);
+
+double multiply_f64(double a, double b);
+float multiply_f32(float a, float b);
+
+// The version of this library, as "MAJOR.MINOR.PATCH". lib_version() returns
+// it in a string with static storage duration, which must not be freed.
+#define MULTIPLY_VERSION "1.2.0"
+const char *lib_version(void);
+
+#define MULTIPLY_OK 0
+#define MULTIPLY_OVERFLOW 1
+
+// Writes a * b to *out and returns MULTIPLY_OK, or returns MULTIPLY_OVERFLOW
+// without touching *out if the product doesn't fit in an int.
+int multiply_checked(int a, int b, int *out);
+
+// Writes a[i] * b[i] to out[i] for each i below n, wrapping around on
+// overflow. Doesn't print, and doesn't count towards multiply_call_count().
+void multiply_batch(const int *a, const int *b, int *out, size_t n);
+
+// divide.c
+
+int divide(int a, int b, int *out);
+int divide_errno(int a, int b);
+void clear_errno(void);
+int safe_divide(int a, int b, int *out);
+
+// strings.c
+
+char *greet(const char *name);
+void greet_free(char *greeting);
+const char *get_version(void);
+int describe_result(int value, char *buf, size_t buflen);
+
+// cstr_ownership.c
+
+// Must not be freed.
+const char *static_string(void);
+// Must be released with free().
+char *heap_string(const char *in);
+
+// geometry.c
+
+typedef struct {
+    double x;
+    double y;
+} Point;
+
+typedef struct {
+    int x;
+    int y;
+} IntPoint;
+
+double distance(Point a, Point b);
+IntPoint point_scale(IntPoint p, int factor);
+void point_scale_inplace(IntPoint *p, int factor);
+IntPoint make_point(int x, int y);
+
+// callbacks.c
+
+typedef int (*int_callback)(int);
+typedef void (*for_each_callback)(int value, void *user);
+
+int call_callback(int_callback callback, int value, int *out);
+int apply_twice(int_callback fn, int value);
+void for_each(const int *data, size_t len, for_each_callback cb, void *user);
+
+// arrays.c
+
+long long sum_array(const int *data, size_t len);
+void reverse_array(int *arr, size_t len);
+
+// context.c. The layout of Context is private to context.c.
+
+typedef struct Context Context;
+
+Context *ctx_create(int seed);
+int ctx_get_value(Context *ctx);
+void ctx_destroy(Context *ctx);
+
+// calculator.c. The layout of Calculator is private to calculator.c.
+
+typedef struct Calculator Calculator;
+
+Calculator *calculator_new(void);
+void calculator_accumulate(Calculator *calc, int value);
+int calculator_result(const Calculator *calc);
+void calculator_free(Calculator *calc);
+
+// counter_object.c. The layout of Counter is private to counter_object.c.
+
+typedef struct Counter Counter;
+
+Counter *counter_new(void);
+void counter_increment(Counter *counter);
+int counter_value(const Counter *counter);
+void counter_free(Counter *counter);
+
+// status.c
+
+typedef enum {
+    STATUS_NEGATIVE = -1,
+    STATUS_ZERO = 0,
+    STATUS_POSITIVE = 1,
+} StatusCode;
+
+StatusCode classify(int n);
+
+// compute.c
+
+// Calls rust_add(), which is defined in Rust (src/exported.rs).
+int compute_with_rust(int a, int b);
+
+// outparam.c
+
+int safe_sqrt(double input, double *result);
+
+// counter.c. The counters themselves are left out: bindgen can't translate
+// `_Atomic int`.
+
+// Atomically increments call_count, which multiply() does on every call.
+void increment(void);
+int get_call_count(void);
+void increment_atomic(void);
+
+// union_demo.c
+
+typedef union {
+    int i;
+    float f;
+    unsigned char bytes[4];
+} IntFloat;
+
+IntFloat int_float_from_int(int i);
+IntFloat int_float_from_float(float f);
+size_t int_float_size(void);
+
+// The same kind of union, with a tag saying which field was written.
+
+typedef union {
+    int i;
+    float f;
+} Value;
+
+#define VALUE_INT 0
+#define VALUE_FLOAT 1
+
+typedef struct {
+    int tag;
+    Value value;
+} TaggedValue;
+
+TaggedValue tagged_from_int(int i);
+TaggedValue tagged_from_float(float f);
+int tagged_as_double(TaggedValue value, double *out);
+
+// calculate.c
+
+typedef enum {
+    OP_ADD = 0,
+    OP_SUB = 1,
+    OP_MUL = 2,
+    OP_DIV = 3,
+} Op;
+
+#define CALC_OK 0
+#define CALC_ERR_DIVIDE_BY_ZERO 1
+#define CALC_ERR_UNKNOWN_OP 2
+#define CALC_ERR_OVERFLOW 3
+
+// Applies op to a and b and writes the result to *out. Returns CALC_OK, or
+// one of the CALC_ERR_* codes without touching *out. An op outside the enum
+// is reported as CALC_ERR_UNKNOWN_OP.
+int calculate(Op op, int a, int b, int *out);
+int op_from_symbol(char symbol);
+
+// range.c
+
+int *make_range(int start, int count);
+int *make_range_with_len(int n, size_t *out_len);
+void free_range(int *values);
+
+// varargs.c
+
+// Sums count ints passed after it, wrapping around on overflow.
+int sum_ints(int count, ...);
+
+// numeric.c
+
+double scale(double value, double factor);
+
+// matrix.c
+
+void mat_mul(const double *a, const double *b, double *c, size_t n);
+
+// linked_list.c
+
+typedef struct Node {
+    int value;
+    struct Node *next;
+} Node;
+
+Node *make_list(const int *values, size_t len);
+Node *make_counting_list(int n);
+void free_list(Node *head);
+
+// color.c
+
+enum Color { RED, GREEN, BLUE };
+
+enum Color next_color(enum Color color);
+
+// sort.c
+
+void sort_ints(int *arr, size_t len);
+
+// nullable.c
+
+int *find_first(int *arr, size_t len, int target);
+
+typedef struct {
+    int retries;
+    int timeout_ms;
+} Config;
+
+#define CONFIG_DEFAULT_RETRIES 3
+#define CONFIG_DEFAULT_TIMEOUT_MS 100
+
+// Returns (retries + 1) * timeout_ms, wrapping around on overflow, with the
+// defaults if cfg is NULL.
+int configure(const Config *cfg);
+
+int *maybe_value(int fail);
+
+// global_state.c. The counter itself is `static`, so it isn't declared here.
+
+void bump_global(void);
+int read_global(void);
+
+// progress.c
+
+typedef void (*progress_callback)(unsigned percent, void *user);
+
+long long slow_sum(long long n, progress_callback progress, void *user);
+
+// Returned by slow_sum_cancellable(), which is left out because bindgen
+// can't translate its `const _Atomic int *` parameter.
+#define SLOW_SUM_OK 0
+#define SLOW_SUM_CANCELLED 1
+
+// record.c
+
+#define RECORD_NAME_LEN 64
+
+typedef struct {
+    char name[RECORD_NAME_LEN];
+    int id;
+    double score;
+} Record;
+
+Record make_record(const char *name, int id, double score);
+void print_record(Record r);
+
+// library.c
+
+int library_init(void);
+void library_deinit(void);
+int library_is_initialized(void);
+int library_next_id(void);
+
+// booleans.c
+
+_Bool all_positive(const int *arr, size_t len);
+void set_flags(_Bool *flags, size_t len, _Bool value);
+
+// alloc_string.c
+
+#define GET_MESSAGE_OK 0
+#define GET_MESSAGE_NULL_POINTER 1
+#define GET_MESSAGE_NO_MEMORY 2
+
+// On success, *out must be released with free_message().
+int get_message(char **out);
+void free_message(char *msg);
+
+// c_result.c
+
+#define CRESULT_OK 0
+#define CRESULT_ERR 1
+
+// A result returned by value: value is set when tag is CRESULT_OK, errcode
+// (one of the CALC_ERR_* codes) when tag is CRESULT_ERR.
+typedef struct {
+    int tag;
+    int value;
+    int errcode;
+} CResult;
+
+CResult try_multiply(int a, int b);
+size_t c_result_size(void);
+
+// vtable.c. Not called Calculator, which is the opaque type above.
+
+typedef struct {
+    int (*add)(int, int);
+    int (*sub)(int, int);
+} CalculatorVTable;
+
+// Must not be freed.
+const CalculatorVTable *c_calculator(void);
+int apply_add(const CalculatorVTable *calc, int a, int b);
+int apply_sub(const CalculatorVTable *calc, int a, int b);
+
+// ring_buffer.c. Not opaque: the layout is the interface, so that code that
+// only has the memory, like another process mapping it, can use it too.
+
+#define RB_OK 0
+#define RB_FULL 1
+#define RB_EMPTY 2
+
+// A fixed-capacity FIFO queue. The values are data[head], data[(head + 1) %
+// capacity], ..., len of them. Not synchronized.
+typedef struct {
+    size_t capacity;
+    size_t head;
+    size_t len;
+    int data[];
+} RingBuffer;
+
+RingBuffer *rb_create(size_t capacity);
+int rb_push(RingBuffer *rb, int value);
+int rb_pop(RingBuffer *rb, int *out);
+void rb_destroy(RingBuffer *rb);
+size_t rb_data_offset(void);
+
+// stdcall.c, Windows only.
+
+#ifdef _WIN32
+int __stdcall multiply_stdcall(int a, int b);
+#endif
+
+#endif
@@ -0,0 +1,34 @@
+#include "multiply.h"
+
+// Returns a pointer to the first of the len integers starting at arr that
+// equals target, or NULL if none does. Only reads the array.
+int *find_first(int *arr, size_t len, int target) {
+    for (size_t i = 0; i < len; i++) {
+        if (arr[i] == target) {
+            return &arr[i];
+        }
+    }
+
+    return NULL;
+}
+
+// Returns the longest an operation may take with the settings in *cfg, in
+// milliseconds: every try, the first one and the retries, running into the
+// timeout. A NULL cfg means the default settings, CONFIG_DEFAULT_RETRIES
+// and CONFIG_DEFAULT_TIMEOUT_MS. The total wraps around if it overflows.
+int configure(const Config *cfg) {
+    if (cfg == NULL) {
+        return (CONFIG_DEFAULT_RETRIES + 1) * CONFIG_DEFAULT_TIMEOUT_MS;
+    }
+
+    // Unsigned overflow wraps around, signed overflow is undefined.
+    return (int)(((unsigned)cfg->retries + 1u) * (unsigned)cfg->timeout_ms);
+}
+
+// Returns NULL if fail is non-zero, and otherwise a pointer to a static int
+// holding 42, which must not be freed. Only there to test both ways a
+// pointer can come back.
+int *maybe_value(int fail) {
+    static int value = 42;
+    return fail ? NULL : &value;
+}
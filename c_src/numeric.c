@@ -0,0 +1,7 @@
+#include "multiply.h"
+
+// Returns value multiplied by factor. NaN and the infinities pass through
+// as IEEE 754 arithmetic says: any NaN operand gives NaN.
+double scale(double value, double factor) {
+    return value * factor;
+}
@@ -0,0 +1,14 @@
+#include <math.h>
+
+#include "multiply.h"
+
+// Writes the square root of input to *result and returns 0, or returns -1
+// without touching *result if input is negative.
+int safe_sqrt(double input, double *result) {
+    if (input < 0.0) {
+        return -1;
+    }
+
+    *result = sqrt(input);
+    return 0;
+}
@@ -0,0 +1,58 @@
+#include <stdatomic.h>
+#include <stddef.h>
+
+#include "multiply.h"
+
+// Returns 0 + 1 + ... + (n - 1), wrapping around on overflow, one addition
+// at a time to stand in for a slow computation. If progress isn't NULL, it
+// is called with 0 first, then whenever the completed percentage goes up,
+// and finally with 100, passing user along untouched. A negative n counts
+// as 0.
+long long slow_sum(long long n, progress_callback progress, void *user) {
+    unsigned long long sum = 0;
+    unsigned reported = 0;
+
+    if (progress != NULL) {
+        progress(0, user);
+    }
+
+    for (long long i = 0; i < n; i++) {
+        sum += (unsigned long long)i;
+
+        if (progress != NULL) {
+            unsigned percent = (unsigned)(100.0 * (double)(i + 1) / (double)n);
+            if (percent > reported) {
+                reported = percent;
+                progress(percent, user);
+            }
+        }
+    }
+
+    if (progress != NULL && reported < 100) {
+        progress(100, user);
+    }
+
+    return (long long)sum;
+}
+
+// Like slow_sum(), but checks *cancel_flag before every addition and gives
+// up as soon as another thread sets it to a non-zero value. Writes the sum
+// to *out and returns SLOW_SUM_OK, or returns SLOW_SUM_CANCELLED without
+// touching *out.
+//
+// The flag is atomic, not just volatile: volatile stops the compiler from
+// caching the value, but a plain read racing with a write from another
+// thread is still a data race, which C11 leaves undefined.
+int slow_sum_cancellable(long long n, const _Atomic int *cancel_flag, long long *out) {
+    unsigned long long sum = 0;
+
+    for (long long i = 0; i < n; i++) {
+        if (atomic_load_explicit(cancel_flag, memory_order_relaxed) != 0) {
+            return SLOW_SUM_CANCELLED;
+        }
+        sum += (unsigned long long)i;
+    }
+
+    *out = (long long)sum;
+    return SLOW_SUM_OK;
+}
@@ -0,0 +1,34 @@
+#include "multiply.h"
+
+// Returns a newly allocated array holding start, start + 1, ..., start +
+// count - 1, or NULL if count is 0 or allocation fails. The caller owns the
+// result and must release it with free_range().
+int *make_range(int start, int count) {
+    if (count <= 0) {
+        return NULL;
+    }
+
+    int *values = lib_alloc((size_t)count * sizeof(int));
+    if (values == NULL) {
+        return NULL;
+    }
+
+    for (int i = 0; i < count; i++) {
+        values[i] = start + i;
+    }
+
+    return values;
+}
+
+// Returns a newly allocated array holding 0, 1, ..., n - 1 and writes its
+// length to *out_len, or returns NULL and writes 0 if n isn't positive or
+// allocation fails. Release the result with free_range().
+int *make_range_with_len(int n, size_t *out_len) {
+    int *values = make_range(0, n);
+    *out_len = values == NULL ? 0 : (size_t)n;
+    return values;
+}
+
+void free_range(int *values) {
+    lib_free(values);
+}
@@ -0,0 +1,25 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "multiply.h"
+
+// Returns a record holding a copy of name, cut off after
+// RECORD_NAME_LEN - 1 bytes so there is always room for the terminator.
+Record make_record(const char *name, int id, double score) {
+    Record r;
+
+    // Zeroes the bytes after the name and any padding, so Rust never sees
+    // uninitialized memory.
+    memset(&r, 0, sizeof r);
+    snprintf(r.name, sizeof r.name, "%s", name);
+    r.id = id;
+    r.score = score;
+
+    return r;
+}
+
+// Prints r, which is passed by value: all RECORD_NAME_LEN bytes of the name
+// are copied along with the other fields.
+void print_record(Record r) {
+    printf("[C] Record #%d: %s, score %.2f\n", r.id, r.name, r.score);
+}
@@ -0,0 +1,58 @@
+#include <stddef.h>
+#include <stdint.h>
+
+#include "multiply.h"
+
+_Static_assert(offsetof(RingBuffer, data) == 3 * sizeof(size_t),
+               "RingBuffer's header must have no padding");
+
+// Returns a new, empty ring buffer with room for capacity values, or NULL
+// if capacity is 0 or allocation fails. Release it with rb_destroy().
+RingBuffer *rb_create(size_t capacity) {
+    if (capacity == 0 || capacity > (SIZE_MAX - sizeof(RingBuffer)) / sizeof(int)) {
+        return NULL;
+    }
+
+    RingBuffer *rb = lib_alloc(sizeof(RingBuffer) + capacity * sizeof(int));
+    if (rb == NULL) {
+        return NULL;
+    }
+
+    rb->capacity = capacity;
+    rb->head = 0;
+    rb->len = 0;
+    return rb;
+}
+
+// Appends value after the newest one, or returns RB_FULL and leaves the
+// buffer unchanged if there is no room.
+int rb_push(RingBuffer *rb, int value) {
+    if (rb->len == rb->capacity) {
+        return RB_FULL;
+    }
+
+    rb->data[(rb->head + rb->len) % rb->capacity] = value;
+    rb->len++;
+    return RB_OK;
+}
+
+// Removes the oldest value and writes it to *out, or returns RB_EMPTY and
+// leaves *out untouched if there is none.
+int rb_pop(RingBuffer *rb, int *out) {
+    if (rb->len == 0) {
+        return RB_EMPTY;
+    }
+
+    *out = rb->data[rb->head];
+    rb->head = (rb->head + 1) % rb->capacity;
+    rb->len--;
+    return RB_OK;
+}
+
+void rb_destroy(RingBuffer *rb) {
+    lib_free(rb);
+}
+
+size_t rb_data_offset(void) {
+    return offsetof(RingBuffer, data);
+}
@@ -0,0 +1,16 @@
+#include <stdlib.h>
+
+#include "multiply.h"
+
+static int compare_ints(const void *a, const void *b) {
+    int x = *(const int *)a;
+    int y = *(const int *)b;
+
+    // Not x - y, which overflows for values far apart.
+    return (x > y) - (x < y);
+}
+
+// Sorts the len integers starting at arr in place, in ascending order.
+void sort_ints(int *arr, size_t len) {
+    qsort(arr, len, sizeof(int), compare_ints);
+}
@@ -0,0 +1,13 @@
+#include "multiply.h"
+
+StatusCode classify(int n) {
+    if (n < 0) {
+        return STATUS_NEGATIVE;
+    }
+
+    if (n == 0) {
+        return STATUS_ZERO;
+    }
+
+    return STATUS_POSITIVE;
+}
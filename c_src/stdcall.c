@@ -0,0 +1,17 @@
+#include "multiply.h"
+
+// Only built for Windows targets by build.rs. The check keeps the file
+// harmless where it is built anyway, like by the Makefile's wildcard.
+#ifdef _WIN32
+
+// Multiplies a and b like multiply(), wrapping around on overflow, but
+// without the printing and with the calling convention most of the Win32
+// API uses (WINAPI is #defined to __stdcall). On 32-bit x86, __stdcall has
+// the callee pop its arguments off the stack, where __cdecl, the default for
+// C, leaves that to the caller. The two can't call each other. Everywhere
+// else the keyword is accepted and ignored.
+int __stdcall multiply_stdcall(int a, int b) {
+    return (int)((unsigned)a * (unsigned)b);
+}
+
+#endif
@@ -0,0 +1,42 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "multiply.h"
+
+// Returns a newly allocated greeting for name, or NULL if allocation fails.
+// The caller owns the result and must release it with greet_free().
+char *greet(const char *name) {
+    // "Hello, " + name + "!" + NUL terminator
+    size_t len = strlen("Hello, ") + strlen(name) + 2;
+
+    char *greeting = lib_alloc(len);
+    if (greeting == NULL) {
+        return NULL;
+    }
+
+    snprintf(greeting, len, "Hello, %s!", name);
+    return greeting;
+}
+
+void greet_free(char *greeting) {
+    lib_free(greeting);
+}
+
+// Writes a description of value to buf, truncated to fit buflen bytes
+// including the NUL terminator, like snprintf. Returns the length of the
+// whole description, not counting the terminator, so a return value of
+// buflen or more means it was truncated. buf may be NULL if buflen is 0,
+// which just asks for the length. Returns a negative value on error.
+int describe_result(int value, char *buf, size_t buflen) {
+    const char *sign = value < 0 ? "negative" : value == 0 ? "zero" : "positive";
+    const char *parity = value % 2 == 0 ? "even" : "odd";
+
+    return snprintf(buf, buflen, "%d is %s and %s", value, sign, parity);
+}
+
+// The returned string is a literal with static storage duration. It lives for
+// the whole program and must not be freed by the caller. The same version as
+// lib_version().
+const char *get_version(void) {
+    return MULTIPLY_VERSION;
+}
@@ -0,0 +1,50 @@
+#include <stddef.h>
+
+#include "multiply.h"
+
+_Static_assert(sizeof(IntFloat) == 4, "IntFloat must be 4 bytes");
+
+IntFloat int_float_from_int(int i) {
+    IntFloat value;
+    value.i = i;
+    return value;
+}
+
+IntFloat int_float_from_float(float f) {
+    IntFloat value;
+    value.f = f;
+    return value;
+}
+
+size_t int_float_size(void) {
+    return sizeof(IntFloat);
+}
+
+TaggedValue tagged_from_int(int i) {
+    TaggedValue value;
+    value.tag = VALUE_INT;
+    value.value.i = i;
+    return value;
+}
+
+TaggedValue tagged_from_float(float f) {
+    TaggedValue value;
+    value.tag = VALUE_FLOAT;
+    value.value.f = f;
+    return value;
+}
+
+// Reads whichever field the tag says was written, or returns -1 for a tag
+// that isn't VALUE_INT or VALUE_FLOAT.
+int tagged_as_double(TaggedValue value, double *out) {
+    switch (value.tag) {
+    case VALUE_INT:
+        *out = value.value.i;
+        return 0;
+    case VALUE_FLOAT:
+        *out = value.value.f;
+        return 0;
+    default:
+        return -1;
+    }
+}
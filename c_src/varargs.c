@@ -0,0 +1,18 @@
+#include <stdarg.h>
+
+#include "multiply.h"
+
+// Sums count ints passed after it, wrapping around on overflow.
+int sum_ints(int count, ...) {
+    va_list args;
+    va_start(args, count);
+
+    // Unsigned overflow wraps around, signed overflow is undefined.
+    unsigned total = 0;
+    for (int i = 0; i < count; i++) {
+        total += (unsigned)va_arg(args, int);
+    }
+
+    va_end(args);
+    return (int)total;
+}
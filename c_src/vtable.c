@@ -0,0 +1,29 @@
+#include "multiply.h"
+
+// Both wrap on overflow, like the Rust functions in src/vtable.rs, instead
+// of overflowing a signed int, which is undefined behavior.
+static int c_add(int a, int b) {
+    return (int)((unsigned)a + (unsigned)b);
+}
+
+static int c_sub(int a, int b) {
+    return (int)((unsigned)a - (unsigned)b);
+}
+
+static const CalculatorVTable C_CALCULATOR = {c_add, c_sub};
+
+// A vtable filled in with C functions. It lives in static memory and must
+// not be freed.
+const CalculatorVTable *c_calculator(void) {
+    return &C_CALCULATOR;
+}
+
+// Calls through whichever functions calc was filled in with, in C or in
+// another language.
+int apply_add(const CalculatorVTable *calc, int a, int b) {
+    return calc->add(a, b);
+}
+
+int apply_sub(const CalculatorVTable *calc, int a, int b) {
+    return calc->sub(a, b);
+}
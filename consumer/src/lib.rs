@@ -0,0 +1,12 @@
+//! A crate using `rust-ffi-to-c` as a library.
+//!
+//! It only sees the public API: the safe wrappers, and the raw declarations
+//! in `rust_ffi_to_c::raw`. Linking the C code is handled by the build
+//! script of `rust-ffi-to-c`, so nothing here knows about `c_src/`.
+
+use rust_ffi_to_c::multiply;
+
+/// The area of a `width` by `height` rectangle, computed in C.
+pub fn area(width: i32, height: i32) -> i32 {
+    multiply(width, height)
+}
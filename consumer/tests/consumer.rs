@@ -0,0 +1,15 @@
+use rust_ffi_to_c::{ffi, raw};
+
+#[test]
+fn area() {
+    ffi::set_verbose(false);
+
+    assert_eq!(ffi_consumer::area(6, 7), 42);
+    assert_eq!(ffi_consumer::area(-3, 4), -12);
+}
+
+#[test]
+fn raw_declarations_are_reachable() {
+    // SAFETY: `multiply_f64` takes and returns plain values.
+    assert_eq!(unsafe { raw::multiply_f64(1.5, 2.0) }, 3.0);
+}
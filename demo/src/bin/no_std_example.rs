@@ -0,0 +1,71 @@
+//! Calling C's `multiply()` without the Rust standard library.
+//!
+//! Firmware and other embedded code often has no `std`, but can still call
+//! existing C libraries: everything FFI needs (`extern "C"`, `c_int`) lives
+//! in `core`. This binary shows what a program has to provide itself once
+//! `std` is gone. It runs on Linux, where libc stands in for the rest of the
+//! platform, so it can also print.
+//!
+//! It needs `panic = "abort"`, which is set in the `no-std` profile, and is
+//! only built with the `no-std-example` feature:
+//!
+//!     cargo run --profile no-std --features no-std-example --bin no_std_example
+
+// Don't link `std`, only `core`. `core` has no I/O, no allocator and no
+// threads, but has everything needed to declare and call C functions.
+#![no_std]
+// Don't generate Rust's usual `main`. Without `std` there is nothing to
+// set up the Rust runtime and call `fn main()`, so we provide the C `main`
+// ourselves below, and the C runtime calls it like in any C program.
+#![no_main]
+
+use core::ffi::{c_char, c_int};
+use core::panic::PanicInfo;
+
+// `std` normally links libc for us. Without it, rustc tells the linker not
+// to link any default libraries, so ask for libc explicitly: it provides the
+// C runtime that calls `main`, and the `printf` that `multiply()` and this
+// file use.
+#[link(name = "c")]
+extern "C" {
+    fn printf(format: *const c_char, ...) -> c_int;
+}
+
+// The bindings come from ffi-core, which only uses `core`. It declares the C
+// functions but doesn't link them: the `rustc-link-lib` line from the build
+// script of rust-ffi-to-c only applies where that crate is linked, and this
+// binary doesn't use it (it depends on `std`), so name the library here. Its
+// directory does come from the build script's `rustc-link-search` line,
+// which Cargo passes on to this package.
+#[link(name = "multiply", kind = "static")]
+extern "C" {}
+
+// The C entry point, called by the C runtime with the usual arguments.
+// `#[no_mangle]` keeps the symbol name `main`, so the C runtime finds it.
+#[no_mangle]
+pub extern "C" fn main(_argc: c_int, _argv: *const *const c_char) -> c_int {
+    let result = ffi_core::multiply(5000, 5);
+    let checked = ffi_core::multiply_checked(c_int::MAX, 2);
+
+    // SAFETY: the format strings are NUL-terminated and match their one
+    // `int` argument.
+    unsafe {
+        printf(c"[Rust] Result without std: %d\n".as_ptr(), result);
+        printf(
+            c"[Rust] multiply_checked(INT_MAX, 2) overflowed: %d\n".as_ptr(),
+            c_int::from(checked.is_none()),
+        );
+    }
+
+    0
+}
+
+// `std` normally decides what a panic does. Without it, the program has to
+// say so itself. There's nowhere to report the panic and no way to unwind,
+// so just stop here. With `panic = "abort"`, no unwinding code is needed
+// either, which is also why the `eh_personality` item `std` would provide
+// isn't needed.
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
@@ -0,0 +1,583 @@
+use std::env;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use rust_ffi_to_c::{
+    alloc_string, arithmetic, arrays, booleans, c_result, calculate, calculator, callbacks, cli,
+    color, context, counter, cstr_ownership, errors, exported, ffi, global_state, globals, library,
+    linked_list, matrix, nullable, numeric, outparam, progress, range, record, ring_buffer,
+    roundtrip, safe, sort, status, strings, threads, types, union_demo, varargs, version, vtable,
+};
+
+#[cfg(feature = "dynamic")]
+use rust_ffi_to_c::dynamic;
+
+fn main() {
+    println!("[Rust] Hello from Rust! 🦀");
+
+    let args = match cli::parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let (a, b) = args.operands;
+
+    println!("[Rust] Calling function in C..");
+
+    let result = if args.op == calculate::Op::Mul {
+        // multiply() would silently wrap around on overflow, so don't let
+        // user input get that far.
+        let Some(result) = safe::checked_multiply(a, b) else {
+            eprintln!("error: {} * {} does not fit in a C int", a, b);
+            process::exit(1);
+        };
+        result
+    } else {
+        // calculate() checks for overflow and division by zero in C.
+        match calculate::calculate(args.op, a, b) {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("error: {:?}({}, {}) failed: {}", args.op, a, b, err);
+                process::exit(1);
+            }
+        }
+    };
+
+    println!("[Rust] Result: {}", result);
+
+    let result = ffi::multiply_f64(2.5, -4.0);
+
+    println!("[Rust] Floating-point result: {}", result);
+
+    match version::require_version((1, 2, 0)) {
+        Ok(()) => println!("[Rust] libmultiply version: {}", version::version()),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    #[cfg(feature = "dynamic")]
+    {
+        println!("[Rust] Calling the same function from a library loaded at runtime..");
+
+        match dynamic::DynamicMultiply::load(&dynamic::default_path()) {
+            Ok(library) => match library.require_version((1, 2, 0)) {
+                Ok(()) => println!("[Rust] Result: {}", library.multiply(5000, 5)),
+                Err(err) => println!("[Rust] Refusing to use the loaded library: {}", err),
+            },
+            Err(err) => println!("[Rust] Error: {}", err),
+        }
+    }
+
+    println!("[Rust] Calling the overflow-checked wrapper..");
+
+    match safe::safe_multiply(i32::MAX, 2) {
+        Ok(result) => println!("[Rust] Result: {}", result),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    println!(
+        "[Rust] checked_multiply(i32::MAX, 2) = {:?}",
+        safe::checked_multiply(i32::MAX, 2)
+    );
+
+    for (a, b) in [(6, 7), (i32::MAX, 2)] {
+        match safe::multiply_checked(a, b) {
+            Ok(result) => println!("[Rust] multiply_checked({}, {}) = {}", a, b, result),
+            Err(err) => println!("[Rust] multiply_checked({}, {}) failed: {}", a, b, err),
+        }
+    }
+
+    println!("[Rust] Dividing in C..");
+
+    for (a, b) in [(10, 2), (10, 0)] {
+        match arithmetic::divide(a, b) {
+            Ok(result) => println!("[Rust] {} / {} = {}", a, b, result),
+            Err(err) => println!("[Rust] {} / {} failed: {}", a, b, err),
+        }
+    }
+
+    for (a, b) in [(-7, 2), (i32::MIN, 1), (7, 0)] {
+        match arithmetic::divide_errno(a, b) {
+            Ok(result) => println!("[Rust] {} / {} = {}", a, b, result),
+            Err(err) => println!("[Rust] {} / {} failed: {}", a, b, err),
+        }
+    }
+
+    for (a, b) in [(9, 3), (9, 0), (i32::MIN, -1)] {
+        match errors::safe_divide(a, b) {
+            Ok(result) => println!("[Rust] {} / {} = {}", a, b, result),
+            Err(err) => println!("[Rust] {} / {} failed: {}", a, b, err),
+        }
+    }
+
+    println!("[Rust] Passing a string to C..");
+
+    for name in ["Ferris", "Fer\0ris"] {
+        match strings::greet(name) {
+            Ok(greeting) => println!("[Rust] C says: {}", greeting),
+            Err(err) => println!("[Rust] Error: {}", err),
+        }
+    }
+
+    match strings::version() {
+        Ok(version) => println!("[Rust] C library version: {}", version),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    match strings::describe_result(a) {
+        Ok(description) => println!("[Rust] Described by C: {}", description),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    println!(
+        "[Rust] Borrowed from C: {:?}",
+        cstr_ownership::static_string()
+    );
+
+    match cstr_ownership::heap_string("owned by Rust, freed by C's free()") {
+        Ok(Some(owned)) => println!("[Rust] Copied by C: {:?}", owned),
+        Ok(None) => println!("[Rust] C failed to allocate"),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    match alloc_string::get_message() {
+        Ok(message) => println!("[Rust] Allocated by C: {:?}", message.as_str()),
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    println!("[Rust] Passing structs to C..");
+
+    let a = types::Point { x: 0.0, y: 0.0 };
+    let b = types::Point { x: 3.0, y: 4.0 };
+    let distance = types::distance(a, b);
+
+    // If the struct layout didn't match, C would have read the wrong fields.
+    let expected = (b.x - a.x).hypot(b.y - a.y);
+    assert!((distance - expected).abs() <= f64::EPSILON * expected);
+
+    println!("[Rust] Distance from {:?} to {:?}: {}", a, b, distance);
+
+    println!("[Rust] Passing a Rust function to C..");
+
+    match callbacks::call_callback(Some(exported::rust_callback), 21) {
+        Some(result) => println!("[Rust] Result: {}", result),
+        None => println!("[Rust] C refused to call the callback"),
+    }
+
+    match callbacks::call_callback(None, 21) {
+        Some(result) => println!("[Rust] Result: {}", result),
+        None => println!("[Rust] C refused to call a NULL callback"),
+    }
+
+    println!(
+        "[Rust] apply_twice(double, 3) = {}",
+        callbacks::apply_twice(callbacks::double, 3)
+    );
+    println!(
+        "[Rust] apply_twice_with(|x| x + 10, 3) = {}",
+        callbacks::apply_twice_with(|x| x + 10, 3)
+    );
+
+    let mut point = types::make_point(2, -3);
+    println!("[Rust] C made a point: {:?}", point);
+    println!(
+        "[Rust] Scaled by value: {:?}",
+        types::point_scale(point, 10)
+    );
+    types::point_scale_inplace(&mut point, 10);
+    println!("[Rust] Scaled in place: {:?}", point);
+
+    println!("[Rust] Passing a slice to C..");
+    println!("[Rust] sum(&[1, 2, 3, 4]) = {}", arrays::sum(&[1, 2, 3, 4]));
+    println!("[Rust] sum(&[]) = {}", arrays::sum(&[]));
+    println!(
+        "[Rust] sum(&[i32::MAX; 4]) = {}",
+        arrays::sum(&[i32::MAX; 4])
+    );
+
+    let mut seen = Vec::new();
+    callbacks::for_each(&[1, 2, 3], |value| seen.push(value * 100));
+    println!("[Rust] Closure called from C collected: {:?}", seen);
+
+    let mut values = [1, 2, 3, 4, 5];
+    arrays::reverse_slice(&mut values);
+    println!("[Rust] Reversed in C: {:?}", values);
+    println!(
+        "[Rust] sum_slice(&{:?}) = {}",
+        values,
+        arrays::sum_slice(&values)
+    );
+
+    println!("[Rust] Using an opaque C context..");
+
+    let ctx = context::CContext::new(42);
+    let values: Vec<i32> = (0..3).map(|_| ctx.value()).collect();
+    println!("[Rust] Values from the context: {:?}", values);
+
+    println!("[Rust] Using a C calculator..");
+
+    match calculator::Calculator::new() {
+        Ok(mut calc) => {
+            for value in [10, 20, 12] {
+                calc.accumulate(value);
+            }
+            println!("[Rust] Calculator total: {}", calc.result());
+        }
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    match counter::Counter::new() {
+        Ok(mut counter) => {
+            for _ in 0..3 {
+                counter.increment();
+            }
+            println!("[Rust] Counter value: {}", counter.value());
+            // Dropping `counter` here frees it in C.
+        }
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    println!("[Rust] Passing an enum to C..");
+
+    for (symbol, a, b) in [('+', 40, 2), ('/', 1, 0), ('%', 7, 3)] {
+        match calculate::Op::from_symbol(symbol) {
+            Some(op) => match calculate::calculate(op, a, b) {
+                Ok(result) => println!("[Rust] {} {} {} = {}", a, symbol, b, result),
+                Err(err) => println!("[Rust] {} {} {} failed: {}", a, symbol, b, err),
+            },
+            None => println!("[Rust] C doesn't know the operation {:?}", symbol),
+        }
+    }
+
+    println!("[Rust] Getting a result struct from C..");
+
+    for (a, b) in [(6, 7), (i32::MAX, 2)] {
+        match c_result::try_multiply(a, b) {
+            Ok(product) => println!("[Rust] {} * {} = {}", a, b, product),
+            Err(err) => println!("[Rust] {} * {} failed: {}", a, b, err),
+        }
+    }
+
+    println!("[Rust] Getting an enum from C..");
+
+    for n in [-5, 0, 5] {
+        match status::classify(n) {
+            Ok(status) => println!("[Rust] classify({}) = {:?}", n, status),
+            Err(err) => println!("[Rust] Error: {}", err),
+        }
+    }
+
+    println!("[Rust] Calling C, which calls back into Rust..");
+    println!(
+        "[Rust] compute_with_rust(40, 2) = {}",
+        roundtrip::compute_with_rust(40, 2)
+    );
+
+    println!("[Rust] Getting a result through an out pointer..");
+
+    for x in [4.0, -1.0] {
+        match outparam::safe_sqrt(x) {
+            Ok(root) => println!("[Rust] sqrt({}) = {}", x, root),
+            Err(err) => println!("[Rust] Error: {}", err),
+        }
+    }
+
+    println!("[Rust] Using C global variables..");
+
+    // Nothing else in this program has taken the token.
+    let mut token = globals::CallCountToken::take().expect("token already taken");
+
+    // multiply() has been called before, and counts every call.
+    globals::reset_direct(&mut token);
+    ffi::set_verbose(false);
+    for i in 1..=3 {
+        ffi::multiply(i, i);
+    }
+    ffi::set_verbose(true);
+    println!(
+        "[Rust] call_count() after 3 calls to multiply() = {}",
+        globals::call_count(&token)
+    );
+    println!(
+        "[Rust] Read directly: {}",
+        globals::call_count_direct(&token)
+    );
+
+    globals::reset_direct(&mut token);
+    println!(
+        "[Rust] After a reset from Rust: {}",
+        globals::call_count(&token)
+    );
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..1000 {
+                    globals::increment_atomic();
+                    globals::increment_atomic_from_rust();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!(
+        "[Rust] Atomic counter after 4 threads: {}",
+        globals::atomic_call_count()
+    );
+
+    println!("[Rust] Reading a C union..");
+
+    println!(
+        "[Rust] size_of::<IntFloat>() = {}, sizeof(IntFloat) = {}",
+        std::mem::size_of::<union_demo::IntFloat>(),
+        union_demo::c_size()
+    );
+
+    let one = union_demo::from_float(1.0);
+    println!("[Rust] 1.0f32 from C: {:?}", one);
+    assert_eq!(one.as_int() as u32, 1.0f32.to_bits());
+    println!("[Rust] As u32: {:#010x}", one.as_int() as u32);
+    println!("[Rust] 42 from C: {:?}", union_demo::from_int(42));
+
+    let tagged = union_demo::tagged_from_float(2.5);
+    println!(
+        "[Rust] Tagged by C: {:?}, read by C: {:?}",
+        union_demo::Number::try_from(tagged),
+        union_demo::tagged_as_f64(tagged)
+    );
+
+    println!("[Rust] Borrowing an array allocated by C..");
+
+    for (start, count) in [(10, 5), (0, 0), (i32::MAX, 2)] {
+        match range::make_range(start, count) {
+            Ok(values) => println!("[Rust] make_range({}, {}) = {:?}", start, count, values),
+            Err(err) => println!("[Rust] make_range({}, {}) failed: {}", start, count, err),
+        }
+    }
+
+    println!("[Rust] Copying an array allocated by C into a Vec..");
+    println!("[Rust] make_range_vec(5) = {:?}", range::make_range_vec(5));
+
+    println!("[Rust] Calling a variadic C function..");
+
+    for values in [&[][..], &[10, 20, 30], &[1, 2, 3, 4, 5]] {
+        match varargs::sum_ints(values) {
+            Some(sum) => println!("[Rust] sum_ints({:?}) = {}", values, sum),
+            None => println!("[Rust] sum_ints({:?}) takes at most 4 values", values),
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    {
+        // SAFETY: three `c_int`s follow the count.
+        let sum = unsafe { varargs::sum_ints_rust(3, 10, 20, 30) };
+        println!("[Rust] sum_ints_rust(3, 10, 20, 30) = {}", sum);
+    }
+
+    println!("[Rust] Scaling doubles in C..");
+
+    for (value, factor) in [(2.5, 0.5), (-1.0, 3.0), (f64::NAN, 2.0)] {
+        println!(
+            "[Rust] scale({}, {}) = {}",
+            value,
+            factor,
+            numeric::scale(value, factor)
+        );
+    }
+
+    println!("[Rust] Multiplying matrices in C..");
+
+    let a = [1.0, 2.0, 3.0, 4.0];
+    let b = [5.0, 6.0, 7.0, 8.0];
+    println!(
+        "[Rust] {:?} x {:?} = {:?}",
+        a,
+        b,
+        matrix::mat_mul(&a, &b, 2)
+    );
+
+    println!("[Rust] Walking a linked list built by C..");
+
+    match linked_list::CLinkedList::new(&[1, 2, 3]) {
+        Ok(list) => println!("[Rust] List: {:?}", list.collect::<Vec<_>>()),
+        Err(err) => println!("[Rust] make_list failed: {}", err),
+    }
+
+    match linked_list::CList::new(5) {
+        Ok(list) => println!(
+            "[Rust] Walked twice: sum {}, max {:?}",
+            list.iter().sum::<i32>(),
+            list.iter().max()
+        ),
+        Err(err) => println!("[Rust] make_counting_list failed: {}", err),
+    }
+
+    println!("[Rust] Passing an enum to C..");
+
+    let mut light = color::Color::Red;
+    for _ in 0..3 {
+        match color::next_color(light) {
+            Ok(next) => {
+                println!("[Rust] next_color({:?}) = {:?}", light, next);
+                light = next;
+            }
+            Err(err) => println!("[Rust] next_color({:?}) failed: {}", light, err),
+        }
+    }
+
+    match color::next_color_raw(7) {
+        Ok(next) => println!("[Rust] next_color(7) = {:?}", next),
+        Err(err) => println!("[Rust] next_color(7) failed: {}", err),
+    }
+
+    println!("[Rust] Sorting a Vec in place in C..");
+
+    let mut values = vec![5, -2, 9, 0, 3];
+    sort::sort_slice_via_c(&mut values);
+    println!("[Rust] sort_ints: {:?}", values);
+
+    let mut values = vec![i32::MAX, 1, i32::MIN, -1];
+    sort::sort_slice_with_qsort(&mut values);
+    println!("[Rust] qsort with a Rust comparator: {:?}", values);
+
+    println!("[Rust] Using C's hidden global state..");
+
+    let counter = global_state::GlobalCounter::take().expect("counter already taken");
+    for _ in 0..3 {
+        counter.bump();
+    }
+    println!("[Rust] read_global() = {}", counter.read());
+
+    println!("[Rust] Running a slow C function with progress reports..");
+
+    let sum = progress::slow_sum(10_000_000, |percent| {
+        if percent % 25 == 0 {
+            println!("[Rust] slow_sum: {}%", percent);
+        }
+    });
+    println!("[Rust] slow_sum(10000000) = {}", sum);
+
+    let task = progress::CancellableTask::new(1 << 40);
+    let token = task.cancel_token();
+    let worker = thread::spawn(move || task.run());
+    thread::sleep(Duration::from_millis(10));
+    token.cancel();
+    match worker.join().unwrap() {
+        Ok(sum) => println!("[Rust] slow_sum_cancellable finished: {}", sum),
+        Err(err) => println!("[Rust] slow_sum_cancellable: {}", err),
+    }
+
+    println!("[Rust] Passing a struct with an array inside to C..");
+
+    match record::Record::new("Ferris", 1, 99.5) {
+        Ok(ferris) => record::print_record(&ferris),
+        Err(err) => println!("[Rust] Record::new failed: {}", err),
+    }
+    let from_c = record::make_record(c"Corro", 2, 87.25);
+    println!("[Rust] Record from C: {:?}", from_c);
+    match record::Record::new(&"x".repeat(100), 3, 0.0) {
+        Ok(record) => println!("[Rust] Record::new succeeded: {:?}", record),
+        Err(err) => println!("[Rust] Record::new failed: {}", err),
+    }
+
+    println!("[Rust] Passing booleans to C..");
+
+    println!(
+        "[Rust] all_positive([1, 2, 3]) = {}",
+        booleans::all_positive(&[1, 2, 3])
+    );
+    println!(
+        "[Rust] all_positive([1, -2, 3]) = {}",
+        booleans::all_positive(&[1, -2, 3])
+    );
+    let mut flags = [false; 4];
+    booleans::set_flags(&mut flags, true);
+    println!("[Rust] set_flags(true): {:?}", flags);
+
+    println!("[Rust] Initializing a C library with a guard..");
+
+    match library::LibraryGuard::new() {
+        Ok(guard) => {
+            println!(
+                "[Rust] IDs: {}, {}",
+                library::next_id(&guard),
+                library::next_id(&guard)
+            );
+            drop(guard);
+            println!(
+                "[Rust] Initialized after drop: {}",
+                library::is_initialized()
+            );
+        }
+        Err(err) => println!("[Rust] Error: {}", err),
+    }
+
+    println!("[Rust] Calling through a vtable of function pointers..");
+
+    {
+        use vtable::Calc;
+
+        let c = vtable::c_calculator();
+        println!("[Rust] C's vtable: 40 + 2 = {}", c.add(40, 2));
+        let rust = vtable::make_rust_calculator();
+        println!(
+            "[Rust] C calling Rust's vtable: 40 - 2 = {}",
+            vtable::apply_sub(&rust, 40, 2)
+        );
+    }
+
+    println!("[Rust] Sharing a ring buffer's memory with C..");
+
+    match ring_buffer::RingBuffer::new(2) {
+        Ok(mut rb) => {
+            rb.push(1);
+            rb.push(2);
+            println!("[Rust] Pushed by C, full: {}", !rb.push(3));
+            let mut view = rb.view();
+            println!("[Rust] Popped in place by Rust: {:?}", view.pop());
+            view.push(3);
+            println!(
+                "[Rust] Popped by C: {:?}, {:?}, {:?}",
+                rb.pop(),
+                rb.pop(),
+                rb.pop()
+            );
+        }
+        Err(err) => println!("[Rust] RingBuffer::new failed: {}", err),
+    }
+
+    println!("[Rust] Calling multiply() from 4 threads at once..");
+
+    ffi::set_verbose(false);
+    let pairs: Vec<_> = (1..=8).map(|i| (i, i)).collect();
+    println!(
+        "[Rust] Squares: {:?}",
+        threads::multiply_in_parallel(&pairs, 4)
+    );
+    ffi::set_verbose(true);
+
+    println!("[Rust] Getting a pointer that may be NULL from C..");
+
+    let values = [4, 8, 15, 16, 23, 42];
+    for target in [15, 7] {
+        match nullable::find_first(&values, target) {
+            Some(index) => println!("[Rust] find_first({}) = index {}", target, index),
+            None => println!("[Rust] find_first({}) = not found", target),
+        }
+    }
+
+    let cfg = nullable::Config {
+        retries: 1,
+        timeout_ms: 250,
+    };
+    println!(
+        "[Rust] configure(Some(&cfg)) = {} ms",
+        nullable::configure(Some(&cfg))
+    );
+    println!("[Rust] configure(None) = {} ms", nullable::configure(None));
+}
@@ -0,0 +1,142 @@
+//! Tests for the demo binary's command-line handling.
+
+use std::process::Command;
+
+use rust_ffi_to_c::calculate::Op;
+use rust_ffi_to_c::cli::{parse_args, ArgError, Args, DEFAULT_OPERANDS};
+
+fn args<'a>(args: &'a [&str]) -> impl Iterator<Item = String> + 'a {
+    args.iter().map(|arg| arg.to_string())
+}
+
+#[test]
+fn no_args_uses_defaults() {
+    assert_eq!(
+        parse_args(args(&[])),
+        Ok(Args {
+            op: Op::Mul,
+            operands: DEFAULT_OPERANDS
+        })
+    );
+}
+
+#[test]
+fn two_operands() {
+    let operands = |list| parse_args(args(list)).map(|args| args.operands);
+
+    assert_eq!(operands(&["12", "34"]), Ok((12, 34)));
+    assert_eq!(operands(&["-12", " 34 "]), Ok((-12, 34)));
+}
+
+#[test]
+fn op_flag() {
+    let op = |list| parse_args(args(list)).map(|args| args.op);
+
+    assert_eq!(op(&["--op", "add", "1", "2"]), Ok(Op::Add));
+    assert_eq!(op(&["1", "2", "--op=sub"]), Ok(Op::Sub));
+    assert_eq!(op(&["1", "--op", "/", "2"]), Ok(Op::Div));
+    assert_eq!(op(&["--op", "*"]), Ok(Op::Mul));
+    assert_eq!(
+        parse_args(args(&["--op", "div"])).map(|args| args.operands),
+        Ok(DEFAULT_OPERANDS)
+    );
+}
+
+#[test]
+fn bad_op_flag() {
+    assert_eq!(parse_args(args(&["--op"])), Err(ArgError::MissingOp));
+    assert_eq!(
+        parse_args(args(&["--op", "pow", "1", "2"])),
+        Err(ArgError::UnknownOp("pow".into()))
+    );
+    assert_eq!(
+        parse_args(args(&["--op=%", "1", "2"])),
+        Err(ArgError::UnknownOp("%".into()))
+    );
+}
+
+#[test]
+fn wrong_count() {
+    assert_eq!(parse_args(args(&["12"])), Err(ArgError::WrongCount(1)));
+    assert_eq!(
+        parse_args(args(&["1", "2", "3"])),
+        Err(ArgError::WrongCount(3))
+    );
+}
+
+#[test]
+fn not_a_number() {
+    assert_eq!(
+        parse_args(args(&["12", "abc"])),
+        Err(ArgError::NotANumber("abc".into()))
+    );
+}
+
+#[test]
+fn out_of_range() {
+    assert_eq!(
+        parse_args(args(&["2147483648", "1"])),
+        Err(ArgError::OutOfRange("2147483648".into()))
+    );
+}
+
+fn run(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_demo"))
+        .args(args)
+        .output()
+        .expect("failed to run the binary")
+}
+
+#[test]
+fn binary_multiplies_operands() {
+    let output = run(&["12", "34"]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("[Rust] Result: 408"));
+}
+
+#[test]
+fn binary_applies_op() {
+    let output = run(&["--op", "div", "408", "12"]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("[Rust] Result: 34"));
+}
+
+#[test]
+fn binary_reports_division_by_zero() {
+    let output = run(&["--op", "div", "1", "0"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("division by zero"), "stderr: {}", stderr);
+}
+
+#[test]
+fn binary_rejects_bad_input() {
+    for bad in [
+        &["12", "abc"][..],
+        &["12"],
+        &["99999999999", "1"],
+        &["--op", "pow", "1", "2"],
+    ] {
+        let output = run(bad);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        assert_eq!(output.status.code(), Some(2), "args: {:?}", bad);
+        assert!(stderr.contains("usage:"), "stderr: {}", stderr);
+    }
+}
+
+#[test]
+fn binary_reports_overflow() {
+    let output = run(&["2147483647", "2"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        stderr.contains("does not fit in a C int"),
+        "stderr: {}",
+        stderr
+    );
+}
@@ -0,0 +1,61 @@
+//! Loading the C library at runtime instead of linking it at build time.
+//!
+//! The rest of the crate links `multiply` into the binary: if it is missing,
+//! the program doesn't build. Here the library is opened with `dlopen` (or
+//! `LoadLibrary` on Windows) while the program runs, and `multiply` is looked
+//! up by name. Both can fail, so both return a `Result`, and the program can
+//! decide what to do without the library.
+//!
+//! The build script leaves a shared build of `c_src/multiply.c` in OUT_DIR.
+//! Set MULTIPLY_DYLIB to load a different one:
+//!
+//!     cargo run --example dynamic
+//!     MULTIPLY_DYLIB=/path/to/libmultiply.so cargo run --example dynamic
+
+use core::ffi::c_int;
+use std::env;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use libloading::{Library, Symbol};
+
+/// The signature of `multiply` in `c_src/multiply.h`. Nothing checks it
+/// against the library: a symbol is just an address, so getting it wrong is
+/// undefined behavior and not an error.
+type MultiplyFn = unsafe extern "C" fn(c_int, c_int) -> c_int;
+
+fn library_path() -> PathBuf {
+    env::var_os("MULTIPLY_DYLIB")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            Path::new(env!("OUT_DIR")).join(format!("{}multiply{}", DLL_PREFIX, DLL_SUFFIX))
+        })
+}
+
+fn multiply(path: &Path, a: i32, b: i32) -> Result<i32, libloading::Error> {
+    // SAFETY: loading a library runs its initialization code, and libmultiply
+    // has none.
+    let library = unsafe { Library::new(path) }?;
+
+    // SAFETY: `MultiplyFn` is the signature `multiply` is defined with. The
+    // symbol borrows `library`, so it can't outlive it.
+    let multiply: Symbol<MultiplyFn> = unsafe { library.get(b"multiply") }?;
+
+    // SAFETY: `multiply` takes and returns plain integers.
+    Ok(unsafe { multiply(a, b) })
+}
+
+fn main() {
+    let path = library_path();
+
+    println!("[Rust] Loading {}..", path.display());
+
+    match multiply(&path, 6, 7) {
+        Ok(result) => println!("[Rust] Result: {}", result),
+        Err(err) => {
+            eprintln!("[Rust] Error: {}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// A C program calling into the Rust library.
+//
+// Build the static library, then compile this file against the header in
+// include/ and link it against the library:
+//
+//     cargo build
+//     cc examples/main.c -Iinclude -Ic_src target/debug/librust_ffi_to_c.a \
+//         -lpthread -ldl -lm -o target/main
+//     ./target/main
+
+#include <stdio.h>
+
+#include "rust_exports.h"
+
+int main(void) {
+    printf("[C] Calling rust_callback(21)..\n");
+    fflush(stdout);
+
+    int result = rust_callback(21);
+
+    printf("[C] Result: %d\n", result);
+    return 0;
+}
@@ -0,0 +1,127 @@
+//! The numeric part of libmultiply's API, for `#![no_std]` programs.
+//!
+//! Everything here uses only `core`: the `extern "C"` declarations, the
+//! `c_int` and friends from `core::ffi`, and wrappers that don't allocate or
+//! print from Rust. String wrappers, error types built on `std::error`, and
+//! everything else that needs `std` stay in `rust-ffi-to-c`.
+//!
+//! This is a separate crate rather than a `no_std` feature of
+//! `rust-ffi-to-c`, because that crate is also built as a `staticlib` and a
+//! `cdylib` for C programs, and those need a panic handler, which only `std`
+//! or the final program can provide. An `rlib` doesn't, so this crate can be
+//! `#![no_std]` unconditionally.
+//!
+//! The wrappers take and return `c_int` rather than `i32`. On the usual
+//! targets they are the same, but on some embedded ones, such as AVR, a C
+//! `int` is 16 bits wide.
+//!
+//! This crate only declares the C functions and doesn't link libmultiply:
+//! the program using it does, as `demo/src/bin/no_std_example.rs` shows.
+
+#![no_std]
+#![deny(missing_docs)]
+
+// Every wrapper is `#[inline]`, so it is compiled into the program calling
+// it, and wrappers it doesn't call never reach the linker. Otherwise the
+// `assert!` in `multiply_batch` would link in `core`'s panic machinery, which
+// refers to the unwinding support a `no_std` program doesn't have, even when
+// nothing calls it.
+
+use core::ffi::c_int;
+
+/// The raw declarations, for callers who want to make the calls
+/// themselves.
+pub mod raw {
+    use core::ffi::{c_double, c_float, c_int};
+
+    extern "C" {
+        /// C's `multiply()`. Prints its arguments unless turned off with
+        /// [`multiply_set_verbose`].
+        pub fn multiply(a: c_int, b: c_int) -> c_int;
+        /// C's `multiply_f64()`.
+        pub fn multiply_f64(a: c_double, b: c_double) -> c_double;
+        /// C's `multiply_f32()`.
+        pub fn multiply_f32(a: c_float, b: c_float) -> c_float;
+        /// C's `multiply_checked()`: writes the product to `out` and returns
+        /// 0, or returns 1 if it overflows.
+        pub fn multiply_checked(a: c_int, b: c_int, out: *mut c_int) -> c_int;
+        /// C's `multiply_batch()`. `size_t` is `usize` on every target Rust
+        /// supports, see `rust_ffi_to_c::arrays::c_size_t`.
+        pub fn multiply_batch(a: *const c_int, b: *const c_int, out: *mut c_int, n: usize);
+        /// C's `multiply_set_verbose()`.
+        pub fn multiply_set_verbose(enabled: c_int);
+        /// C's `multiply_call_count()`.
+        pub fn multiply_call_count() -> c_int;
+    }
+}
+
+/// Multiplies two integers in C.
+///
+/// Like `rust_ffi_to_c::ffi::multiply`, this wraps around on overflow. Use
+/// [`multiply_checked`] when the product might not fit.
+#[inline]
+pub fn multiply(a: c_int, b: c_int) -> c_int {
+    // SAFETY: `multiply` takes two integers by value and has no pointer
+    // arguments. Its only side effect is printing to stdout.
+    unsafe { raw::multiply(a, b) }
+}
+
+/// Multiplies two integers in C, or returns `None` if the product doesn't
+/// fit in a `c_int`.
+#[inline]
+pub fn multiply_checked(a: c_int, b: c_int) -> Option<c_int> {
+    let mut out = 0;
+
+    // SAFETY: `out` is valid for writing one `c_int`, and C only writes it
+    // when returning 0.
+    let status = unsafe { raw::multiply_checked(a, b, &mut out) };
+
+    (status == 0).then_some(out)
+}
+
+/// Multiplies two `f64`s in C.
+#[inline]
+pub fn multiply_f64(a: f64, b: f64) -> f64 {
+    // SAFETY: `multiply_f64` takes and returns plain values.
+    unsafe { raw::multiply_f64(a, b) }
+}
+
+/// Multiplies two `f32`s in C.
+#[inline]
+pub fn multiply_f32(a: f32, b: f32) -> f32 {
+    // SAFETY: `multiply_f32` takes and returns plain values.
+    unsafe { raw::multiply_f32(a, b) }
+}
+
+/// Multiplies `a` and `b` element by element in C, writing the products to
+/// `out`. Products wrap around on overflow.
+///
+/// # Panics
+///
+/// Panics if the slices don't all have the same length.
+#[inline]
+pub fn multiply_batch(a: &[c_int], b: &[c_int], out: &mut [c_int]) {
+    assert!(
+        a.len() == out.len() && b.len() == out.len(),
+        "multiply_batch needs slices of the same length"
+    );
+
+    // SAFETY: `a` and `b` are valid for reading, and `out` for writing,
+    // `out.len()` integers. `out` is borrowed mutably, so it can't overlap
+    // the other two.
+    unsafe { raw::multiply_batch(a.as_ptr(), b.as_ptr(), out.as_mut_ptr(), out.len()) }
+}
+
+/// Turns the printing in [`multiply`] on (the default) or off.
+#[inline]
+pub fn set_verbose(enabled: bool) {
+    // SAFETY: `multiply_set_verbose` takes a plain integer.
+    unsafe { raw::multiply_set_verbose(c_int::from(enabled)) }
+}
+
+/// How many times C's `multiply()` has been called.
+#[inline]
+pub fn call_count() -> c_int {
+    // SAFETY: `multiply_call_count` has no preconditions.
+    unsafe { raw::multiply_call_count() }
+}
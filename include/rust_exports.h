@@ -0,0 +1,123 @@
+#ifndef RUST_EXPORTS_H
+#define RUST_EXPORTS_H
+
+/* Generated by cbindgen from src/exported.rs. Do not edit: run `cargo xtask gen-header`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include "multiply.h"
+
+/**
+ * Returned by [`rust_multiply_checked`] on success.
+ */
+#define RUST_MULTIPLY_OK 0
+
+/**
+ * Returned by [`rust_multiply_checked`] when the product doesn't fit.
+ */
+#define RUST_MULTIPLY_OVERFLOW 1
+
+/**
+ * Returned by [`rust_multiply_checked`] when `out` is NULL.
+ */
+#define RUST_MULTIPLY_NULL_POINTER 2
+
+/**
+ * Returned by [`rust_divide`] on success.
+ */
+#define RUST_DIVIDE_OK 0
+
+/**
+ * Returned by [`rust_divide`] when `out` is NULL.
+ */
+#define RUST_DIVIDE_NULL_POINTER 1
+
+/**
+ * Returned by [`rust_divide`] when the division panicked.
+ */
+#define RUST_DIVIDE_PANICKED 2
+
+/**
+ * Doubles its argument.
+ *
+ * C can call this by name, or receive it as a function pointer (see
+ * `callbacks::call_callback`).
+ */
+int rust_callback(int x);
+
+/**
+ * Adds two integers, wrapping on overflow.
+ *
+ * C calls this by name from `compute_with_rust()` in `c_src/compute.c`.
+ *
+ * The addition can't panic, but the body is wrapped in
+ * [`ffi_safe!`](crate::ffi_safe) like any exported function should be, so a
+ * later change that can panic doesn't unwind into C. Every `int` is a valid
+ * sum, so the sentinel, 0, can't be told apart from a real result;
+ * functions that can fail should return a status code instead, like
+ * [`rust_multiply_checked`].
+ */
+int rust_add(int a, int b);
+
+/**
+ * Multiplies two integers, writing the product to `out`.
+ *
+ * Returns an error code instead of panicking, since a panic can't unwind
+ * into C: [`RUST_MULTIPLY_OK`], [`RUST_MULTIPLY_OVERFLOW`] or
+ * [`RUST_MULTIPLY_NULL_POINTER`]. `out` is only written on success.
+ *
+ * # Safety
+ *
+ * `out` must be NULL or valid for writing one `int`.
+ */
+int rust_multiply_checked(int a, int b, int *out);
+
+/**
+ * Divides `a` by `b`, writing the quotient to `out`.
+ *
+ * Returns [`RUST_DIVIDE_OK`], [`RUST_DIVIDE_NULL_POINTER`] or
+ * [`RUST_DIVIDE_PANICKED`]. `out` is only written on success.
+ *
+ * Unlike [`rust_multiply_checked`], this doesn't check its input: `a / b`
+ * panics when `b` is 0, or for `i32::MIN / -1`. A panic must never unwind
+ * out of an `extern "C"` function, since the C frames above it have no
+ * unwind information and nothing to run on the way up. That used to be
+ * undefined behavior; since Rust 1.81 the process aborts instead. Either
+ * way, the panic has to be stopped here, with `catch_unwind`, and turned
+ * into something C understands.
+ *
+ * `catch_unwind` requires its closure to be `UnwindSafe`, which a closure
+ * capturing the raw `out` pointer is not. `AssertUnwindSafe` overrides the
+ * check: it is fine here because `out` isn't written until the division
+ * has succeeded, so a panic can't leave it half updated.
+ *
+ * The panic message is still printed to stderr by the panic hook.
+ *
+ * # Safety
+ *
+ * `out` must be NULL or valid for writing one `int`.
+ */
+int rust_divide(int a, int b, int *out);
+
+/**
+ * [`nullable::configure`](crate::nullable::configure) implemented in Rust,
+ * for C to call. Takes the settings as a `Config *`, NULL for the defaults.
+ * Receiving it as an `Option<NonNull<Config>>` means the NULL check can't
+ * be skipped.
+ *
+ * `Config` is defined in `c_src/multiply.h`, which the generated header
+ * includes for it. The pointer isn't `const` in the header, since cbindgen
+ * can't tell that it is only read through.
+ *
+ * The total wraps around if it overflows.
+ *
+ * # Safety
+ *
+ * `cfg` must be NULL or point to a valid `Config` for the duration of the
+ * call.
+ */
+int rust_configure(Config *cfg);
+
+#endif  /* RUST_EXPORTS_H */
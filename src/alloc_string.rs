@@ -0,0 +1,144 @@
+//! Getting a string C allocates through a `char **` out parameter.
+//!
+//! `int get_message(char **out)` returns a status code, and on success
+//! stores a pointer to a new string in `*out`. The caller then owns the
+//! string and must give it back to `free_message()`. On the Rust side the
+//! `char **` is simply a pointer to a local `*mut c_char`: Rust starts it
+//! as NULL, lends C its address, and only looks at it once the status code
+//! says C has written it.
+//!
+//! The string is owned by [`CAllocatedString`], which calls
+//! `free_message()` in `Drop`. It must never be `Copy`: two copies would
+//! both free the same string. It isn't `Clone` either, and a moved-from
+//! value can't be used again:
+//!
+//! ```compile_fail
+//! use rust_ffi_to_c::alloc_string::get_message;
+//!
+//! let a = get_message().unwrap();
+//! let b = a;
+//! println!("{}", a.as_str()); // error: `a` was moved into `b`
+//! ```
+
+use core::ffi::{c_char, c_int};
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::ptr::{self, NonNull};
+use std::str::Utf8Error;
+
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use core::ffi::{c_char, c_int};
+
+    extern "C" {
+        pub fn get_message(out: *mut *mut c_char) -> c_int;
+        pub fn free_message(msg: *mut c_char);
+    }
+}
+
+// Status codes from `c_src/multiply.h`. `GET_MESSAGE_NULL_POINTER` is left
+// out: Rust never passes C a NULL `out`.
+const GET_MESSAGE_OK: c_int = 0;
+const GET_MESSAGE_NO_MEMORY: c_int = 2;
+
+/// Error returned by [`get_message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageError {
+    /// C failed to allocate the string.
+    OutOfMemory,
+    /// C reported success but left the pointer NULL.
+    NullMessage,
+    /// The string C returned isn't valid UTF-8. It has already been freed.
+    InvalidUtf8(Utf8Error),
+    /// C returned a status code this wrapper doesn't expect.
+    Unknown(i32),
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::OutOfMemory => write!(f, "C failed to allocate the message"),
+            MessageError::NullMessage => write!(f, "C returned a NULL message"),
+            MessageError::InvalidUtf8(err) => write!(f, "message is not valid UTF-8: {}", err),
+            MessageError::Unknown(status) => write!(f, "unknown status code {}", status),
+        }
+    }
+}
+
+impl Error for MessageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MessageError::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A string allocated by `get_message()`, freed with `free_message()` when
+/// dropped.
+///
+/// The contents were checked to be UTF-8 when it was created, so
+/// [`as_str`](Self::as_str) can't fail.
+pub struct CAllocatedString(NonNull<c_char>);
+
+// SAFETY: the string is plain heap memory owned by this value, which only
+// ever reads it, so borrowing it from several threads is fine, and
+// `free_message()` may free it on any thread.
+unsafe impl Send for CAllocatedString {}
+unsafe impl Sync for CAllocatedString {}
+
+impl CAllocatedString {
+    /// The string, without its NUL terminator.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the pointer is a NUL-terminated string that we own until
+        // `drop`, which the returned borrow can't outlast.
+        let bytes = unsafe { CStr::from_ptr(self.0.as_ptr()) }.to_bytes();
+
+        // SAFETY: `get_message` checked the bytes, and nothing has written
+        // to them since.
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+}
+
+impl Drop for CAllocatedString {
+    fn drop(&mut self) {
+        // SAFETY: the string came from `get_message()`, we own it, and
+        // `drop` runs once.
+        unsafe { raw::free_message(self.0.as_ptr()) }
+    }
+}
+
+impl fmt::Debug for CAllocatedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CAllocatedString")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+/// Asks C for its message.
+pub fn get_message() -> Result<CAllocatedString, MessageError> {
+    let mut out: *mut c_char = ptr::null_mut();
+
+    // SAFETY: `&mut out` is valid for writing one pointer and outlives the
+    // call.
+    let status = unsafe { raw::get_message(&mut out) };
+
+    match status {
+        GET_MESSAGE_OK => {}
+        GET_MESSAGE_NO_MEMORY => return Err(MessageError::OutOfMemory),
+        other => return Err(MessageError::Unknown(other)),
+    }
+
+    // Wrap it straight away, so it is freed even if the check below fails.
+    let message = CAllocatedString(out.owned_or(MessageError::NullMessage)?);
+
+    // SAFETY: C returned GET_MESSAGE_OK, so `out` is a NUL-terminated
+    // string, owned by `message`, which outlives this borrow.
+    let bytes = unsafe { CStr::from_ptr(out) }.to_bytes();
+    std::str::from_utf8(bytes).map_err(MessageError::InvalidUtf8)?;
+
+    Ok(message)
+}
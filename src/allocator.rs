@@ -0,0 +1,113 @@
+//! Having C allocate through Rust's allocator.
+//!
+//! Every C function in this crate that hands out memory for a matching C
+//! function to free, like `make_range()` and `free_range()` or
+//! `counter_new()` and `counter_free()`, allocates it with `lib_alloc()`
+//! and frees it with `lib_free()`. Those use `malloc()` and `free()` until
+//! `set_allocator()` gives them two other functions to call.
+//! [`install_rust_allocator`] gives them [`std::alloc::alloc`] and
+//! [`std::alloc::dealloc`], so the memory behind a [`CIntBuffer`] or a
+//! [`Counter`] comes from, and goes back to, whatever `#[global_allocator]`
+//! the program uses, and shows up in its statistics.
+//!
+//! [`CIntBuffer`]: crate::range::CIntBuffer
+//! [`Counter`]: crate::counter::Counter
+//!
+//! ```
+//! use rust_ffi_to_c::{allocator, range};
+//!
+//! allocator::install_rust_allocator();
+//!
+//! // Allocated by Rust's allocator on C's behalf, and freed by it on drop.
+//! let values = range::make_range(1, 3).unwrap();
+//! assert_eq!(*values, [1, 2, 3]);
+//! ```
+//!
+//! # Sizes and alignment
+//!
+//! Rust's `dealloc` needs the same [`Layout`] that `alloc` was given, but
+//! `free_range()` is only given a pointer. So `lib_alloc()` puts a header
+//! in front of every allocation, holding its size and the `dealloc` it
+//! needs. The size is passed back to Rust's `dealloc` hook, which rebuilds
+//! the layout from it, and the `dealloc` keeps memory allocated by `malloc()`
+//! before the hooks were installed from ever reaching Rust's allocator. The
+//! alignment is always [`ALIGN`], since C expects any allocation to be
+//! aligned like `malloc()`'s.
+//!
+//! The hooks can only be installed once: switching to yet another allocator
+//! later would gain nothing, since the memory already handed out has to be
+//! freed by the one that allocated it anyway.
+
+use core::ffi::{c_int, c_void};
+use std::alloc::{self, Layout};
+use std::sync::Once;
+
+/// The alignment of every allocation made for C, at least that of C's
+/// `max_align_t` on every common platform.
+pub const ALIGN: usize = 16;
+
+const SET_ALLOCATOR_OK: c_int = 0;
+const SET_ALLOCATOR_ALREADY_SET: c_int = 2;
+
+mod raw {
+    use core::ffi::{c_int, c_void};
+
+    extern "C" {
+        pub fn set_allocator(
+            alloc: Option<unsafe extern "C" fn(usize) -> *mut c_void>,
+            dealloc: Option<unsafe extern "C" fn(*mut c_void, usize)>,
+        ) -> c_int;
+    }
+}
+
+// Allocates `size` bytes for `lib_alloc()`, aligned to `ALIGN`. Returns
+// NULL if `size` is 0 or the allocation fails, like `malloc()` may.
+extern "C" fn rust_alloc(size: usize) -> *mut c_void {
+    let Ok(layout) = Layout::from_size_align(size, ALIGN) else {
+        return core::ptr::null_mut();
+    };
+    if size == 0 {
+        return core::ptr::null_mut();
+    }
+
+    // SAFETY: the layout has a non-zero size.
+    unsafe { alloc::alloc(layout) }.cast()
+}
+
+// Frees memory from `rust_alloc` for `lib_free()`, which passes the size it
+// allocated with. `ptr` must not have been freed yet.
+unsafe extern "C" fn rust_dealloc(ptr: *mut c_void, size: usize) {
+    // SAFETY: `rust_alloc` only returns memory for a size and alignment
+    // that made a valid layout, and `lib_free()` passes the same size.
+    let layout = unsafe { Layout::from_size_align_unchecked(size, ALIGN) };
+
+    // SAFETY: `lib_free()` only passes pointers `rust_alloc` returned, once
+    // each, so `ptr` was allocated with this layout and still is.
+    unsafe { alloc::dealloc(ptr.cast(), layout) }
+}
+
+/// Makes the C library allocate through Rust's global allocator.
+///
+/// Only the first call installs the hooks; later calls do nothing.
+/// Memory C allocated before the first call is still freed with `free()`.
+///
+/// # Panics
+///
+/// Panics if something other than this function already installed
+/// allocator hooks in C.
+pub fn install_rust_allocator() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        // SAFETY: the two functions are a matching pair that can be called
+        // from any thread, and `lib_free()` passes `rust_dealloc` the size
+        // it gave `rust_alloc`.
+        let status = unsafe { raw::set_allocator(Some(rust_alloc), Some(rust_dealloc)) };
+
+        match status {
+            SET_ALLOCATOR_OK => {}
+            SET_ALLOCATOR_ALREADY_SET => panic!("C's allocator hooks were already set"),
+            status => panic!("set_allocator() failed with status {}", status),
+        }
+    });
+}
@@ -0,0 +1,106 @@
+//! Translating C error conventions into Rust `Result`s.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::mem::MaybeUninit;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn divide(a: c_int, b: c_int, out: *mut c_int) -> c_int;
+        pub fn divide_errno(a: c_int, b: c_int) -> c_int;
+        pub fn clear_errno();
+    }
+}
+
+// Status codes returned by `divide()` in `c_src/divide.c`.
+const DIVIDE_OK: c_int = 0;
+const DIVIDE_BY_ZERO: c_int = 1;
+const DIVIDE_OVERFLOW: c_int = 2;
+
+/// Error returned by [`divide`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivideError {
+    /// The divisor was zero.
+    DivisionByZero,
+    /// The quotient doesn't fit in an `i32` (`i32::MIN / -1`).
+    Overflow,
+    /// C returned a status code we don't know about.
+    Unknown(i32),
+}
+
+impl fmt::Display for DivideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DivideError::DivisionByZero => write!(f, "division by zero"),
+            DivideError::Overflow => write!(f, "division overflowed i32"),
+            DivideError::Unknown(code) => write!(f, "unknown status code {}", code),
+        }
+    }
+}
+
+impl Error for DivideError {}
+
+/// Divides `a` by `b` in C.
+///
+/// The C function reports errors through its return value and writes the
+/// quotient through an out pointer, which it only does on success.
+pub fn divide(a: i32, b: i32) -> Result<i32, DivideError> {
+    // C may not write to `out` at all, so it starts out uninitialized rather
+    // than with a made-up value that could be mistaken for a result.
+    let mut out = MaybeUninit::<c_int>::uninit();
+
+    // SAFETY: `out` is valid for writing one `c_int`, which is all `divide`
+    // writes, and it outlives the call.
+    let status = unsafe { raw::divide(a, b, out.as_mut_ptr()) };
+
+    match status {
+        // SAFETY: on DIVIDE_OK, C has written the quotient to `out`.
+        DIVIDE_OK => Ok(unsafe { out.assume_init() }),
+        DIVIDE_BY_ZERO => Err(DivideError::DivisionByZero),
+        DIVIDE_OVERFLOW => Err(DivideError::Overflow),
+        code => Err(DivideError::Unknown(code)),
+    }
+}
+
+/// Divides `a` by `b` in C, using the `errno` convention.
+///
+/// The C function returns `INT_MIN` and sets `errno` on failure. Since
+/// `INT_MIN` is also a valid quotient, only `errno` can tell the two apart,
+/// which means it has to be cleared first: it is never reset on success, so
+/// it may still hold an error from some earlier, unrelated call.
+///
+/// Errors have [`io::ErrorKind::InvalidInput`] and wrap the OS error C
+/// reported (`EDOM` for a zero divisor, `ERANGE` for `i32::MIN / -1`).
+pub fn divide_errno(a: i32, b: i32) -> io::Result<i32> {
+    // `errno` is thread-local, so nothing on another thread can change it
+    // between these two calls.
+    clear_errno();
+
+    // SAFETY: `divide_errno` takes and returns plain integers.
+    let result = unsafe { raw::divide_errno(a, b) };
+
+    if result != c_int::MIN {
+        return Ok(result);
+    }
+
+    // Read errno right away, before anything else gets a chance to set it.
+    let os_error = io::Error::last_os_error();
+
+    match os_error.raw_os_error() {
+        Some(0) | None => Ok(result),
+        Some(_) => Err(io::Error::new(io::ErrorKind::InvalidInput, os_error)),
+    }
+}
+
+/// Resets `errno` to 0 on the current thread.
+///
+/// C functions never clear `errno` on success, so this has to be called
+/// before any call whose `errno` we want to look at.
+pub(crate) fn clear_errno() {
+    // SAFETY: `clear_errno` has no preconditions.
+    unsafe { raw::clear_errno() }
+}
@@ -0,0 +1,79 @@
+//! Passing Rust slices to C as a pointer and a length.
+
+/// C's `size_t`.
+///
+/// Rust maps `size_t` to `usize` on every target it supports (the `libc`
+/// crate does the same), but `core::ffi::c_size_t` is not stable yet. Naming
+/// the type keeps that assumption in one place, and `c_src/arrays.c` checks
+/// the C side of it with a `_Static_assert`.
+#[allow(non_camel_case_types)]
+pub type c_size_t = usize;
+
+mod raw {
+    use super::c_size_t;
+    use core::ffi::{c_int, c_longlong};
+
+    extern "C" {
+        pub fn sum_array(data: *const c_int, len: c_size_t) -> c_longlong;
+        pub fn reverse_array(arr: *mut c_int, len: c_size_t);
+    }
+}
+
+/// Returns the sum of `values`, computed in C.
+///
+/// The slice is passed without copying: C gets a pointer to its first
+/// element and its length. For an empty slice, `as_ptr()` returns a
+/// non-null, well-aligned pointer that doesn't point to anything, which is
+/// fine because C never reads from it when the length is 0.
+pub fn sum(values: &[i32]) -> i64 {
+    let len: c_size_t = values.len();
+
+    // SAFETY: `values.as_ptr()` is valid for reading `len` integers for the
+    // duration of the call, and C reads no further than that.
+    unsafe { raw::sum_array(values.as_ptr(), len) }
+}
+
+/// Returns the sum of `values` as an `i32`, computed in C. The sum wraps
+/// around on overflow.
+///
+/// This is [`sum`] narrowed to the `int` the C function returned before it
+/// was widened to `long long`. Truncating the wide sum gives the same result
+/// as adding with `i32::wrapping_add`.
+pub fn sum_array(values: &[i32]) -> i32 {
+    sum(values) as i32
+}
+
+/// Returns the sum of `s` as an `i32`, computed in C.
+///
+/// # Panics
+///
+/// Panics if `s` is empty, like [`reverse_slice`], or if the sum doesn't fit
+/// in an `i32`, like `s.iter().sum::<i32>()` does in debug builds.
+pub fn sum_slice(s: &[i32]) -> i32 {
+    assert!(!s.is_empty(), "sum_slice called with an empty slice");
+
+    i32::try_from(sum(s)).expect("sum of slice overflowed i32")
+}
+
+/// Reverses `s` in place, in C.
+///
+/// C receives a `*mut` pointer, so the slice has to be borrowed mutably:
+/// a shared `&[i32]` would let C write to memory Rust thinks is immutable.
+///
+/// # Panics
+///
+/// Panics if `s` is empty. `reverse_array` returns early when `len` is 0,
+/// but a C function that computed `len - 1` without that check would read
+/// and write far out of bounds. The wrappers in this module don't rely on C
+/// getting it right: they check the edge case in Rust, where a broken
+/// precondition becomes a panic rather than undefined behavior.
+pub fn reverse_slice(s: &mut [i32]) {
+    assert!(!s.is_empty(), "reverse_slice called with an empty slice");
+
+    let len: c_size_t = s.len();
+
+    // SAFETY: `s.as_mut_ptr()` is valid for reading and writing `len > 0`
+    // integers, and the exclusive borrow means nothing else can observe the
+    // slice while C modifies it.
+    unsafe { raw::reverse_array(s.as_mut_ptr(), len) }
+}
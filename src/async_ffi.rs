@@ -0,0 +1,85 @@
+//! Calling blocking C functions from async code.
+//!
+//! An async task runs on one of a few executor threads, shared with every
+//! other task. A C function that blocks, by sleeping, waiting on I/O or just
+//! computing for a long time, holds its thread the whole time. Every task
+//! queued behind it waits too. `tokio::task::spawn_blocking` moves the call
+//! onto a thread pool meant for that, and gives back a future that
+//! completes when the call does.
+//!
+//! Only built with the `tokio` feature.
+//!
+//! # Cancellation
+//!
+//! Dropping a future is how async code cancels it, for example when a
+//! `tokio::time::timeout` runs out or a `select!` picks another branch. A
+//! C function on a blocking thread doesn't notice: nothing can stop it from
+//! outside. [`run_cancellable`] passes C a cancellation flag, the
+//! `CancellableTask` from `crate::progress`, and sets it when the future is
+//! dropped, so C stops at its next check instead of running to the end for
+//! nobody.
+//!
+//! ```
+//! # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+//! use std::time::Duration;
+//! use rust_ffi_to_c::async_ffi;
+//! use rust_ffi_to_c::progress::CancellableTask;
+//!
+//! // Would take many minutes to finish.
+//! let task = CancellableTask::new(1 << 40);
+//! let token = task.cancel_token();
+//!
+//! let future = async_ffi::run_cancellable(task);
+//! let result = tokio::time::timeout(Duration::from_millis(10), future).await;
+//!
+//! assert!(result.is_err());
+//! assert!(token.is_cancelled());
+//! # });
+//! ```
+
+use std::panic;
+
+use tokio::task::{self, JoinError};
+
+use crate::progress::{CancelToken, CancellableTask, TaskError};
+use crate::safe::{self, OverflowError};
+
+/// Multiplies two integers in C on a blocking thread, refusing inputs that
+/// would overflow, like [`safe::safe_multiply`].
+pub async fn multiply_async(a: i32, b: i32) -> Result<i32, OverflowError> {
+    task::spawn_blocking(move || safe::safe_multiply(a, b))
+        .await
+        .map_err(|_| OverflowError::ThreadPanicked)?
+}
+
+// Cancels the task when dropped, which happens either after it has finished,
+// when cancelling does nothing, or when the future awaiting it is dropped.
+struct CancelOnDrop(CancelToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Runs `task` on a blocking thread, and cancels it if the returned future
+/// is dropped before it finishes.
+///
+/// A panic on the blocking thread is resumed here. The task is also
+/// reported as [`TaskError::Cancelled`] when the runtime shuts down before
+/// it starts.
+pub async fn run_cancellable(task: CancellableTask) -> Result<i64, TaskError> {
+    let _cancel = CancelOnDrop(task.cancel_token());
+
+    match task::spawn_blocking(move || task.run()).await {
+        Ok(result) => result,
+        Err(err) => Err(join_error(err)),
+    }
+}
+
+fn join_error(err: JoinError) -> TaskError {
+    match err.try_into_panic() {
+        Ok(payload) => panic::resume_unwind(payload),
+        Err(_) => TaskError::Cancelled,
+    }
+}
@@ -0,0 +1,18 @@
+//! Declarations generated by bindgen from `c_src/multiply.h` at build time.
+//!
+//! Only built with the `bindgen` feature. The generated file lives in
+//! OUT_DIR, so it is never checked in.
+//!
+//! The header declares everything in `c_src/`, but only `crate::ffi` uses
+//! these declarations. The other modules define their own Rust types for the
+//! C structs, with derives and documentation bindgen doesn't generate.
+
+#![allow(
+    dead_code,
+    missing_docs,
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals
+)]
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
@@ -0,0 +1,54 @@
+//! Passing booleans to and from C.
+//!
+//! Rust's `bool` and C99's `_Bool` (`bool` with `<stdbool.h>`) are
+//! guaranteed to match: both are one byte, hold 0 for false and 1 for true,
+//! and are passed and returned the same way. So `bool` can be used directly
+//! in `extern "C"` signatures, behind pointers, and as `#[repr(C)]` struct
+//! fields, wherever the C side says `_Bool`.
+//!
+//! It is not a match for C's other way of spelling a boolean, an `int` that
+//! is zero or not. `int` is usually four bytes, and "true" can be any
+//! non-zero value, while a Rust `bool` holding anything but 0 or 1 is
+//! undefined behavior. A C function returning `int` for yes or no has to be
+//! declared as returning `c_int` and compared with 0 on the Rust side, like
+//! `library_is_initialized()` in `crate::library`. The same goes for C code
+//! that writes a `_Bool` through a `char *` or `memcpy`, which can store
+//! values `_Bool` itself never would.
+//!
+//! Safe Rust can't produce a `bool` that isn't 0 or 1, so nothing passed to
+//! C through these wrappers can be one either. Integers don't even convert
+//! to `bool` with `as`:
+//!
+//! ```compile_fail
+//! let flag = 2u8 as bool;
+//! ```
+//!
+//! Going the other way, C's `_Bool` only ever holds 0 or 1, because
+//! assigning anything to it converts the value first.
+
+use crate::arrays::c_size_t;
+
+mod raw {
+    use super::c_size_t;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn all_positive(arr: *const c_int, len: c_size_t) -> bool;
+        pub fn set_flags(flags: *mut bool, len: c_size_t, value: bool);
+    }
+}
+
+/// Whether every element of `s` is positive, checked in C. True for an
+/// empty slice.
+pub fn all_positive(s: &[i32]) -> bool {
+    // SAFETY: `s` is valid for reading `s.len()` integers. C returns a
+    // `_Bool`, which is always 0 or 1.
+    unsafe { raw::all_positive(s.as_ptr(), s.len()) }
+}
+
+/// Sets every element of `s` to `value`, in C.
+pub fn set_flags(s: &mut [bool], value: bool) {
+    // SAFETY: `s` is valid for writing `s.len()` `_Bool`s, which have the
+    // layout of `bool`, and C only ever stores 0 or 1 in them.
+    unsafe { raw::set_flags(s.as_mut_ptr(), s.len(), value) }
+}
@@ -0,0 +1,82 @@
+//! Returning a tagged "result struct" from C by value.
+//!
+//! C has no `Result`, but a struct can carry the same information:
+//!
+//! ```text
+//! typedef struct {
+//!     int tag;      // CRESULT_OK or CRESULT_ERR
+//!     int value;    // valid when tag is CRESULT_OK
+//!     int errcode;  // a CALC_ERR_* code when tag is CRESULT_ERR
+//! } CResult;
+//! ```
+//!
+//! Returned by value, it needs no out pointer and no allocation. The tag is
+//! a plain `int` rather than a Rust enum, for the same reason as in
+//! `crate::status`: C can put any integer there, and a `#[repr(C)]` enum
+//! field holding something that isn't a variant is undefined behavior. The
+//! `From` impl turns a tag it doesn't know into [`CalcError::Corrupt`]
+//! instead of panicking.
+
+use core::ffi::c_int;
+use std::mem::{align_of, offset_of, size_of};
+
+use crate::calculate::CalcError;
+
+mod raw {
+    use super::CResult;
+    use crate::arrays::c_size_t;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn try_multiply(a: c_int, b: c_int) -> CResult;
+        pub fn c_result_size() -> c_size_t;
+    }
+}
+
+/// `CRESULT_OK` from `c_src/multiply.h`: `value` holds the result.
+pub const CRESULT_OK: c_int = 0;
+/// `CRESULT_ERR` from `c_src/multiply.h`: `errcode` says what went wrong.
+pub const CRESULT_ERR: c_int = 1;
+
+/// The `CResult` struct from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CResult {
+    /// [`CRESULT_OK`] or [`CRESULT_ERR`], or anything else if C is buggy.
+    pub tag: c_int,
+    /// The result, when `tag` is [`CRESULT_OK`].
+    pub value: c_int,
+    /// One of the `CALC_ERR_*` codes, when `tag` is [`CRESULT_ERR`].
+    pub errcode: c_int,
+}
+
+// Three `int`s with no padding, as the `_Static_assert` in c_src/c_result.c
+// checks on the C side.
+const _: () = assert!(size_of::<CResult>() == 3 * size_of::<c_int>());
+const _: () = assert!(align_of::<CResult>() == align_of::<c_int>());
+const _: () = assert!(offset_of!(CResult, value) == size_of::<c_int>());
+const _: () = assert!(offset_of!(CResult, errcode) == 2 * size_of::<c_int>());
+
+impl From<CResult> for Result<i32, CalcError> {
+    fn from(result: CResult) -> Self {
+        match result.tag {
+            CRESULT_OK => Ok(result.value),
+            CRESULT_ERR => Err(CalcError::from_status(result.errcode)),
+            tag => Err(CalcError::Corrupt(tag)),
+        }
+    }
+}
+
+/// Multiplies `a` and `b` in C, which reports overflow in the returned
+/// struct.
+pub fn try_multiply(a: i32, b: i32) -> Result<i32, CalcError> {
+    // SAFETY: `try_multiply` takes plain integers and returns a struct by
+    // value, whose fields are all valid for any bit pattern.
+    unsafe { raw::try_multiply(a, b) }.into()
+}
+
+/// The size of `CResult` as C sees it, `sizeof(CResult)`.
+pub fn c_size() -> usize {
+    // SAFETY: `c_result_size` takes no arguments and only returns a value.
+    unsafe { raw::c_result_size() }
+}
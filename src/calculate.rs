@@ -0,0 +1,153 @@
+//! Passing a `#[repr(C)]` enum to C to select an operation.
+//!
+//! Going into C, an [`Op`] is always one of its variants, so passing it is
+//! straightforward. Coming out of C is the dangerous direction, as in
+//! `crate::status`: C may hand back any integer, and turning one that isn't a
+//! variant into an `Op` (with `transmute` or a pointer cast) is undefined
+//! behavior. [`Op::try_from`] checks the value first.
+//!
+//! The raw declaration takes the operation as a `c_int` rather than an `Op`,
+//! which lets [`calculate_raw`] pass C values no `Op` can hold, to see C
+//! reject them.
+
+use core::ffi::{c_char, c_int};
+use std::error::Error;
+use std::fmt;
+use std::mem::MaybeUninit;
+
+mod raw {
+    use core::ffi::{c_char, c_int};
+
+    extern "C" {
+        pub fn calculate(op: c_int, a: c_int, b: c_int, out: *mut c_int) -> c_int;
+        pub fn op_from_symbol(symbol: c_char) -> c_int;
+    }
+}
+
+// Status codes from `c_src/multiply.h`.
+const CALC_OK: c_int = 0;
+const CALC_ERR_DIVIDE_BY_ZERO: c_int = 1;
+const CALC_ERR_UNKNOWN_OP: c_int = 2;
+const CALC_ERR_OVERFLOW: c_int = 3;
+
+/// The `Op` enum from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `OP_ADD`, `a + b`.
+    Add = 0,
+    /// `OP_SUB`, `a - b`.
+    Sub = 1,
+    /// `OP_MUL`, `a * b`.
+    Mul = 2,
+    /// `OP_DIV`, `a / b`, rounding towards zero.
+    Div = 3,
+}
+
+/// Error returned when an integer isn't an [`Op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownOp(pub c_int);
+
+impl fmt::Display for UnknownOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown operation {}", self.0)
+    }
+}
+
+impl Error for UnknownOp {}
+
+impl TryFrom<c_int> for Op {
+    type Error = UnknownOp;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Op::Add),
+            1 => Ok(Op::Sub),
+            2 => Ok(Op::Mul),
+            3 => Ok(Op::Div),
+            other => Err(UnknownOp(other)),
+        }
+    }
+}
+
+impl Op {
+    /// Asks C which operation `symbol` (`+`, `-`, `*` or `/`) stands for.
+    ///
+    /// Returns `None` for anything else, including characters that aren't
+    /// ASCII, which a C `char` can't hold.
+    pub fn from_symbol(symbol: char) -> Option<Op> {
+        let symbol = u8::try_from(symbol).ok().filter(u8::is_ascii)?;
+
+        // SAFETY: `op_from_symbol` takes and returns plain integers.
+        let op = unsafe { raw::op_from_symbol(symbol as c_char) };
+
+        // C returns -1 for unknown symbols, and could in principle return
+        // anything else; only a valid discriminant becomes an `Op`.
+        Op::try_from(op).ok()
+    }
+}
+
+/// Error returned by [`calculate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcError {
+    /// `b` was zero for [`Op::Div`].
+    DivisionByZero,
+    /// C didn't recognize the operation.
+    UnknownOp,
+    /// The result doesn't fit in an `i32`.
+    Overflow,
+    /// C returned a status code that isn't in `multiply.h`.
+    Unknown(i32),
+    /// C returned a `CResult` whose tag is neither `CRESULT_OK` nor
+    /// `CRESULT_ERR`, see `crate::c_result`.
+    Corrupt(i32),
+}
+
+impl CalcError {
+    // Maps one of the `CALC_ERR_*` codes to an error. `CALC_OK` isn't one,
+    // and comes out as `Unknown(0)`.
+    pub(crate) fn from_status(status: c_int) -> CalcError {
+        match status {
+            CALC_ERR_DIVIDE_BY_ZERO => CalcError::DivisionByZero,
+            CALC_ERR_UNKNOWN_OP => CalcError::UnknownOp,
+            CALC_ERR_OVERFLOW => CalcError::Overflow,
+            other => CalcError::Unknown(other),
+        }
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::UnknownOp => write!(f, "unknown operation"),
+            CalcError::Overflow => write!(f, "result overflowed i32"),
+            CalcError::Unknown(status) => write!(f, "unknown status code {}", status),
+            CalcError::Corrupt(tag) => write!(f, "result has unknown tag {}", tag),
+        }
+    }
+}
+
+impl Error for CalcError {}
+
+/// Applies `op` to `a` and `b` in C.
+pub fn calculate(op: Op, a: i32, b: i32) -> Result<i32, CalcError> {
+    calculate_raw(op as c_int, a, b)
+}
+
+/// Like [`calculate`], but passes the operation to C as a plain integer.
+///
+/// Any value is safe to pass: C checks it and reports values outside `Op`
+/// as [`CalcError::UnknownOp`].
+pub fn calculate_raw(op: c_int, a: i32, b: i32) -> Result<i32, CalcError> {
+    let mut out = MaybeUninit::<c_int>::uninit();
+
+    // SAFETY: `out` is valid for writing one `c_int`.
+    let status = unsafe { raw::calculate(op, a, b, out.as_mut_ptr()) };
+
+    match status {
+        // SAFETY: C writes `out` before returning `CALC_OK`.
+        CALC_OK => Ok(unsafe { out.assume_init() }),
+        other => Err(CalcError::from_status(other)),
+    }
+}
@@ -0,0 +1,178 @@
+//! Owning an opaque C object through a typed handle.
+//!
+//! `src/context.rs` stores its C object as a `*mut c_void`, which works but
+//! lets any pointer be passed where a context is expected. Here the C type
+//! gets its own Rust type, `RawCalculator`, so the compiler can tell a
+//! calculator apart from any other pointer, and the handle is a `NonNull`,
+//! so a NULL from C can't slip into the wrapper.
+//!
+//! # Threads
+//!
+//! A C calculator is a plain heap object with no global state, so it can be
+//! moved to another thread, and [`Calculator`] is `Send`. Two threads
+//! calling `calculator_accumulate()` on the same one at once is a data race,
+//! though, so it isn't `Sync`: sharing a `&Calculator` between threads is
+//! rejected at compile time.
+//!
+//! ```compile_fail
+//! use std::thread;
+//!
+//! use rust_ffi_to_c::calculator::Calculator;
+//!
+//! let calc = Calculator::new().unwrap();
+//! thread::scope(|s| {
+//!     s.spawn(|| calc.result()); // `Calculator` cannot be shared between threads
+//! });
+//! ```
+//!
+//! [`SharedCalculator`] puts it behind a mutex, so C only ever sees one
+//! thread at a time, and is `Send + Sync`:
+//!
+//! ```
+//! use std::thread;
+//!
+//! use rust_ffi_to_c::calculator::SharedCalculator;
+//!
+//! let calc = SharedCalculator::new().unwrap();
+//! thread::scope(|s| {
+//!     s.spawn(|| calc.accumulate(1));
+//!     s.spawn(|| calc.accumulate(2));
+//! });
+//! assert_eq!(calc.result(), 3);
+//! ```
+
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::marker::{PhantomData, PhantomPinned};
+use std::ptr::NonNull;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::ptr_ext::PtrExt;
+
+/// The C `struct Calculator`, whose fields Rust never sees.
+///
+/// A zero-sized `#[repr(C)]` struct can't be constructed outside this
+/// module, so it can only ever be used behind a pointer. The marker makes it
+/// `!Send`, `!Sync` and `!Unpin`, since we know nothing about what C does
+/// with it. This is the pattern the Rustonomicon recommends for opaque
+/// types.
+#[repr(C)]
+pub struct RawCalculator {
+    _private: [u8; 0],
+    _marker: PhantomData<(*mut u8, PhantomPinned)>,
+}
+
+mod raw {
+    use super::RawCalculator;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn calculator_new() -> *mut RawCalculator;
+        pub fn calculator_accumulate(calc: *mut RawCalculator, value: c_int);
+        pub fn calculator_result(calc: *const RawCalculator) -> c_int;
+        pub fn calculator_free(calc: *mut RawCalculator);
+    }
+}
+
+/// Error returned by [`Calculator::new`] and `Counter::new` when C fails to
+/// allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NullHandleError;
+
+impl fmt::Display for NullHandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "C returned a NULL handle")
+    }
+}
+
+impl Error for NullHandleError {}
+
+/// An owned C calculator, freed when dropped.
+///
+/// `Send` but not `Sync`: see the [module docs](self#threads).
+pub struct Calculator {
+    ptr: NonNull<RawCalculator>,
+    // `Cell<()>` is `Send` but not `Sync`. `NonNull` is neither, so this
+    // changes nothing on its own, but it says what this type is meant to be,
+    // and keeps it that way if the pointer is ever replaced.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+// SAFETY: a C calculator is a heap object only reachable through this
+// handle, and `calculator.c` keeps no global or thread-local state, so any
+// thread may use or free it, as long as only one does at a time. `Calculator`
+// isn't `Sync`, so that is still guaranteed.
+unsafe impl Send for Calculator {}
+
+impl Calculator {
+    /// Creates a calculator with a total of 0.
+    pub fn new() -> Result<Self, NullHandleError> {
+        // SAFETY: `calculator_new` has no preconditions.
+        let ptr = unsafe { raw::calculator_new() };
+
+        let ptr = ptr.owned_or(NullHandleError)?;
+
+        Ok(Calculator {
+            ptr,
+            _not_sync: PhantomData,
+        })
+    }
+
+    /// Adds `value` to the running total. The total wraps around on
+    /// overflow.
+    ///
+    /// C modifies the calculator, so this takes `&mut self`.
+    pub fn accumulate(&mut self, value: i32) {
+        // SAFETY: the handle is valid until `drop`, and `&mut self`
+        // guarantees nobody else is using it.
+        unsafe { raw::calculator_accumulate(self.ptr.as_ptr(), value) }
+    }
+
+    /// Returns the running total.
+    ///
+    /// C takes a `const Calculator *` and only reads, so `&self` is enough.
+    pub fn result(&self) -> i32 {
+        // SAFETY: the handle is valid until `drop`.
+        unsafe { raw::calculator_result(self.ptr.as_ptr()) }
+    }
+}
+
+impl Drop for Calculator {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from `calculator_new`, and `drop` runs
+        // once.
+        unsafe { raw::calculator_free(self.ptr.as_ptr()) }
+    }
+}
+
+/// A [`Calculator`] that can be shared between threads.
+///
+/// Each call locks a mutex around the C calculator, so calls from different
+/// threads take turns instead of racing.
+pub struct SharedCalculator(Mutex<Calculator>);
+
+impl SharedCalculator {
+    /// Creates a calculator with a total of 0.
+    pub fn new() -> Result<Self, NullHandleError> {
+        Calculator::new().map(|calc| SharedCalculator(Mutex::new(calc)))
+    }
+
+    /// Adds `value` to the running total. The total wraps around on
+    /// overflow.
+    pub fn accumulate(&self, value: i32) {
+        self.lock().accumulate(value)
+    }
+
+    /// Returns the running total.
+    pub fn result(&self) -> i32 {
+        self.lock().result()
+    }
+
+    // A panic can't leave the C calculator half-updated, since every C call
+    // either runs to completion or not at all, so a poisoned lock is safe
+    // to keep using.
+    fn lock(&self) -> MutexGuard<'_, Calculator> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
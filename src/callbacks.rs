@@ -0,0 +1,178 @@
+//! Calling Rust from C through function pointers.
+//!
+//! A C function pointer like `int (*)(int)` is an `extern "C" fn(c_int) ->
+//! c_int` in Rust. The `extern "C"` part matters: it makes the Rust function
+//! use the C calling convention, so C knows where to find the arguments and
+//! the return value.
+//!
+//! C function pointers may be NULL, but Rust `fn` pointers never are. An
+//! `Option<extern "C" fn(..)>` has the same size as the pointer, with `None`
+//! represented as NULL, so it is the right type for a nullable callback.
+//!
+//! # Closures
+//!
+//! Only `extern "C" fn` items can be passed to C. Closures can't, not even
+//! ones that capture nothing: a non-capturing closure coerces to a plain
+//! Rust `fn` pointer, which uses the Rust calling convention, and a
+//! capturing closure carries its captured state around with it, which a bare
+//! function pointer has no room for:
+//!
+//! ```compile_fail
+//! let factor = 3;
+//! let callback: extern "C" fn(i32) -> i32 = |x| x * factor;
+//! ```
+//!
+//! [`apply_twice_with`] shows the workaround for C APIs that take nothing but
+//! a function pointer: pass C an `extern "C"` trampoline, and let the
+//! trampoline find the Rust function to call somewhere else. APIs that also
+//! take a `void *` "user data" argument make this much easier, since the
+//! closure can travel through that pointer.
+//!
+//! Variadic callbacks (C functions taking `...` or a `va_list`) are a
+//! separate topic: see `std::ffi::VaList`, which is still unstable.
+
+use core::ffi::{c_int, c_void};
+use std::any::Any;
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+use std::panic::{self, AssertUnwindSafe};
+
+/// The C signature `int (*)(int)`.
+pub type IntCallback = extern "C" fn(c_int) -> c_int;
+
+/// A Rust function or non-capturing closure, for [`apply_twice_with`].
+pub type RustCallback = fn(i32) -> i32;
+
+/// The C signature `void (*)(int value, void *user)`.
+pub type ForEachCallback = extern "C" fn(c_int, *mut c_void);
+
+mod raw {
+    use super::{ForEachCallback, IntCallback};
+    use crate::arrays::c_size_t;
+    use core::ffi::{c_int, c_void};
+
+    extern "C" {
+        pub fn call_callback(callback: Option<IntCallback>, value: c_int, out: *mut c_int)
+            -> c_int;
+        pub fn apply_twice(callback: Option<IntCallback>, value: c_int) -> c_int;
+        pub fn for_each(data: *const c_int, len: c_size_t, cb: ForEachCallback, user: *mut c_void);
+    }
+}
+
+/// Has C call `callback` with `value`.
+///
+/// Returns `None` if `callback` is `None`: C sees a NULL pointer and refuses
+/// to call it.
+pub fn call_callback(callback: Option<IntCallback>, value: i32) -> Option<i32> {
+    let mut out = MaybeUninit::<c_int>::uninit();
+
+    // SAFETY: `out` is valid for writing one `c_int`. `callback` is either
+    // NULL, which C checks for, or a valid function with the right signature.
+    let status = unsafe { raw::call_callback(callback, value, out.as_mut_ptr()) };
+
+    // SAFETY: C only returns 0 after writing the callback's result to `out`.
+    (status == 0).then(|| unsafe { out.assume_init() })
+}
+
+/// A plain `extern "C"` function to pass to [`apply_twice`].
+pub extern "C" fn double(x: c_int) -> c_int {
+    x.wrapping_mul(2)
+}
+
+/// Has C call `callback` twice, feeding the first result into the second
+/// call: `apply_twice(double, 3)` is `12`.
+pub fn apply_twice(callback: IntCallback, value: i32) -> i32 {
+    // SAFETY: `callback` is a valid function with the right signature.
+    unsafe { raw::apply_twice(Some(callback), value) }
+}
+
+thread_local! {
+    // The Rust function the trampoline forwards to. It is thread-local so
+    // that calls on different threads don't see each other's functions.
+    static CURRENT: Cell<Option<RustCallback>> = const { Cell::new(None) };
+}
+
+extern "C" fn trampoline(x: c_int) -> c_int {
+    let f = CURRENT
+        .with(Cell::get)
+        .expect("trampoline called outside of apply_twice_with");
+
+    f(x)
+}
+
+/// Like [`apply_twice`], but takes a Rust function or non-capturing closure.
+///
+/// C only ever sees `trampoline`, which looks up `f` in a thread-local and
+/// calls it. If `f` panics, the panic can't unwind through C and the process
+/// aborts instead.
+pub fn apply_twice_with(f: RustCallback, value: i32) -> i32 {
+    // Restore whatever was there before, in case `f` itself calls
+    // `apply_twice_with`.
+    let previous = CURRENT.with(|current| current.replace(Some(f)));
+
+    let result = apply_twice(trampoline, value);
+
+    CURRENT.with(|current| current.set(previous));
+
+    result
+}
+
+// What `for_each` hands to C as the user pointer.
+struct ForEachState<F> {
+    f: F,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+extern "C" fn for_each_trampoline<F: FnMut(i32)>(value: c_int, user: *mut c_void) {
+    // SAFETY: C passes back the pointer `for_each` gave it, which points to a
+    // `ForEachState<F>` that outlives the call. Nothing else accesses it while
+    // C is running, so the `&mut` is unique.
+    let state = unsafe { &mut *user.cast::<ForEachState<F>>() };
+
+    // C has no way to stop early, so once the closure has panicked, ignore
+    // the remaining values.
+    if state.panic.is_some() {
+        return;
+    }
+
+    // Unwinding out of an `extern "C"` function aborts the process, so catch
+    // the panic here and let `for_each` rethrow it once C has returned.
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (state.f)(value))) {
+        state.panic = Some(payload);
+    }
+}
+
+/// Has C call `f` with each of `values`, in order.
+///
+/// `f` can be any closure, including one that captures and mutates state:
+/// it travels through C's `void *user` argument, and a monomorphized
+/// `extern "C"` trampoline turns it back into a closure on the other side.
+///
+/// The closure isn't boxed: C only uses the user pointer until `for_each`
+/// returns, so it can point into this function's stack frame, and `f` is
+/// dropped as usual when the frame goes away. A `Box` (handed over with
+/// `Box::into_raw` and reclaimed with `Box::from_raw`) is only needed when C
+/// keeps the pointer after the call returns, such as a callback it stores
+/// to call later.
+///
+/// If `f` panics, C is left to finish its loop without calling it again,
+/// and the panic is resumed after C returns.
+pub fn for_each<F: FnMut(i32)>(values: &[i32], f: F) {
+    let mut state = ForEachState { f, panic: None };
+
+    // SAFETY: `values` is valid for reading `values.len()` integers, and
+    // `state` lives on this stack frame until C returns, so the user pointer
+    // never dangles. The trampoline is instantiated for exactly this `F`.
+    unsafe {
+        raw::for_each(
+            values.as_ptr(),
+            values.len(),
+            for_each_trampoline::<F>,
+            (&mut state as *mut ForEachState<F>).cast(),
+        )
+    };
+
+    if let Some(payload) = state.panic {
+        panic::resume_unwind(payload);
+    }
+}
@@ -0,0 +1,110 @@
+//! Command-line arguments for the demo binary in `demo/`.
+//!
+//! Lives in the library rather than in `demo/src/main.rs` so it can be
+//! tested without running the binary.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::calculate::Op;
+
+/// The operands used when none are given.
+pub const DEFAULT_OPERANDS: (i32, i32) = (5000, 5);
+
+/// How to run the binary, printed with argument errors.
+pub const USAGE: &str = "usage: demo [--op OP] [A B]\n\nMultiplies A and B in C. Both must be integers that fit in a C int.\nWithout operands, multiplies 5000 and 5.\n\nOptions:\n  --op OP  Apply OP instead of multiplying: add, sub, mul or div, or\n           one of + - * /";
+
+/// The parsed command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Args {
+    /// The operation from `--op`, [`Op::Mul`] by default.
+    pub op: Op,
+    /// The two operands, [`DEFAULT_OPERANDS`] if none were given.
+    pub operands: (i32, i32),
+}
+
+/// Error returned by [`parse_args`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// There were arguments, but not exactly two.
+    WrongCount(usize),
+    /// An argument isn't an integer.
+    NotANumber(String),
+    /// An argument is an integer, but doesn't fit in a C `int`.
+    OutOfRange(String),
+    /// `--op` was the last argument.
+    MissingOp,
+    /// The value of `--op` isn't an operation.
+    UnknownOp(String),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::WrongCount(count) => write!(f, "expected 2 operands, got {}", count),
+            ArgError::NotANumber(arg) => write!(f, "`{}` is not an integer", arg),
+            ArgError::OutOfRange(arg) => write!(f, "`{}` does not fit in a C int", arg),
+            ArgError::MissingOp => write!(f, "`--op` needs an operation"),
+            ArgError::UnknownOp(op) => write!(f, "`{}` is not an operation", op),
+        }
+    }
+}
+
+impl Error for ArgError {}
+
+/// Parses the command line, without the program name.
+///
+/// `--op OP` (or `--op=OP`) may appear anywhere. No operands at all means
+/// [`DEFAULT_OPERANDS`].
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, ArgError> {
+    let mut op = Op::Mul;
+    let mut operands = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--op" {
+            op = parse_op(&args.next().ok_or(ArgError::MissingOp)?)?;
+        } else if let Some(value) = arg.strip_prefix("--op=") {
+            op = parse_op(value)?;
+        } else {
+            operands.push(arg);
+        }
+    }
+
+    let operands = match operands.as_slice() {
+        [] => DEFAULT_OPERANDS,
+        [a, b] => (parse_operand(a)?, parse_operand(b)?),
+        _ => return Err(ArgError::WrongCount(operands.len())),
+    };
+
+    Ok(Args { op, operands })
+}
+
+fn parse_op(arg: &str) -> Result<Op, ArgError> {
+    let op = match arg {
+        "add" => Some(Op::Add),
+        "sub" => Some(Op::Sub),
+        "mul" => Some(Op::Mul),
+        "div" => Some(Op::Div),
+        // A single character is looked up by C, see `Op::from_symbol`.
+        _ => {
+            let mut chars = arg.chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Op::from_symbol(symbol),
+                _ => None,
+            }
+        }
+    };
+
+    op.ok_or_else(|| ArgError::UnknownOp(arg.to_owned()))
+}
+
+fn parse_operand(arg: &str) -> Result<i32, ArgError> {
+    // Parse into something wider first, to tell a number that is too big
+    // apart from something that isn't a number at all.
+    let value: i128 = arg
+        .trim()
+        .parse()
+        .map_err(|_| ArgError::NotANumber(arg.to_owned()))?;
+
+    i32::try_from(value).map_err(|_| ArgError::OutOfRange(arg.to_owned()))
+}
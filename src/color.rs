@@ -0,0 +1,85 @@
+//! Passing a Rust enum to C and back.
+//!
+//! `enum Color` in `c_src/multiply.h` leaves its values implicit, so C
+//! numbers them from 0: `RED` is 0, `GREEN` 1 and `BLUE` 2. [`Color`] does
+//! the same, with `#[repr(C)]` giving it the size of a C enum.
+//!
+//! Going into C, a `Color` is always one of the three values, and converts
+//! to its discriminant with `as c_int`. Coming back, C may return any `int`,
+//! so, as in `crate::status`, the declaration returns a `c_int` and
+//! [`Color::try_from`] checks it before it becomes a `Color`. `next_color()`
+//! returns anything outside the enum unchanged, which [`next_color_raw`]
+//! uses to show the check failing.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        // Takes a `c_int` rather than a `Color` so that `next_color_raw` can
+        // pass values that aren't one. `Color` is `#[repr(C)]`, so `as c_int`
+        // gives C exactly what it expects.
+        pub fn next_color(color: c_int) -> c_int;
+    }
+}
+
+/// The `Color` enum from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// `RED`.
+    Red,
+    /// `GREEN`.
+    Green,
+    /// `BLUE`.
+    Blue,
+}
+
+/// Error returned when C produces a value that isn't a [`Color`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownColor(pub c_int);
+
+impl fmt::Display for UnknownColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown color {}", self.0)
+    }
+}
+
+impl Error for UnknownColor {}
+
+impl TryFrom<c_int> for Color {
+    type Error = UnknownColor;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Color::Red),
+            1 => Ok(Color::Green),
+            2 => Ok(Color::Blue),
+            other => Err(UnknownColor(other)),
+        }
+    }
+}
+
+/// Asks C for the color after `color`.
+///
+/// C only returns values in the enum for valid input, but that is C's
+/// promise, not something the compiler checks, so this still returns a
+/// `Result`.
+pub fn next_color(color: Color) -> Result<Color, UnknownColor> {
+    // SAFETY: `next_color` takes and returns plain integers.
+    let next = unsafe { raw::next_color(color as c_int) };
+
+    Color::try_from(next)
+}
+
+/// Like [`next_color`], but takes the color as an integer, the way it might
+/// arrive from a file or another C library.
+pub fn next_color_raw(color: c_int) -> Result<Color, UnknownColor> {
+    // SAFETY: `next_color` takes and returns plain integers.
+    let next = unsafe { raw::next_color(color) };
+
+    Color::try_from(next)
+}
@@ -0,0 +1,75 @@
+//! Owning an opaque C object through a pointer.
+//!
+//! `c_src/context.c` never tells its callers what a `Context` looks like:
+//! it hands out pointers from `ctx_create()`, expects them back in every
+//! other call, and frees them in `ctx_destroy()`. Rust only needs to store
+//! the pointer, so it is declared as a `*mut c_void`.
+
+use core::ffi::c_void;
+
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use core::ffi::{c_int, c_void};
+
+    extern "C" {
+        pub fn ctx_create(seed: c_int) -> *mut c_void;
+        pub fn ctx_get_value(ctx: *mut c_void) -> c_int;
+        pub fn ctx_destroy(ctx: *mut c_void);
+    }
+}
+
+/// An owned C `Context`, destroyed when dropped.
+///
+/// Since `CContext` is not `Clone` or `Copy`, there is exactly one owner of
+/// the pointer, and `Drop` runs exactly once, so the context can't be freed
+/// twice.
+///
+/// # Thread safety
+///
+/// `CContext` is neither `Send` nor `Sync`, because it contains a raw
+/// pointer. That is the right default: `ctx_get_value()` mutates the context
+/// without any locking. It would be sound to add
+///
+/// ```ignore
+/// unsafe impl Send for CContext {}
+/// ```
+///
+/// if the C library documents that a context may be used from a different
+/// thread than the one that created it (no thread-local state, no thread
+/// affinity), since owning a `CContext` on another thread still means only one
+/// thread uses it at a time. `Sync` would additionally need C to tolerate
+/// concurrent calls on the same context, which this one doesn't.
+pub struct CContext(*mut c_void);
+
+impl CContext {
+    /// Creates a new context seeded with `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if C fails to allocate the context.
+    pub fn new(seed: i32) -> Self {
+        // SAFETY: `ctx_create` has no preconditions.
+        let ctx = unsafe { raw::ctx_create(seed) }
+            .to_option()
+            .expect("ctx_create failed to allocate a context");
+
+        CContext(ctx.as_ptr())
+    }
+
+    /// Returns the next value of the context's pseudo-random sequence.
+    pub fn value(&self) -> i32 {
+        // SAFETY: the pointer came from `ctx_create` and is only freed in
+        // `drop`. C mutates the context here, even though we only have
+        // `&self`, but since `CContext` is not `Sync` no other thread can be
+        // using it at the same time.
+        unsafe { raw::ctx_get_value(self.0) }
+    }
+}
+
+impl Drop for CContext {
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from `ctx_create`, and `drop` runs once.
+        unsafe { raw::ctx_destroy(self.0) }
+    }
+}
@@ -0,0 +1,65 @@
+//! Helpers for moving strings across the boundary.
+//!
+//! Every string example needs the same few steps: turn a `&str` into a
+//! NUL-terminated `CString` on the way in, and wrap a `*const c_char` in a
+//! `CStr` and copy it into a `String` on the way out. Each of them is easy to
+//! get subtly wrong, by forgetting the NULL check or the `free`, so they are
+//! written once here.
+//!
+//! The `String`s these return are copies, owned by Rust. When Rust should
+//! keep using C's memory instead, see the wrapper types in
+//! [`crate::cstr_ownership`].
+
+use core::ffi::c_char;
+use std::ffi::{CStr, CString, NulError};
+
+/// Copies `s` into a NUL-terminated `CString` for C.
+///
+/// Fails if `s` contains a NUL byte: C would see the string end there, and
+/// silently lose the rest.
+pub fn to_cstring(s: &str) -> Result<CString, NulError> {
+    CString::new(s)
+}
+
+/// Copies the C string at `ptr` into a `String`, leaving C's copy alone.
+///
+/// Returns an empty string if `ptr` is NULL. Bytes that aren't valid UTF-8
+/// are replaced with `U+FFFD`.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string that stays valid
+/// and unchanged for the duration of the call.
+pub unsafe fn from_c_borrowed(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    // SAFETY: the caller guarantees that `ptr` points to a valid string, and
+    // it isn't NULL.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Copies the C string at `ptr` into a `String`, and frees C's copy with
+/// C's `free`.
+///
+/// Returns an empty string if `ptr` is NULL. Bytes that aren't valid UTF-8
+/// are replaced with `U+FFFD`.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string allocated by C's
+/// `malloc` (or `strdup`, and so on) that the caller owns. It must not be
+/// used again after this call.
+pub unsafe fn from_c_owned(ptr: *mut c_char) -> String {
+    // SAFETY: the caller guarantees that `ptr` is NULL or a valid string.
+    let s = unsafe { from_c_borrowed(ptr) };
+
+    // SAFETY: the string came from `malloc` and is owned by the caller, who
+    // hands it over here. `free(NULL)` does nothing.
+    unsafe { libc::free(ptr.cast()) };
+
+    s
+}
@@ -0,0 +1,83 @@
+//! RAII over a C object: a counter created by C and freed in `Drop`.
+//!
+//! This is the smallest version of the opaque handle pattern from
+//! `crate::calculator`, spelled out for the two ways it usually goes wrong:
+//!
+//! - Freeing twice. `counter_free` must run exactly once per counter. Rust
+//!   calls `drop` exactly once per value, and [`Counter`] can't be
+//!   duplicated, so that is the only place it needs to be called.
+//! - Copying the handle. If `Counter` were `Copy` or `Clone`, two values
+//!   would own the same C object and both would free it. It derives
+//!   neither, and implementing `Copy` isn't even allowed for a type with a
+//!   `Drop` impl:
+//!
+//! ```compile_fail,E0382
+//! use rust_ffi_to_c::counter::Counter;
+//!
+//! let a = Counter::new().unwrap();
+//! let b = a;
+//! a.value(); // error: `a` was moved into `b`
+//! ```
+
+use std::marker::{PhantomData, PhantomPinned};
+use std::ptr::NonNull;
+
+pub use crate::calculator::NullHandleError;
+use crate::ptr_ext::PtrExt;
+
+/// The C `struct Counter`, whose fields Rust never sees.
+#[repr(C)]
+pub struct RawCounter {
+    _private: [u8; 0],
+    _marker: PhantomData<(*mut u8, PhantomPinned)>,
+}
+
+mod raw {
+    use super::RawCounter;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn counter_new() -> *mut RawCounter;
+        pub fn counter_increment(counter: *mut RawCounter);
+        pub fn counter_value(counter: *const RawCounter) -> c_int;
+        pub fn counter_free(counter: *mut RawCounter);
+    }
+}
+
+/// An owned C counter, freed when dropped.
+pub struct Counter(NonNull<RawCounter>);
+
+// SAFETY: as for `Calculator`, a C counter is a heap object only reachable
+// through this handle, and `counter_object.c` keeps no global state, so it
+// may be used and freed on any thread, one at a time.
+unsafe impl Send for Counter {}
+
+impl Counter {
+    /// Creates a counter starting at 0.
+    pub fn new() -> Result<Self, NullHandleError> {
+        // SAFETY: `counter_new` has no preconditions.
+        let ptr = unsafe { raw::counter_new() };
+
+        ptr.owned_or(NullHandleError).map(Counter)
+    }
+
+    /// Adds one to the counter, wrapping around after `i32::MAX`.
+    pub fn increment(&mut self) {
+        // SAFETY: the handle is valid until `drop`, and `&mut self`
+        // guarantees nobody else is using it.
+        unsafe { raw::counter_increment(self.0.as_ptr()) }
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> i32 {
+        // SAFETY: the handle is valid until `drop`.
+        unsafe { raw::counter_value(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from `counter_new`, and `drop` runs once.
+        unsafe { raw::counter_free(self.0.as_ptr()) }
+    }
+}
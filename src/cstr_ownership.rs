@@ -0,0 +1,108 @@
+//! Who owns a string that comes back from C?
+//!
+//! A `*const c_char` from C says nothing about who owns the memory behind
+//! it, so the C documentation has to. The two common answers get two
+//! different Rust types here, so that the compiler remembers the answer:
+//!
+//! - `static_string()` returns a string C owns forever. [`BorrowedCStr`]
+//!   only borrows it, and never frees it.
+//! - `heap_string()` returns a `strdup` copy the caller owns and must
+//!   `free()`. [`OwnedCStr`] frees it in `Drop`, with `libc::free`, which is
+//!   the same `free` C's `strdup` expects. Rust's own allocator, as used by
+//!   `CString::from_raw`, would be the wrong one.
+//!
+//! Since they are different types, one can't be passed where the other is
+//! expected, for example to free a static string:
+//!
+//! ```compile_fail
+//! use rust_ffi_to_c::cstr_ownership::{static_string, OwnedCStr};
+//!
+//! let owned: OwnedCStr = static_string();
+//! ```
+//!
+//! [`crate::strings`] avoids the question for `greet()` by copying the
+//! string into a `String` and handing it straight back to C to free. The
+//! types here let Rust keep using the C memory instead of copying it.
+
+use std::ffi::{CStr, NulError};
+use std::fmt;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use crate::convert;
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use core::ffi::c_char;
+
+    extern "C" {
+        pub fn static_string() -> *const c_char;
+        pub fn heap_string(input: *const c_char) -> *mut c_char;
+    }
+}
+
+/// A string borrowed from C, which C frees (or never frees) on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorrowedCStr<'a>(&'a CStr);
+
+impl<'a> Deref for BorrowedCStr<'a> {
+    type Target = CStr;
+
+    fn deref(&self) -> &CStr {
+        self.0
+    }
+}
+
+/// A string allocated by C's `malloc`, freed with C's `free` when dropped.
+pub struct OwnedCStr(NonNull<core::ffi::c_char>);
+
+// SAFETY: the string is plain heap memory owned by this value, which only
+// ever reads it, so borrowing it from several threads is fine, and `free()`
+// may free it on any thread.
+unsafe impl Send for OwnedCStr {}
+unsafe impl Sync for OwnedCStr {}
+
+impl Deref for OwnedCStr {
+    type Target = CStr;
+
+    fn deref(&self) -> &CStr {
+        // SAFETY: the pointer came from C as a NUL-terminated string, and we
+        // own it until `drop`, which the returned borrow can't outlast.
+        unsafe { CStr::from_ptr(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for OwnedCStr {
+    fn drop(&mut self) {
+        // SAFETY: the string was allocated by C's `malloc`, we own it, and
+        // `drop` runs once.
+        unsafe { libc::free(self.0.as_ptr().cast()) }
+    }
+}
+
+impl fmt::Debug for OwnedCStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedCStr").field(&&**self).finish()
+    }
+}
+
+/// Borrows the string C keeps in static memory.
+pub fn static_string() -> BorrowedCStr<'static> {
+    // SAFETY: C returns a pointer to a NUL-terminated string literal, which
+    // lives for the whole program.
+    BorrowedCStr(unsafe { CStr::from_ptr(raw::static_string()) })
+}
+
+/// Has C copy `input` onto its heap.
+///
+/// Returns `Ok(None)` if C fails to allocate, and an error if `input`
+/// contains a NUL byte.
+pub fn heap_string(input: &str) -> Result<Option<OwnedCStr>, NulError> {
+    let input = convert::to_cstring(input)?;
+
+    // SAFETY: `input` is NUL-terminated and outlives the call. C only reads
+    // it.
+    let ptr = unsafe { raw::heap_string(input.as_ptr()) };
+
+    Ok(ptr.to_option().map(OwnedCStr))
+}
@@ -0,0 +1,152 @@
+//! Calling `multiply` from a shared library loaded at runtime.
+//!
+//! Only built with the `dynamic` feature. The rest of the crate links the C
+//! code statically, so a missing library fails the build. Here it fails when
+//! the library is loaded instead, and the caller gets a [`DynamicError`] to
+//! deal with. `examples/dynamic.rs` shows the same thing without the
+//! wrapper.
+
+use core::ffi::{c_char, c_int};
+use std::borrow::Cow;
+use std::env;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use libloading::{Library, Symbol};
+
+use crate::version::{self, Version, VersionError};
+
+/// The signature of `multiply` in `c_src/multiply.h`.
+type MultiplyFn = unsafe extern "C" fn(c_int, c_int) -> c_int;
+
+/// The signature of `lib_version` in `c_src/multiply.h`.
+type LibVersionFn = unsafe extern "C" fn() -> *const c_char;
+
+/// Error returned by [`DynamicMultiply::load`].
+#[derive(Debug)]
+pub enum DynamicError {
+    /// The library couldn't be opened.
+    LibraryNotFound {
+        /// Where the library was looked for.
+        path: PathBuf,
+        /// Why it couldn't be opened.
+        source: libloading::Error,
+    },
+    /// The library doesn't export the symbol.
+    SymbolNotFound {
+        /// The symbol that was looked up.
+        name: &'static str,
+        /// Why it couldn't be found.
+        source: libloading::Error,
+    },
+}
+
+impl fmt::Display for DynamicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicError::LibraryNotFound { path, source } => {
+                write!(f, "failed to load {}: {}", path.display(), source)
+            }
+            DynamicError::SymbolNotFound { name, source } => {
+                write!(f, "symbol `{}` not found in the library: {}", name, source)
+            }
+        }
+    }
+}
+
+impl Error for DynamicError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DynamicError::LibraryNotFound { source, .. }
+            | DynamicError::SymbolNotFound { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Where to find the shared library: `MULTIPLY_DYLIB` if set, or the one the
+/// build script leaves in OUT_DIR.
+pub fn default_path() -> PathBuf {
+    env::var_os("MULTIPLY_DYLIB")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            Path::new(env!("OUT_DIR")).join(format!("{}multiply{}", DLL_PREFIX, DLL_SUFFIX))
+        })
+}
+
+/// A loaded shared library and the `multiply` function it exports.
+pub struct DynamicMultiply {
+    multiply: MultiplyFn,
+    // `None` for a library older than `lib_version()`.
+    lib_version: Option<LibVersionFn>,
+    // Unloads the library when dropped, so it has to outlive `multiply`,
+    // which points into it.
+    _library: Library,
+}
+
+impl DynamicMultiply {
+    /// Loads the library at `path` and looks up `multiply` in it.
+    pub fn load(path: &Path) -> Result<Self, DynamicError> {
+        // SAFETY: loading a library runs its initialization code, and
+        // libmultiply has none.
+        let library =
+            unsafe { Library::new(path) }.map_err(|source| DynamicError::LibraryNotFound {
+                path: path.to_owned(),
+                source,
+            })?;
+
+        // SAFETY: `MultiplyFn` is the signature `multiply` is defined with.
+        let symbol: Symbol<MultiplyFn> =
+            unsafe { library.get(b"multiply") }.map_err(|source| DynamicError::SymbolNotFound {
+                name: "multiply",
+                source,
+            })?;
+
+        // Copying the function pointer out of the `Symbol` drops the borrow
+        // of `library`. It stays valid because the library is stored next to
+        // it and only unloaded when `self` is dropped.
+        let multiply = *symbol;
+
+        // SAFETY: `LibVersionFn` is the signature `lib_version` is defined
+        // with. A missing symbol isn't an error here, see `require_version`.
+        let lib_version = unsafe { library.get::<LibVersionFn>(b"lib_version") }
+            .ok()
+            .map(|symbol| *symbol);
+
+        Ok(DynamicMultiply {
+            multiply,
+            lib_version,
+            _library: library,
+        })
+    }
+
+    /// Multiplies two integers with the loaded `multiply`.
+    pub fn multiply(&self, a: i32, b: i32) -> i32 {
+        // SAFETY: the library is still loaded, and `multiply` takes and
+        // returns plain integers.
+        unsafe { (self.multiply)(a, b) }
+    }
+
+    /// The version the loaded library reports, or `None` if it is too old
+    /// to have `lib_version()`.
+    ///
+    /// The string lives in the library, so unlike [`version::version`] it is
+    /// only borrowed for as long as the library stays loaded.
+    pub fn version(&self) -> Option<Cow<'_, str>> {
+        let lib_version = self.lib_version?;
+
+        // SAFETY: the library is still loaded, and `lib_version` returns a
+        // NUL-terminated string literal from it, which stays valid until
+        // `self` unloads the library.
+        Some(unsafe { CStr::from_ptr(lib_version()) }.to_string_lossy())
+    }
+
+    /// Checks that the loaded library is `min` or newer, since a shared
+    /// library on disk may be older than the code loading it.
+    pub fn require_version(&self, min: Version) -> Result<(), VersionError> {
+        let found = self.version().ok_or(VersionError::Missing)?;
+        version::check_version(&found, min)
+    }
+}
@@ -0,0 +1,84 @@
+//! Handling C functions that return -1 and set `errno` on failure.
+//!
+//! This is the convention most of POSIX uses: the return value only says
+//! *that* something went wrong, and `errno` says *what*. Reading `errno`
+//! safely takes two rules:
+//!
+//! - Clear it before the call. C never resets it on success, so it may
+//!   still hold an error from an earlier, unrelated call.
+//! - Read it right after the call. Anything else that runs in between, like
+//!   an allocation or a `println!`, may change it.
+//!
+//! `errno` is thread-local, so other threads can't interfere.
+//! [`std::io::Error::last_os_error`] reads it portably, and the `libc` crate
+//! provides the error constants to compare it against.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::arithmetic::clear_errno;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn safe_divide(a: c_int, b: c_int, out: *mut c_int) -> c_int;
+    }
+}
+
+/// Error returned by [`safe_divide`].
+#[derive(Debug)]
+pub enum DivError {
+    /// The divisor was zero (`EDOM`).
+    DivideByZero,
+    /// The quotient doesn't fit in an `i32` (`ERANGE`).
+    Overflow,
+    /// C failed with some other `errno`.
+    Os(io::Error),
+}
+
+impl fmt::Display for DivError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DivError::DivideByZero => write!(f, "division by zero"),
+            DivError::Overflow => write!(f, "division overflowed i32"),
+            DivError::Os(err) => write!(f, "division failed: {}", err),
+        }
+    }
+}
+
+impl Error for DivError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DivError::Os(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Divides `a` by `b` in C.
+pub fn safe_divide(a: i32, b: i32) -> Result<i32, DivError> {
+    // Every bit pattern is a valid `c_int`, so unlike in
+    // `arithmetic::divide`, a zeroed value is a fine starting point.
+    let mut out: c_int = 0;
+
+    clear_errno();
+
+    // SAFETY: `&mut out` is valid for writing one `c_int` for the duration
+    // of the call.
+    let status = unsafe { raw::safe_divide(a, b, &mut out) };
+
+    if status == 0 {
+        return Ok(out);
+    }
+
+    let err = io::Error::last_os_error();
+
+    Err(match err.raw_os_error() {
+        Some(libc::EDOM) => DivError::DivideByZero,
+        Some(libc::ERANGE) => DivError::Overflow,
+        _ => DivError::Os(err),
+    })
+}
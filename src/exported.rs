@@ -0,0 +1,186 @@
+//! Rust functions exported to C.
+//!
+//! `extern "C"` gives a function the C calling convention, and
+//! `#[no_mangle]` keeps its symbol name exactly as written, so C code can
+//! declare it and call it by name. `cargo xtask gen-header` generates those
+//! declarations from this file with cbindgen into `include/rust_exports.h`,
+//! which is committed so the C code builds without cbindgen, and checked
+//! against this file by `cargo test`.
+//!
+//! These are the only symbols the `cdylib` build of this crate exports:
+//!
+//! - `rust_callback`
+//! - `rust_add`
+//! - `rust_multiply_checked`
+//! - `rust_divide`
+//! - `rust_configure`
+//!
+//! # Forgetting `#[no_mangle]`
+//!
+//! Without `#[no_mangle]`, everything still compiles:
+//!
+//! ```
+//! use core::ffi::c_int;
+//!
+//! pub extern "C" fn rust_add_mangled(a: c_int, b: c_int) -> c_int {
+//!     a.wrapping_add(b)
+//! }
+//!
+//! // It even works when passed to C as a function pointer, since the
+//! // calling convention is still C's.
+//! let callback: extern "C" fn(c_int, c_int) -> c_int = rust_add_mangled;
+//! assert_eq!(callback(2, 3), 5);
+//! ```
+//!
+//! But the symbol in the object file is no longer called `rust_add_mangled`.
+//! Rust mangles it into something like
+//! `_ZN13rust_ffi_to_c8exported16rust_add_mangled17h1a2b3c4d5e6f7a8bE`,
+//! which encodes the module path and a hash, and a C file declaring
+//! `int rust_add_mangled(int, int);` fails to link with an "undefined
+//! reference" error. The failure only shows up when linking the C code, not
+//! when compiling the Rust code.
+
+use core::ffi::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr::NonNull;
+
+use crate::nullable::{Config, CONFIG_DEFAULT_RETRIES, CONFIG_DEFAULT_TIMEOUT_MS};
+
+/// Doubles its argument.
+///
+/// C can call this by name, or receive it as a function pointer (see
+/// `callbacks::call_callback`).
+#[no_mangle]
+pub extern "C" fn rust_callback(x: c_int) -> c_int {
+    println!("[Rust] rust_callback called from C with {}", x);
+
+    // Panicking here would have to unwind through C, so stay clear of the
+    // overflow check.
+    x.wrapping_mul(2)
+}
+
+/// Adds two integers, wrapping on overflow.
+///
+/// C calls this by name from `compute_with_rust()` in `c_src/compute.c`.
+///
+/// The addition can't panic, but the body is wrapped in
+/// [`ffi_safe!`](crate::ffi_safe) like any exported function should be, so a
+/// later change that can panic doesn't unwind into C. Every `int` is a valid
+/// sum, so the sentinel, 0, can't be told apart from a real result;
+/// functions that can fail should return a status code instead, like
+/// [`rust_multiply_checked`].
+#[no_mangle]
+pub extern "C" fn rust_add(a: c_int, b: c_int) -> c_int {
+    crate::ffi_safe!(0, { a.wrapping_add(b) })
+}
+
+/// Returned by [`rust_multiply_checked`] on success.
+pub const RUST_MULTIPLY_OK: c_int = 0;
+/// Returned by [`rust_multiply_checked`] when the product doesn't fit.
+pub const RUST_MULTIPLY_OVERFLOW: c_int = 1;
+/// Returned by [`rust_multiply_checked`] when `out` is NULL.
+pub const RUST_MULTIPLY_NULL_POINTER: c_int = 2;
+
+/// Multiplies two integers, writing the product to `out`.
+///
+/// Returns an error code instead of panicking, since a panic can't unwind
+/// into C: [`RUST_MULTIPLY_OK`], [`RUST_MULTIPLY_OVERFLOW`] or
+/// [`RUST_MULTIPLY_NULL_POINTER`]. `out` is only written on success.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writing one `int`.
+#[no_mangle]
+pub unsafe extern "C" fn rust_multiply_checked(a: c_int, b: c_int, out: *mut c_int) -> c_int {
+    if out.is_null() {
+        return RUST_MULTIPLY_NULL_POINTER;
+    }
+
+    let Some(product) = a.checked_mul(b) else {
+        return RUST_MULTIPLY_OVERFLOW;
+    };
+
+    // SAFETY: `out` is not NULL, and the caller guarantees it is valid.
+    unsafe { out.write(product) };
+
+    RUST_MULTIPLY_OK
+}
+
+/// Returned by [`rust_divide`] on success.
+pub const RUST_DIVIDE_OK: c_int = 0;
+/// Returned by [`rust_divide`] when `out` is NULL.
+pub const RUST_DIVIDE_NULL_POINTER: c_int = 1;
+/// Returned by [`rust_divide`] when the division panicked.
+pub const RUST_DIVIDE_PANICKED: c_int = 2;
+
+/// Divides `a` by `b`, writing the quotient to `out`.
+///
+/// Returns [`RUST_DIVIDE_OK`], [`RUST_DIVIDE_NULL_POINTER`] or
+/// [`RUST_DIVIDE_PANICKED`]. `out` is only written on success.
+///
+/// Unlike [`rust_multiply_checked`], this doesn't check its input: `a / b`
+/// panics when `b` is 0, or for `i32::MIN / -1`. A panic must never unwind
+/// out of an `extern "C"` function, since the C frames above it have no
+/// unwind information and nothing to run on the way up. That used to be
+/// undefined behavior; since Rust 1.81 the process aborts instead. Either
+/// way, the panic has to be stopped here, with `catch_unwind`, and turned
+/// into something C understands.
+///
+/// `catch_unwind` requires its closure to be `UnwindSafe`, which a closure
+/// capturing the raw `out` pointer is not. `AssertUnwindSafe` overrides the
+/// check: it is fine here because `out` isn't written until the division
+/// has succeeded, so a panic can't leave it half updated.
+///
+/// The panic message is still printed to stderr by the panic hook.
+///
+/// # Safety
+///
+/// `out` must be NULL or valid for writing one `int`.
+#[no_mangle]
+pub unsafe extern "C" fn rust_divide(a: c_int, b: c_int, out: *mut c_int) -> c_int {
+    if out.is_null() {
+        return RUST_DIVIDE_NULL_POINTER;
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let quotient = a / b;
+
+        // SAFETY: `out` is not NULL, and the caller guarantees it is valid.
+        unsafe { out.write(quotient) };
+    }));
+
+    match result {
+        Ok(()) => RUST_DIVIDE_OK,
+        Err(_) => RUST_DIVIDE_PANICKED,
+    }
+}
+
+/// [`nullable::configure`](crate::nullable::configure) implemented in Rust,
+/// for C to call. Takes the settings as a `Config *`, NULL for the defaults.
+/// Receiving it as an `Option<NonNull<Config>>` means the NULL check can't
+/// be skipped.
+///
+/// `Config` is defined in `c_src/multiply.h`, which the generated header
+/// includes for it. The pointer isn't `const` in the header, since cbindgen
+/// can't tell that it is only read through.
+///
+/// The total wraps around if it overflows.
+///
+/// # Safety
+///
+/// `cfg` must be NULL or point to a valid `Config` for the duration of the
+/// call.
+#[no_mangle]
+pub unsafe extern "C" fn rust_configure(cfg: Option<NonNull<Config>>) -> c_int {
+    let cfg = match cfg {
+        // SAFETY: the caller guarantees that a non-NULL `cfg` points to a
+        // valid `Config`.
+        Some(cfg) => unsafe { *cfg.as_ptr() },
+        None => Config {
+            retries: CONFIG_DEFAULT_RETRIES,
+            timeout_ms: CONFIG_DEFAULT_TIMEOUT_MS,
+        },
+    };
+
+    cfg.retries.wrapping_add(1).wrapping_mul(cfg.timeout_ms)
+}
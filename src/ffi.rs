@@ -0,0 +1,213 @@
+//! Safe bindings to the functions implemented in `c_src/`.
+//!
+//! The raw `extern "C"` declarations are kept private in `raw`, so the only
+//! way to reach C from the rest of the crate is through the safe functions
+//! below. They are written by hand by default, or generated by bindgen from
+//! `c_src/multiply.h` with the `bindgen` feature. [`crate::raw`] re-exports
+//! them for callers who want to make the unsafe calls themselves.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+
+// The hand-written declarations are always compiled, even when bindgen's are
+// the ones in use, so the check below can compare the two.
+#[cfg_attr(feature = "bindgen", allow(dead_code))]
+pub(crate) mod manual {
+    use crate::arrays::c_size_t;
+    use core::ffi::{c_double, c_float, c_int};
+
+    extern "C" {
+        /// Returns `a * b`, printing its inputs unless verbose output is
+        /// off. Wraps around on overflow.
+        pub fn multiply(a: c_int, b: c_int) -> c_int;
+        /// Returns `a * b`.
+        pub fn multiply_f64(a: c_double, b: c_double) -> c_double;
+        /// Returns `a * b`.
+        pub fn multiply_f32(a: c_float, b: c_float) -> c_float;
+        /// Turns the printing in `multiply()` on (non-zero) or off (0).
+        pub fn multiply_set_verbose(enabled: c_int);
+        /// Returns how many times `multiply()` has been called.
+        pub fn multiply_call_count() -> c_int;
+        /// Writes `a[i] * b[i]` to `out[i]` for each `i` below `n`, wrapping
+        /// on overflow.
+        pub fn multiply_batch(a: *const c_int, b: *const c_int, out: *mut c_int, n: c_size_t);
+    }
+}
+
+#[cfg(not(feature = "bindgen"))]
+pub(crate) use manual as raw;
+
+// With the `bindgen` feature, the declarations come from `c_src/multiply.h`.
+#[cfg(feature = "bindgen")]
+pub(crate) use crate::bindings as raw;
+
+// Fails to compile if multiply.h and the hand-written declarations ever
+// disagree about a signature.
+#[cfg(feature = "bindgen")]
+const _: () = {
+    let _: [unsafe extern "C" fn(c_int, c_int) -> c_int; 2] =
+        [manual::multiply, crate::bindings::multiply];
+    let _: [unsafe extern "C" fn(f64, f64) -> f64; 2] =
+        [manual::multiply_f64, crate::bindings::multiply_f64];
+    let _: [unsafe extern "C" fn(f32, f32) -> f32; 2] =
+        [manual::multiply_f32, crate::bindings::multiply_f32];
+    let _: [unsafe extern "C" fn(*const c_int, *const c_int, *mut c_int, usize); 2] =
+        [manual::multiply_batch, crate::bindings::multiply_batch];
+};
+
+/// Error returned by [`try_multiply`] when a value doesn't fit in a `c_int`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FfiConversionError;
+
+impl fmt::Display for FfiConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value does not fit in a C int on this platform")
+    }
+}
+
+impl Error for FfiConversionError {}
+
+/// Multiplies two integers in C.
+///
+/// The product wraps around on overflow, like [`i32::wrapping_mul`]; the
+/// wrappers in [`crate::safe`] report it instead.
+///
+/// `c_int` is `i32` on every mainstream platform, so this never panics there.
+/// Use [`try_multiply`] on targets where `c_int` may be narrower.
+///
+/// ```
+/// use rust_ffi_to_c::ffi;
+///
+/// assert_eq!(ffi::multiply(5000, 5), 25000);
+/// assert_eq!(ffi::multiply(-6, 7), -42);
+/// assert_eq!(ffi::multiply(i32::MAX, 2), -2);
+/// ```
+pub fn multiply(a: i32, b: i32) -> i32 {
+    try_multiply(a, b).expect("c_int is narrower than i32 on this platform")
+}
+
+/// Multiplies two integers in C, converting to and from `c_int` at the
+/// boundary instead of assuming it is the same type as `i32`.
+///
+/// ```
+/// use rust_ffi_to_c::ffi;
+///
+/// assert_eq!(ffi::try_multiply(0, 7), Ok(0));
+/// ```
+// The conversions compile to nothing where `c_int` is `i32`, which is the
+// point: the same code stays correct where it isn't.
+#[allow(clippy::useless_conversion)]
+pub fn try_multiply(a: i32, b: i32) -> Result<i32, FfiConversionError> {
+    let a = c_int::try_from(a).map_err(|_| FfiConversionError)?;
+    let b = c_int::try_from(b).map_err(|_| FfiConversionError)?;
+
+    // SAFETY: `multiply` takes two integers by value and has no pointer
+    // arguments, so it can't touch memory owned by Rust. Its only side effect
+    // is printing to stdout. Any pair of integers is a valid input: C
+    // multiplies them as `unsigned`, so overflow wraps around instead of
+    // being undefined behavior.
+    let result = unsafe { raw::multiply(a, b) };
+
+    i32::try_from(result).map_err(|_| FfiConversionError)
+}
+
+/// Multiplies two `f64`s in C.
+///
+/// `c_double` is always `f64`, and `c_float` always `f32`: Rust only
+/// supports targets where C's floating-point types are IEEE 754 binary64 and
+/// binary32. Floats cross the boundary bit for bit, so NaN, the infinities
+/// and `-0.0` come back exactly as IEEE 754 arithmetic produces them.
+pub fn multiply_f64(a: f64, b: f64) -> f64 {
+    // SAFETY: `multiply_f64` takes and returns plain values.
+    unsafe { raw::multiply_f64(a, b) }
+}
+
+/// Multiplies two `f32`s in C.
+///
+/// C's `float` is passed as is, without being promoted to `double`: that
+/// only happens for variadic arguments and unprototyped calls.
+pub fn multiply_f32(a: f32, b: f32) -> f32 {
+    // SAFETY: `multiply_f32` takes and returns plain values.
+    unsafe { raw::multiply_f32(a, b) }
+}
+
+/// Multiplies `a` and `b` element by element in C, writing the products to
+/// `out`. Products wrap around on overflow.
+///
+/// One call covers the whole slice, so the cost of crossing the boundary is
+/// paid once rather than once per pair, see `benches/ffi_overhead.rs`.
+///
+/// ```
+/// use rust_ffi_to_c::ffi;
+///
+/// let mut out = [0; 3];
+/// ffi::multiply_batch(&[1, 2, 3], &[4, 5, 6], &mut out);
+/// assert_eq!(out, [4, 10, 18]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the slices don't all have the same length.
+pub fn multiply_batch(a: &[i32], b: &[i32], out: &mut [i32]) {
+    assert!(
+        a.len() == out.len() && b.len() == out.len(),
+        "multiply_batch needs slices of the same length"
+    );
+
+    // SAFETY: `a` and `b` are valid for reading, and `out` for writing,
+    // `out.len()` integers. `out` is borrowed mutably, so it can't overlap
+    // the other two.
+    unsafe { raw::multiply_batch(a.as_ptr(), b.as_ptr(), out.as_mut_ptr(), out.len()) }
+}
+
+/// Turns the printing in [`multiply`] on (the default) or off.
+///
+/// Printing costs far more than the multiplication, so benchmarks turn it
+/// off. The setting is an atomic C global, shared by all threads.
+pub fn set_verbose(enabled: bool) {
+    // SAFETY: `multiply_set_verbose` takes a plain integer, and stores it
+    // atomically, so it may race with `multiply()` on other threads.
+    unsafe { raw::multiply_set_verbose(c_int::from(enabled)) }
+}
+
+/// How many times C's `multiply()` has been called, from any thread.
+///
+/// Lets tests check whether a wrapper reached C at all.
+pub fn call_count() -> i32 {
+    // SAFETY: `multiply_call_count` has no preconditions.
+    unsafe { raw::multiply_call_count() }
+}
+
+// Only with bindgen: without it, there is only one set of declarations.
+#[cfg(all(test, feature = "bindgen"))]
+mod tests {
+    use super::*;
+
+    // Calls the generated and the hand-written declarations directly, on the
+    // same inputs. `multiply` only ever goes through one of them.
+    #[test]
+    fn generated_and_hand_written_declarations_agree() {
+        set_verbose(false);
+
+        for (a, b) in [(6, 7), (-3, 5), (0, i32::MAX), (i32::MAX, 2)] {
+            // SAFETY: both declare `multiply()`, which takes two integers by
+            // value.
+            let (generated, hand_written) =
+                unsafe { (crate::bindings::multiply(a, b), manual::multiply(a, b)) };
+            assert_eq!(generated, hand_written);
+        }
+
+        for (a, b) in [(1.5, -2.0), (f64::INFINITY, 0.5), (-0.0, 3.0)] {
+            // SAFETY: both declare `multiply_f64()`, which takes two doubles
+            // by value.
+            let (generated, hand_written) = unsafe {
+                (
+                    crate::bindings::multiply_f64(a, b),
+                    manual::multiply_f64(a, b),
+                )
+            };
+            assert_eq!(generated.to_bits(), hand_written.to_bits());
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! A seam for replacing `multiply()` in unit tests.
+//!
+//! Code that calls C directly can only be tested together with C. Calling
+//! [`multiply`] from this module instead puts a switch in between: normal
+//! builds call the real `multiply()` from `c_src/multiply.c` through
+//! [`crate::ffi`], and the crate's own unit tests (`#[cfg(test)]`) call
+//! whatever closure the test installed with `set_multiply_mock()`. This is
+//! what a crate like `mockall` generates, written out by hand.
+//!
+//! The mock is thread-local, like the tests that use it: `cargo test` runs
+//! each test on a thread of its own, so one test's mock never leaks into
+//! another. A test that never sets one panics on its first call, rather
+//! than quietly falling back to C.
+//!
+//! The switch only changes which function [`multiply`] calls. The unit test
+//! binary is still linked against libmultiply, since the rest of the crate
+//! calls C directly.
+
+#[cfg(test)]
+use std::cell::Cell;
+
+#[cfg(test)]
+thread_local! {
+    static MULTIPLY_MOCK: Cell<Box<dyn Fn(i32, i32) -> i32>> =
+        Cell::new(Box::new(|_, _| panic!("multiply() called without set_multiply_mock()")));
+}
+
+/// Makes [`multiply`] call `f` on this thread instead of C.
+///
+/// Replaces the mock set before, if any.
+#[cfg(test)]
+pub(crate) fn set_multiply_mock(f: impl Fn(i32, i32) -> i32 + 'static) {
+    MULTIPLY_MOCK.with(|mock| mock.set(Box::new(f)));
+}
+
+/// Multiplies two integers in C, or with the mock in unit tests.
+#[cfg(not(test))]
+pub fn multiply(a: i32, b: i32) -> i32 {
+    crate::ffi::multiply(a, b)
+}
+
+/// Multiplies two integers in C, or with the mock in unit tests.
+#[cfg(test)]
+pub fn multiply(a: i32, b: i32) -> i32 {
+    MULTIPLY_MOCK.with(|mock| {
+        // A `Cell` only hands out its value by moving it, so the mock is
+        // swapped out for the call and put back afterwards. A mock that
+        // calls `multiply` itself gets the placeholder, which panics.
+        let f = mock.replace(Box::new(|_, _| {
+            panic!("the multiply() mock called multiply()")
+        }));
+        let product = f(a, b);
+        mock.set(f);
+        product
+    })
+}
+
+/// Multiplies every value by `factor` through [`multiply`].
+///
+/// The unit tests in this module check it against a mock.
+pub fn scale_all(values: &[i32], factor: i32) -> Vec<i32> {
+    values
+        .iter()
+        .map(|&value| multiply(value, factor))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::rc::Rc;
+
+    #[test]
+    fn uses_the_mock_instead_of_c() {
+        set_multiply_mock(|_, _| 42);
+
+        assert_eq!(scale_all(&[1, 2, 3], 10), vec![42, 42, 42]);
+    }
+
+    #[test]
+    fn passes_every_value_and_the_factor() {
+        let calls = Rc::new(Cell::new(0));
+        let seen = Rc::clone(&calls);
+        set_multiply_mock(move |a, b| {
+            seen.set(seen.get() + 1);
+            a - b
+        });
+
+        assert_eq!(scale_all(&[5, 7], 2), vec![3, 5]);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn empty_input_never_calls_multiply() {
+        // No mock: any call would panic.
+        assert_eq!(scale_all(&[], 10), Vec::<i32>::new());
+    }
+}
@@ -0,0 +1,103 @@
+//! Hidden global state in a C library.
+//!
+//! `c_src/global_state.c` keeps a counter in a `static int`, which Rust
+//! can't see or declare: the only way to it is through `bump_global()` and
+//! `read_global()`. Many C libraries work like this, with an error code, a
+//! cache or a configuration stored in a global that every call reads or
+//! writes.
+//!
+//! That state is shared by the whole process, whichever thread calls and
+//! whichever wrapper it goes through. `bump_global()` compiles to a load, an
+//! add and a store; two threads running it at once can both load the same
+//! value and lose an increment, and in C's memory model the unsynchronized
+//! accesses are undefined behavior, not just a wrong count. Nothing in the C
+//! signatures hints at this: neither function takes a pointer, so a Rust
+//! binding looks just like a pure function.
+//!
+//! A safe wrapper has to make the sharing visible to the compiler. Here that
+//! is [`GlobalCounter`]:
+//!
+//! - There is at most one at a time, since the state it stands for exists
+//!   only once. Two handles on different threads would race just like two
+//!   raw calls.
+//! - It is `Send`: handing the one handle to another thread is fine, since
+//!   then only that thread can call C.
+//! - It is not `Sync`. [`GlobalCounter::bump`] takes `&self`, like the C
+//!   function, which takes nothing. If the handle were `Sync`, several
+//!   threads could hold a `&GlobalCounter` and bump at the same time. `Cell`
+//!   is `!Sync` for the same reason.
+//!
+//! ```compile_fail
+//! use std::thread;
+//!
+//! use rust_ffi_to_c::global_state::GlobalCounter;
+//!
+//! let counter = GlobalCounter::take().unwrap();
+//! thread::scope(|s| {
+//!     s.spawn(|| counter.bump()); // `Cell<()>` cannot be shared between threads
+//!     s.spawn(|| counter.bump());
+//! });
+//! ```
+//!
+//! This only holds if nothing else calls the C functions behind the
+//! wrapper's back, including other C code in the process. To share the
+//! state between threads, put the handle in a `Mutex`, as
+//! `calculator::SharedCalculator` does, or change the C side to use atomics,
+//! like `atomic_call_count` in `crate::globals`.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn bump_global();
+        pub fn read_global() -> c_int;
+    }
+}
+
+// Whether the `GlobalCounter` exists.
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// The only way to the counter in `c_src/global_state.c`.
+///
+/// `Send` but not `Sync`: see the [module docs](self).
+#[derive(Debug)]
+pub struct GlobalCounter {
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl GlobalCounter {
+    /// Takes the handle, or returns `None` if someone else holds it.
+    pub fn take() -> Option<Self> {
+        TAKEN
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| GlobalCounter {
+                _not_sync: PhantomData,
+            })
+    }
+
+    /// Adds one to the C counter.
+    pub fn bump(&self) {
+        // SAFETY: there is one handle, and it isn't `Sync`, so only the
+        // thread holding it can be calling into the counter.
+        unsafe { raw::bump_global() }
+    }
+
+    /// Reads the C counter.
+    pub fn read(&self) -> i32 {
+        // SAFETY: see `bump`.
+        unsafe { raw::read_global() }
+    }
+}
+
+impl Drop for GlobalCounter {
+    fn drop(&mut self) {
+        // Releases the calls made through this handle to whichever thread
+        // takes the next one.
+        TAKEN.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,139 @@
+//! Reading and writing global variables defined in C.
+//!
+//! There are two ways to get at `call_count` in `c_src/counter.c`, which C's
+//! `multiply()` increments on every call:
+//!
+//! - Through the functions C provides for it, [`increment`] and
+//!   [`call_count`]. This is usually the better choice: C stays free to
+//!   change how the counter is stored, or to add locking, without breaking
+//!   Rust, and it is the only choice if the C global is `static`.
+//! - Directly, by declaring it in an `extern` block as a `static mut`, like
+//!   [`call_count_direct`] and [`reset_direct`] do. Every access needs
+//!   `unsafe`: the compiler can't see what C does with the variable, and
+//!   nothing stops two threads from reading and writing it at the same time.
+//!
+//! `multiply()` is safe to call from any thread, so C declares the counter
+//! as an `_Atomic int`, and the direct accesses are atomic too: they turn
+//! the address of the `static mut` into an `AtomicI32` with
+//! [`AtomicI32::from_ptr`]. `_Atomic int` in C has the same size, alignment
+//! and representation as an `AtomicI32` in Rust on the mainstream
+//! platforms: `AtomicI32` is `#[repr(C, align(4))]` around an `i32`. Reading
+//! or writing it as a plain `c_int` while another thread calls `multiply()`
+//! would be a data race, and undefined behavior.
+//!
+//! Atomic accesses don't stop a reset from landing in the middle of someone
+//! else's count, though. Every function here that touches the variable
+//! takes a [`CallCountToken`], of which there is only ever one, so whoever
+//! holds it knows nobody else resets the counter from under them. Calls to
+//! `multiply()` from other threads still count.
+//!
+//! An atomic global can also be declared as an atomic on the Rust side. The
+//! atomic type has interior mutability, so it is declared as a plain
+//! `static` and accessed through `&self` methods without `unsafe`, see
+//! [`atomic_call_count`].
+
+use core::ffi::c_int;
+use core::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+mod raw {
+    use core::ffi::c_int;
+    use core::sync::atomic::AtomicI32;
+
+    extern "C" {
+        pub fn increment();
+        pub fn get_call_count() -> c_int;
+        pub fn increment_atomic();
+
+        // An `_Atomic int` in C, so it must only be accessed atomically.
+        #[link_name = "call_count"]
+        pub static mut CALL_COUNT: c_int;
+
+        #[link_name = "atomic_call_count"]
+        pub static ATOMIC_CALL_COUNT: AtomicI32;
+    }
+}
+
+// Whether the `CallCountToken` exists.
+static TOKEN_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Permission to reset and count with `call_count` in `c_src/counter.c`.
+///
+/// At most one token exists at a time. Dropping it lets [`take`] hand out
+/// a new one.
+///
+/// [`take`]: CallCountToken::take
+#[derive(Debug)]
+pub struct CallCountToken {
+    _private: (),
+}
+
+impl CallCountToken {
+    /// Takes the token, or returns `None` if someone else holds it.
+    pub fn take() -> Option<Self> {
+        TOKEN_TAKEN
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| CallCountToken { _private: () })
+    }
+}
+
+impl Drop for CallCountToken {
+    fn drop(&mut self) {
+        TOKEN_TAKEN.store(false, Ordering::Release);
+    }
+}
+
+/// Increments the C counter, like a call to `multiply()` does.
+pub fn increment(_token: &mut CallCountToken) {
+    // SAFETY: `increment` has no preconditions, and updates the variable
+    // atomically.
+    unsafe { raw::increment() }
+}
+
+/// Reads the C counter through its accessor function.
+pub fn call_count(_token: &CallCountToken) -> i32 {
+    // SAFETY: `get_call_count` has no preconditions, and reads the variable
+    // atomically.
+    unsafe { raw::get_call_count() }
+}
+
+/// Reads the C counter directly.
+pub fn call_count_direct(_token: &CallCountToken) -> i32 {
+    call_count_atomic().load(Ordering::SeqCst)
+}
+
+/// Resets the C counter directly, without going through C.
+pub fn reset_direct(_token: &mut CallCountToken) {
+    call_count_atomic().store(0, Ordering::SeqCst);
+}
+
+// `call_count` as the atomic it is in C.
+fn call_count_atomic() -> &'static AtomicI32 {
+    // SAFETY: `addr_of_mut!` takes the address without creating a reference
+    // to a `static mut`. The variable lives for the whole program, is an
+    // `_Atomic int` in C, which has the layout of `AtomicI32`, and is only
+    // ever accessed atomically, in C and here.
+    unsafe { AtomicI32::from_ptr(core::ptr::addr_of_mut!(raw::CALL_COUNT)) }
+}
+
+/// Atomically increments the C atomic counter, from C.
+pub fn increment_atomic() {
+    // SAFETY: `increment_atomic` has no preconditions.
+    unsafe { raw::increment_atomic() }
+}
+
+/// Reads the C atomic counter directly. Safe to call from any thread.
+pub fn atomic_call_count() -> i32 {
+    // SAFETY: the variable is defined in C as an `_Atomic int`, which has the
+    // layout of `AtomicI32`, and is only ever accessed atomically.
+    unsafe { raw::ATOMIC_CALL_COUNT.load(Ordering::SeqCst) }
+}
+
+/// Atomically increments the C atomic counter, from Rust.
+pub fn increment_atomic_from_rust() -> i32 {
+    // SAFETY: see `atomic_call_count`.
+    unsafe { raw::ATOMIC_CALL_COUNT.fetch_add(1, Ordering::SeqCst) + 1 }
+}
+
+// `c_int` has to be `i32` for the `AtomicI32` declaration to make sense.
+const _: () = assert!(core::mem::size_of::<c_int>() == core::mem::size_of::<AtomicI32>());
@@ -0,0 +1,81 @@
+//! Examples of calling C from Rust, and Rust from C.
+//!
+//! Each module covers one FFI pattern and keeps its raw `extern "C"`
+//! declarations private, exposing only safe wrappers. The exception is
+//! [`raw`], which re-exports the declarations behind [`ffi`] for callers who
+//! want them. The C side of every example lives in `c_src/`.
+//!
+//! ```
+//! use rust_ffi_to_c::multiply;
+//!
+//! assert_eq!(multiply(6, 7), 42);
+//! ```
+
+// Defining variadic functions in Rust is unstable, see `varargs`.
+#![cfg_attr(feature = "nightly", feature(c_variadic))]
+#![deny(missing_docs)]
+
+// build.rs can only link libmultiply one way. Cargo features are additive,
+// so nothing else stops both from being turned on.
+#[cfg(all(feature = "static-link", feature = "dynamic-link"))]
+compile_error!(
+    "the `static-link` and `dynamic-link` (or `shared-link`) features can't be used together; \
+     `static-link` is a default feature, so build with `--no-default-features --features \
+     bundled,dynamic-link`"
+);
+
+pub mod alloc_string;
+pub mod allocator;
+pub mod arithmetic;
+pub mod arrays;
+#[cfg(feature = "tokio")]
+pub mod async_ffi;
+#[cfg(feature = "bindgen")]
+mod bindings;
+pub mod booleans;
+pub mod c_result;
+pub mod calculate;
+pub mod calculator;
+pub mod callbacks;
+pub mod cli;
+pub mod color;
+pub mod context;
+pub mod convert;
+pub mod counter;
+pub mod cstr_ownership;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+pub mod errors;
+pub mod exported;
+pub mod ffi;
+pub mod ffi_shim;
+pub mod global_state;
+pub mod globals;
+pub mod library;
+pub mod linked_list;
+pub mod logging;
+pub mod matrix;
+pub mod nullable;
+pub mod numeric;
+pub mod outparam;
+pub mod panic_boundary;
+pub mod progress;
+mod ptr_ext;
+pub mod range;
+pub mod raw;
+pub mod record;
+pub mod ring_buffer;
+pub mod roundtrip;
+pub mod safe;
+pub mod sort;
+pub mod status;
+pub mod stdcall;
+pub mod strings;
+pub mod threads;
+pub mod types;
+pub mod union_demo;
+pub mod varargs;
+pub mod version;
+pub mod vtable;
+
+pub use ffi::multiply;
@@ -0,0 +1,143 @@
+//! A C library that must be initialized before use.
+//!
+//! Libraries like OpenSSL, libcurl and SDL have global `init()` and
+//! `cleanup()` functions: everything else may only be called in between,
+//! and each `init()` must be paired with one `cleanup()`. In C that is up to
+//! the programmer to remember. `c_src/library.c` works the same way, with
+//! `library_init()` and `library_deinit()`, and aborts if
+//! `library_next_id()` is called at the wrong time.
+//!
+//! Rust can make the pairing automatic and the mistakes impossible:
+//!
+//! - [`LibraryGuard::new`] calls `library_init()`, and dropping the guard
+//!   calls `library_deinit()`, so every init has exactly one deinit, even if
+//!   the code in between returns early or panics.
+//! - Every function that needs the library takes a `&LibraryGuard`. There is
+//!   no other way to get one, so calling them before init or after deinit
+//!   doesn't compile.
+//!
+//! # Sharing one guard
+//!
+//! The C library only allows one initialization at a time, so code that
+//! runs on several threads, such as the tests in one test binary, has to
+//! share a single guard. The C functions are thread-safe, so
+//! `LibraryGuard` is `Sync`, and a `OnceLock` can create it on first use and
+//! hand out `&'static` references to it:
+//!
+//! ```
+//! use std::sync::OnceLock;
+//! use std::thread;
+//!
+//! use rust_ffi_to_c::library::{self, LibraryGuard};
+//!
+//! fn library_guard() -> &'static LibraryGuard {
+//!     static GUARD: OnceLock<LibraryGuard> = OnceLock::new();
+//!     GUARD.get_or_init(|| LibraryGuard::new().expect("library_init failed"))
+//! }
+//!
+//! thread::scope(|s| {
+//!     for _ in 0..4 {
+//!         s.spawn(|| library::next_id(library_guard()));
+//!     }
+//! });
+//! ```
+//!
+//! Statics are never dropped, so `library_deinit()` never runs: the library
+//! stays initialized until the process exits. That is usually fine for tests
+//! and command-line tools, and for libraries whose cleanup only frees
+//! memory, but not for ones that must flush or close something on the way
+//! out.
+
+use std::error::Error;
+use std::fmt;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn library_init() -> c_int;
+        pub fn library_deinit();
+        pub fn library_is_initialized() -> c_int;
+        pub fn library_next_id() -> c_int;
+    }
+}
+
+/// Error returned by [`LibraryGuard::new`] when `library_init()` fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitError {
+    /// What `library_init()` returned.
+    pub code: i32,
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "library_init() failed with {} (is another LibraryGuard alive?)",
+            self.code
+        )
+    }
+}
+
+impl Error for InitError {}
+
+/// Proof that the C library is initialized. Deinitializes it when dropped.
+///
+/// ```
+/// use rust_ffi_to_c::library::{self, LibraryGuard};
+///
+/// let guard = LibraryGuard::new().unwrap();
+/// assert_eq!(library::next_id(&guard), 1);
+///
+/// // The library can only be initialized once at a time.
+/// assert!(LibraryGuard::new().is_err());
+///
+/// drop(guard);
+/// assert!(!library::is_initialized());
+/// ```
+#[derive(Debug)]
+pub struct LibraryGuard {
+    _private: (),
+}
+
+impl LibraryGuard {
+    /// Initializes the C library.
+    ///
+    /// Fails if it is already initialized, because another guard is still
+    /// alive.
+    pub fn new() -> Result<Self, InitError> {
+        // SAFETY: `library_init` has no preconditions. It fails, without
+        // changing anything, if the library is already initialized.
+        let code = unsafe { raw::library_init() };
+
+        if code != 0 {
+            return Err(InitError { code });
+        }
+
+        Ok(LibraryGuard { _private: () })
+    }
+}
+
+impl Drop for LibraryGuard {
+    fn drop(&mut self) {
+        // SAFETY: this guard's `library_init` succeeded, and `drop` runs
+        // once, so this is the one matching `library_deinit`. Nothing can
+        // still be using the library through this guard, since it is
+        // borrowed by every such use.
+        unsafe { raw::library_deinit() }
+    }
+}
+
+/// Whether the C library is initialized. Unlike the other functions, this
+/// one works at any time.
+pub fn is_initialized() -> bool {
+    // SAFETY: `library_is_initialized` has no preconditions.
+    unsafe { raw::library_is_initialized() != 0 }
+}
+
+/// Returns a new ID, unique while the library stays initialized.
+pub fn next_id(_guard: &LibraryGuard) -> i32 {
+    // SAFETY: the guard proves the library is initialized, and
+    // `library_next_id` is thread-safe.
+    unsafe { raw::library_next_id() }
+}
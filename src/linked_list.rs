@@ -0,0 +1,226 @@
+//! Walking a linked list that C allocated.
+//!
+//! `struct Node` in `c_src/linked_list.c` points at the next node, so the
+//! Rust struct has to point at itself too: the `next` field is a
+//! `*mut Node`, NULL at the end of the list. Reading through it needs
+//! `unsafe`, since nothing tells the compiler that C keeps every `next`
+//! either NULL or pointing at a live node.
+//!
+//! [`CLinkedList`] owns the head and makes walking the list safe. Like
+//! [`std::vec::IntoIter`], iterating consumes it: each node is visited once,
+//! and the whole list goes back to C with `free_list()` when the
+//! `CLinkedList` is dropped, however far it was iterated.
+//!
+//! [`CList`] holds the same kind of list but lends it out instead: each call
+//! to [`CList::iter`] walks it from the start through a shared borrow, so it
+//! can be iterated any number of times, and the borrow checker makes sure
+//! no iterator outlives the nodes it points into:
+//!
+//! ```compile_fail
+//! use rust_ffi_to_c::linked_list::CList;
+//!
+//! let list = CList::new(3).unwrap();
+//! let mut iter = list.iter();
+//! drop(list); // error: `list` is still borrowed by `iter`
+//! iter.next();
+//! ```
+//!
+//! To check that nothing leaks, run the tests under valgrind:
+//!
+//! ```text
+//! valgrind --leak-check=full target/debug/deps/ffi_integration-<hash>
+//! ```
+
+use core::ffi::c_int;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::arrays::c_size_t;
+use crate::calculator::NullHandleError;
+
+mod raw {
+    use super::{c_size_t, Node};
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn make_list(values: *const c_int, len: c_size_t) -> *mut Node;
+        pub fn make_counting_list(n: c_int) -> *mut Node;
+        pub fn free_list(head: *mut Node);
+    }
+}
+
+/// The `Node` struct from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Node {
+    /// The value stored in this node.
+    pub value: c_int,
+    /// The next node, or NULL at the end of the list.
+    pub next: *mut Node,
+}
+
+/// A list of integers allocated by C, freed by C when dropped.
+///
+/// ```
+/// use rust_ffi_to_c::linked_list::CLinkedList;
+///
+/// let list = CLinkedList::new(&[1, 2, 3]).unwrap();
+/// assert_eq!(list.collect::<Vec<_>>(), [1, 2, 3]);
+/// ```
+pub struct CLinkedList {
+    // The first node, kept so `drop` can free them all. NULL for an empty
+    // list.
+    head: *mut Node,
+    // The next node to yield.
+    cursor: *mut Node,
+}
+
+impl CLinkedList {
+    /// Builds a list holding `values`, in order, in C.
+    pub fn new(values: &[i32]) -> Result<Self, NullHandleError> {
+        // SAFETY: `values` is valid for reading `values.len()` integers, and
+        // C copies them into the nodes.
+        let head = unsafe { raw::make_list(values.as_ptr(), values.len()) };
+
+        // C returns NULL for an empty list too.
+        if head.is_null() && !values.is_empty() {
+            return Err(NullHandleError);
+        }
+
+        Ok(CLinkedList { head, cursor: head })
+    }
+}
+
+impl Iterator for CLinkedList {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.cursor.is_null() {
+            return None;
+        }
+
+        // SAFETY: `cursor` is either NULL or points at a node of the list,
+        // which stays allocated until `self` is dropped. C sets every `next`
+        // to NULL or to another node of the same list.
+        let node = unsafe { &*self.cursor };
+        self.cursor = node.next;
+
+        Some(node.value)
+    }
+}
+
+impl Drop for CLinkedList {
+    fn drop(&mut self) {
+        // SAFETY: `head` came from `make_list` and is freed once, here.
+        // `free_list` accepts NULL.
+        unsafe { raw::free_list(self.head) }
+    }
+}
+
+// Shows the values not iterated over yet.
+impl fmt::Debug for CLinkedList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        let mut node = self.cursor;
+
+        while !node.is_null() {
+            // SAFETY: as in `next`.
+            let current = unsafe { &*node };
+            list.entry(&current.value);
+            node = current.next;
+        }
+
+        list.finish()
+    }
+}
+
+/// A list of integers allocated by C, which can be iterated any number of
+/// times.
+///
+/// ```
+/// use rust_ffi_to_c::linked_list::CList;
+///
+/// let list = CList::new(3).unwrap();
+/// assert_eq!(list.iter().collect::<Vec<_>>(), [0, 1, 2]);
+/// assert_eq!(list.iter().sum::<i32>(), 3);
+/// ```
+pub struct CList {
+    // The first node, NULL for an empty list. Only ever read through, never
+    // written, so shared references into the nodes can't alias a `&mut`.
+    head: *mut Node,
+}
+
+impl CList {
+    /// Builds the list `0, 1, ..., n - 1` in C. It is empty if `n` is 0 or
+    /// less.
+    pub fn new(n: i32) -> Result<Self, NullHandleError> {
+        // SAFETY: `make_counting_list` takes a plain integer and returns a
+        // list the caller owns.
+        let head = unsafe { raw::make_counting_list(n) };
+
+        // C returns NULL for an empty list too.
+        if head.is_null() && n > 0 {
+            return Err(NullHandleError);
+        }
+
+        Ok(CList { head })
+    }
+
+    /// Returns an iterator over the values, from the head of the list.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            node: self.head,
+            _list: PhantomData,
+        }
+    }
+}
+
+impl Drop for CList {
+    fn drop(&mut self) {
+        // SAFETY: `head` came from `make_counting_list` and is freed once,
+        // here. No `Iter` can still point into it, since they borrow `self`.
+        unsafe { raw::free_list(self.head) }
+    }
+}
+
+impl fmt::Debug for CList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a CList {
+    type Item = i32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`CList`], returned by [`CList::iter`].
+#[derive(Clone)]
+pub struct Iter<'a> {
+    // The next node to yield, NULL once the end is reached.
+    node: *const Node,
+    _list: PhantomData<&'a CList>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.node.is_null() {
+            return None;
+        }
+
+        // SAFETY: `node` is a node of the list, which stays allocated while
+        // the `CList` is borrowed for `'a`. C sets every `next` to NULL or
+        // to another node of the same list, and nothing writes to the nodes
+        // while they are shared.
+        let node = unsafe { &*self.node };
+        self.node = node.next;
+
+        Some(node.value)
+    }
+}
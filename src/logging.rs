@@ -0,0 +1,126 @@
+//! Forwarding log messages from C to the [`log`] crate.
+//!
+//! The C library logs through `c_log()`, which formats the message and
+//! hands it to whatever callback was registered with `set_log_callback()`,
+//! or drops it if there is none. [`install_c_logging`] registers one that
+//! passes every message on to [`log::log!`] with the target `libmultiply`,
+//! so C's messages end up wherever the program's logger sends Rust's, and
+//! are filtered by the same level.
+//!
+//! ```
+//! rust_ffi_to_c::logging::install_c_logging();
+//!
+//! // Logs "multiply(6, 7)" at debug level, if a logger is set up.
+//! assert_eq!(rust_ffi_to_c::multiply(6, 7), 42);
+//! ```
+//!
+//! # Threads
+//!
+//! C calls the callback on whichever thread is calling into the library,
+//! and keeps the user pointer until another callback replaces it. So
+//! [`set_log_handler`] only takes a handler that is `'static`, since C may
+//! call it at any time after it was registered, and `Sync`, since C may call
+//! it from several threads at once. A closure capturing a `Mutex` or an
+//! atomic passes both checks; one borrowing a local variable doesn't:
+//!
+//! ```compile_fail
+//! use rust_ffi_to_c::logging::set_log_handler;
+//!
+//! let lines = std::sync::Mutex::new(Vec::new());
+//! let handler = |_level, msg: &str| lines.lock().unwrap().push(msg.to_owned());
+//! set_log_handler(&handler);
+//! ```
+
+use core::ffi::{c_char, c_int, c_void};
+use std::ffi::CStr;
+
+use log::Level;
+
+/// The C signature `void (*)(int level, const char *msg, void *user)`.
+pub type LogCallback = unsafe extern "C" fn(c_int, *const c_char, *mut c_void);
+
+mod raw {
+    use super::LogCallback;
+    use core::ffi::c_void;
+
+    extern "C" {
+        pub fn set_log_callback(cb: Option<LogCallback>, user: *mut c_void);
+    }
+}
+
+/// Turns one of C's `LOG_LEVEL_*` numbers into a [`Level`].
+///
+/// They are numbered like [`Level`], from 1 for `Error` to 5 for `Trace`.
+/// Anything else is logged as an error, rather than dropped, since a
+/// message with a level C doesn't define is more likely a bug than noise.
+pub fn level_from_c(level: c_int) -> Level {
+    match level {
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        5 => Level::Trace,
+        _ => Level::Error,
+    }
+}
+
+unsafe extern "C" fn log_trampoline<F>(level: c_int, msg: *const c_char, user: *mut c_void)
+where
+    F: Fn(Level, &str) + Sync,
+{
+    crate::ffi_safe!((), {
+        if msg.is_null() {
+            return;
+        }
+
+        // SAFETY: `user` is the `&'static F` given to `set_log_handler`, which
+        // registered this instance of the trampoline with it, so it is valid
+        // and of the right type. It is shared between threads, which `F:
+        // Sync` allows.
+        let handler = unsafe { &*user.cast_const().cast::<F>() };
+
+        // SAFETY: C passes a NUL-terminated string that stays valid until
+        // the callback returns, and `msg` isn't used after that.
+        let msg = unsafe { CStr::from_ptr(msg) }.to_string_lossy();
+
+        handler(level_from_c(level), &msg);
+    })
+}
+
+/// Registers `handler` to receive every message the C code logs.
+///
+/// Replaces the handler registered before, if any. Invalid UTF-8 in a
+/// message is replaced with `U+FFFD`. A panic in `handler` is caught
+/// before it reaches C, and the message is dropped.
+pub fn set_log_handler<F>(handler: &'static F)
+where
+    F: Fn(Level, &str) + Sync,
+{
+    // SAFETY: the trampoline is instantiated for `F`, the type `user` points
+    // to. `handler` lives forever, so C may keep the pointer as long as it
+    // wants, and it is `Sync`, so C may use it from any thread.
+    unsafe {
+        raw::set_log_callback(
+            Some(log_trampoline::<F>),
+            (handler as *const F).cast_mut().cast(),
+        )
+    }
+}
+
+/// Stops passing on the C code's log messages, which C then drops.
+pub fn remove_log_handler() {
+    // SAFETY: a NULL callback turns logging off, and C never reads the user
+    // pointer without a callback.
+    unsafe { raw::set_log_callback(None, core::ptr::null_mut()) }
+}
+
+fn forward_to_log(level: Level, msg: &str) {
+    log::log!(target: "libmultiply", level, "{}", msg);
+}
+
+/// Forwards the C code's log messages to the [`log`] crate.
+///
+/// The records have the target `libmultiply`, and C's level converted by
+/// [`level_from_c`].
+pub fn install_c_logging() {
+    set_log_handler(&forward_to_log);
+}
@@ -0,0 +1,176 @@
+//! Passing 2D arrays to C as flat, row-major slices.
+//!
+//! C has no type for "an `n` by `n` array whose size is only known at
+//! runtime" that Rust can name, so the usual convention is a pointer to the
+//! first element and the dimensions, with element `(i, j)` at index
+//! `i * n + j`. A `&[f64]` of length `n * n` has exactly that layout.
+//!
+//! [`Matrix`] keeps the size and the elements together, so the size can't
+//! be wrong by the time C sees them.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::arrays::c_size_t;
+
+mod raw {
+    use super::c_size_t;
+    use core::ffi::c_double;
+
+    extern "C" {
+        pub fn mat_mul(a: *const c_double, b: *const c_double, c: *mut c_double, n: c_size_t);
+    }
+}
+
+/// Error returned when matrix dimensions don't fit together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionError {
+    /// [`Matrix::new`] got a number of elements other than `n * n`.
+    WrongLength {
+        /// The number of rows and columns.
+        n: usize,
+        /// The number of elements given.
+        len: usize,
+    },
+    /// [`Matrix::matmul`] got matrices of different sizes.
+    Mismatch {
+        /// The size of the left-hand matrix.
+        left: usize,
+        /// The size of the right-hand matrix.
+        right: usize,
+    },
+}
+
+impl fmt::Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimensionError::WrongLength { n, len } => {
+                write!(f, "a {}x{} matrix can't have {} elements", n, n, len)
+            }
+            DimensionError::Mismatch { left, right } => write!(
+                f,
+                "can't multiply a {}x{} matrix by a {}x{} matrix",
+                left, left, right, right
+            ),
+        }
+    }
+}
+
+impl Error for DimensionError {}
+
+/// A square matrix of `f64`s in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    n: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    /// Creates an `n` by `n` matrix from its elements in row-major order.
+    pub fn new(n: usize, data: Vec<f64>) -> Result<Self, DimensionError> {
+        if n.checked_mul(n) != Some(data.len()) {
+            return Err(DimensionError::WrongLength { n, len: data.len() });
+        }
+
+        Ok(Matrix { n, data })
+    }
+
+    /// The `n` by `n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        let data = (0..n * n)
+            .map(|index| if index / n == index % n { 1.0 } else { 0.0 })
+            .collect();
+
+        Matrix { n, data }
+    }
+
+    /// The number of rows, which is also the number of columns.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The elements in row-major order.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Multiplies `self` by `other` in C.
+    ///
+    /// ```
+    /// use rust_ffi_to_c::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// assert_eq!(a.matmul(&Matrix::identity(2)).unwrap(), a);
+    /// ```
+    pub fn matmul(&self, other: &Matrix) -> Result<Matrix, DimensionError> {
+        if self.n != other.n {
+            return Err(DimensionError::Mismatch {
+                left: self.n,
+                right: other.n,
+            });
+        }
+
+        Ok(Matrix {
+            n: self.n,
+            // The sizes match, and each `data` has `n * n` elements.
+            data: mat_mul_unchecked(&self.data, &other.data, self.n),
+        })
+    }
+}
+
+/// Multiplies the `n` by `n` matrices `a` and `b` in C.
+///
+/// All matrices are in row-major order. [`Matrix::matmul`] does the same
+/// with the size checks done up front.
+///
+/// ```
+/// use rust_ffi_to_c::matrix;
+///
+/// let a = [1.0, 2.0, 3.0, 4.0];
+/// let identity = [1.0, 0.0, 0.0, 1.0];
+/// assert_eq!(matrix::mat_mul(&a, &identity, 2), a);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `a` or `b` doesn't have exactly `n * n` elements. C trusts `n`,
+/// so a shorter slice would make it read out of bounds.
+pub fn mat_mul(a: &[f64], b: &[f64], n: usize) -> Vec<f64> {
+    let len = n.checked_mul(n).expect("matrix size overflowed usize");
+    assert!(
+        a.len() == len && b.len() == len,
+        "mat_mul needs two {n}x{n} matrices"
+    );
+
+    mat_mul_unchecked(a, b, n)
+}
+
+// `a` and `b` must have `n * n` elements each.
+fn mat_mul_unchecked(a: &[f64], b: &[f64], n: usize) -> Vec<f64> {
+    let len = n * n;
+    debug_assert!(a.len() == len && b.len() == len);
+
+    // The output starts out uninitialized: C writes every element, so there
+    // is no need to zero it first. Its length stays 0 until C is done, so
+    // the `Vec` never claims to hold elements that haven't been written.
+    let mut c = Vec::<f64>::with_capacity(len);
+
+    // SAFETY: `a` and `b` are valid for reading `n * n` doubles, and the
+    // spare capacity of `c` for writing as many. `c` is a fresh allocation,
+    // so it doesn't overlap `a` or `b`. C writes through the pointer without
+    // reading, so the elements being uninitialized doesn't matter.
+    unsafe {
+        raw::mat_mul(
+            a.as_ptr(),
+            b.as_ptr(),
+            c.spare_capacity_mut().as_mut_ptr().cast(),
+            n,
+        )
+    };
+
+    // SAFETY: C has initialized all `len` elements, and the capacity is at
+    // least `len`.
+    unsafe { c.set_len(len) };
+
+    c
+}
@@ -0,0 +1,114 @@
+//! A C function that may return NULL, as an `Option`.
+//!
+//! `find_first()` returns a pointer into the array it was given, or NULL if
+//! the value isn't there. The usual binding returns `*mut c_int`, and every
+//! caller has to remember to check it. This one returns
+//! `Option<NonNull<c_int>>` instead, so the check can't be forgotten.
+//!
+//! That is a valid declaration for a C function returning `int *`:
+//! `NonNull<T>` is never NULL, so `Option` uses NULL to represent `None`,
+//! and the Rust reference guarantees that `Option<NonNull<T>>` has the same
+//! size and ABI as `*mut T`. The same holds for `Option<&T>`, `Option<&mut
+//! T>`, `Option<Box<T>>` and `Option<extern "C" fn()>`, which is how
+//! `crate::callbacks` declares its nullable callbacks.
+//!
+//! [`find_first`] turns the pointer back into an index by subtracting the
+//! start of the slice from it.
+//!
+//! # Optional arguments
+//!
+//! Going the other way, C APIs often take a pointer that may be NULL to mean
+//! "use the defaults", like `configure()`. On the Rust side that is an
+//! `Option<&Config>`, and [`configure`] turns `None` into `ptr::null()`. The
+//! declaration could take the `Option<&Config>` directly, for the same
+//! reason as above, but spelling the conversion out shows what crosses the
+//! boundary.
+//!
+//! [`rust_configure`](crate::exported::rust_configure) is the same function
+//! written in Rust for C to call, receiving the pointer as an
+//! `Option<NonNull<Config>>`, so the NULL check can't be skipped.
+//!
+//! # Pointers declared as they are in C
+//!
+//! The other wrappers in this crate keep C's `*mut T` in their
+//! declarations and check it right after the call, with the crate's
+//! internal `PtrExt` trait. [`maybe_value`] shows it on the smallest C
+//! function that may or may not return NULL, `maybe_value()`.
+
+use core::ffi::c_int;
+use std::ptr::{self, NonNull};
+
+use crate::arrays::c_size_t;
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use super::{c_size_t, Config};
+    use core::ffi::c_int;
+    use std::ptr::NonNull;
+
+    extern "C" {
+        pub fn find_first(arr: *mut c_int, len: c_size_t, target: c_int) -> Option<NonNull<c_int>>;
+        pub fn configure(cfg: *const Config) -> c_int;
+        pub fn maybe_value(fail: c_int) -> *mut c_int;
+    }
+}
+
+/// Returns the index of the first element of `haystack` equal to `target`,
+/// found by C.
+pub fn find_first(haystack: &[i32], target: i32) -> Option<usize> {
+    // SAFETY: `haystack` is valid for reading `haystack.len()` integers. C
+    // takes a non-const pointer but only reads through it, so passing one
+    // derived from a shared borrow is fine.
+    let found: NonNull<c_int> =
+        unsafe { raw::find_first(haystack.as_ptr().cast_mut(), haystack.len(), target) }?;
+
+    // SAFETY: C returns either NULL, handled above, or a pointer to one of
+    // the elements of `haystack`, so both pointers are in the same
+    // allocation and the distance between them is a whole number of
+    // elements.
+    let index = unsafe { found.as_ptr().offset_from(haystack.as_ptr()) };
+
+    // A pointer into the slice is never before its start.
+    Some(index as usize)
+}
+
+/// Settings for [`configure`], laid out like `Config` in `c_src/nullable.c`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// How many times to try again after the first try.
+    pub retries: c_int,
+    /// How long each try may take, in milliseconds.
+    pub timeout_ms: c_int,
+}
+
+/// The retries C uses without a [`Config`], `CONFIG_DEFAULT_RETRIES`.
+pub const CONFIG_DEFAULT_RETRIES: c_int = 3;
+/// The timeout C uses without a [`Config`], `CONFIG_DEFAULT_TIMEOUT_MS`.
+pub const CONFIG_DEFAULT_TIMEOUT_MS: c_int = 100;
+
+/// Asks C how long an operation may take at most with `cfg`, or with the
+/// defaults if it is `None`, in milliseconds.
+///
+/// The total wraps around if it overflows.
+pub fn configure(cfg: Option<&Config>) -> i32 {
+    let ptr: *const Config = match cfg {
+        Some(cfg) => cfg,
+        None => ptr::null(),
+    };
+
+    // SAFETY: `ptr` is NULL, which C checks for, or points to a `Config`
+    // that is borrowed for the duration of the call. C only reads it.
+    unsafe { raw::configure(ptr) }
+}
+
+/// Reads the value C points to, or returns `None` if it returns NULL, which
+/// it does whenever `fail` is `true`.
+pub fn maybe_value(fail: bool) -> Option<i32> {
+    // SAFETY: `maybe_value` takes a plain integer.
+    let ptr = unsafe { raw::maybe_value(c_int::from(fail)) }.to_option()?;
+
+    // SAFETY: a non-NULL pointer points to a static `int` in C, which is
+    // always initialized and never freed.
+    Some(unsafe { ptr.as_ptr().read() })
+}
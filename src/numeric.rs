@@ -0,0 +1,30 @@
+//! Passing floating-point values to C.
+//!
+//! `c_double` is an alias for `f64`, so a `double` crosses the boundary
+//! without any conversion, the same way `c_int` does for the integer
+//! examples in [`crate::ffi`]. That includes NaN: C receives the same bits
+//! Rust passed, and a NaN operand gives a NaN result, so it propagates back
+//! to Rust instead of being turned into an error. Check with
+//! [`f64::is_nan`] if you need to, since NaN never compares equal to
+//! anything, itself included.
+
+mod raw {
+    use core::ffi::c_double;
+
+    extern "C" {
+        pub fn scale(value: c_double, factor: c_double) -> c_double;
+    }
+}
+
+/// Multiplies `v` by `f` in C.
+///
+/// ```
+/// use rust_ffi_to_c::numeric;
+///
+/// assert_eq!(numeric::scale(2.5, 0.5), 1.25);
+/// assert!(numeric::scale(f64::NAN, 2.0).is_nan());
+/// ```
+pub fn scale(v: f64, f: f64) -> f64 {
+    // SAFETY: `scale` takes and returns plain values.
+    unsafe { raw::scale(v, f) }
+}
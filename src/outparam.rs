@@ -0,0 +1,51 @@
+//! Getting a value out of C through an out pointer, next to a status code.
+//!
+//! `int safe_sqrt(double input, double *result)` is the classic shape: the
+//! return value says whether it worked, and the actual result is written
+//! through a pointer, but only on success. Reading the result on the error
+//! path would read memory C never wrote, which is why it lives in a
+//! `MaybeUninit` until the status code says otherwise.
+
+use std::error::Error;
+use std::fmt;
+use std::mem::MaybeUninit;
+
+mod raw {
+    use core::ffi::{c_double, c_int};
+
+    extern "C" {
+        pub fn safe_sqrt(input: c_double, result: *mut c_double) -> c_int;
+    }
+}
+
+/// Error returned by [`safe_sqrt`] for negative inputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeInputError(pub f64);
+
+impl fmt::Display for NegativeInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot take the square root of {}", self.0)
+    }
+}
+
+impl Error for NegativeInputError {}
+
+/// Returns the square root of `x`, computed in C.
+///
+/// C only rejects inputs below zero, so `NaN` comes back as `NaN` and
+/// infinity as infinity.
+pub fn safe_sqrt(x: f64) -> Result<f64, NegativeInputError> {
+    let mut result = MaybeUninit::<f64>::uninit();
+
+    // SAFETY: `result` is valid for writing one `double` and outlives the
+    // call.
+    let status = unsafe { raw::safe_sqrt(x, result.as_mut_ptr()) };
+
+    if status != 0 {
+        // `result` is still uninitialized here, and is simply dropped.
+        return Err(NegativeInputError(x));
+    }
+
+    // SAFETY: C returned 0, so it has written the result.
+    Ok(unsafe { result.assume_init() })
+}
@@ -0,0 +1,52 @@
+//! Keeping panics from unwinding into C.
+//!
+//! A panic in a Rust function called from C must not unwind out of it: the
+//! C frames above have no unwind information, so this used to be undefined
+//! behavior, and since Rust 1.81 it aborts the process.
+//! [`ffi_safe!`](crate::ffi_safe) wraps the body of an exported function in
+//! [`std::panic::catch_unwind`] and returns a sentinel value to C instead,
+//! the pattern `exported::rust_divide` spells out by hand.
+//!
+//! # `panic = "abort"`
+//!
+//! With `panic = "abort"` in the profile, like the `no-std` profile in
+//! `Cargo.toml`, a panic never unwinds, so there is nothing for
+//! `catch_unwind` to catch: the process aborts on the spot and the sentinel
+//! is never returned. That is still safe for the C caller, which is simply
+//! never returned to, but it means C can't recover from the error.
+
+/// Runs a block, returning `$sentinel` instead if it panics.
+///
+/// Meant for the body of `extern "C"` functions, so a panic is reported to
+/// C as an ordinary return value instead of unwinding into it. The panic
+/// message is still printed by the panic hook, followed by
+/// `panic in FFI`.
+///
+/// The block is wrapped in [`AssertUnwindSafe`](std::panic::AssertUnwindSafe),
+/// so it can capture raw pointers and `&mut` references. Make sure the
+/// sentinel tells C not to trust anything the block may have half written.
+///
+/// ```
+/// use core::ffi::c_int;
+/// use rust_ffi_to_c::ffi_safe;
+///
+/// extern "C" fn always_panics() -> c_int {
+///     ffi_safe!(-1, {
+///         panic!("something went wrong");
+///     })
+/// }
+///
+/// assert_eq!(always_panics(), -1);
+/// ```
+#[macro_export]
+macro_rules! ffi_safe {
+    ($sentinel:expr, $body:block) => {
+        match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| $body)) {
+            Ok(value) => value,
+            Err(_) => {
+                eprintln!("panic in FFI");
+                $sentinel
+            }
+        }
+    };
+}
@@ -0,0 +1,204 @@
+//! Reporting progress from a long-running C function to a Rust closure.
+//!
+//! `slow_sum()` takes a progress callback and a `void *user` pointer, which
+//! it passes back to every call. [`slow_sum`] uses the same trampoline
+//! pattern as `callbacks::for_each`: the closure lives on the caller's stack
+//! for the whole call, C only ever sees a pointer to it, and a monomorphized
+//! `extern "C"` function turns the pointer back into the closure.
+//!
+//! Progress callbacks are where panics tend to happen in practice: a closed
+//! channel, a failed write to the terminal. Unwinding out of an
+//! `extern "C"` function aborts the process, so the trampoline catches the
+//! panic, stops calling the closure, and the panic is resumed once C has
+//! returned. `slow_sum()` can't be told to stop early, so the rest of the
+//! computation still runs.
+//!
+//! # Cancellation
+//!
+//! `slow_sum_cancellable()` can be stopped from another thread: it takes a
+//! pointer to a flag and gives up as soon as it reads a non-zero value. The
+//! flag is an `_Atomic int` in C and an `AtomicI32` in Rust, which have the
+//! same layout (see `crate::globals`). [`CancellableTask`] owns the flag in
+//! an `Arc`, and hands out [`CancelToken`]s that share it, so the flag lives
+//! as long as the longest of them, and in particular until C has returned
+//! from a [`CancellableTask::run`] that is still using it.
+
+use core::ffi::{c_int, c_longlong, c_uint, c_void};
+use core::sync::atomic::{AtomicI32, Ordering};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// The C signature `void (*)(unsigned percent, void *user)`.
+pub type ProgressCallback = extern "C" fn(c_uint, *mut c_void);
+
+const SLOW_SUM_OK: c_int = 0;
+const SLOW_SUM_CANCELLED: c_int = 1;
+
+mod raw {
+    use super::ProgressCallback;
+    use core::ffi::{c_int, c_longlong, c_void};
+    use core::sync::atomic::AtomicI32;
+
+    extern "C" {
+        pub fn slow_sum(
+            n: c_longlong,
+            progress: Option<ProgressCallback>,
+            user: *mut c_void,
+        ) -> c_longlong;
+
+        pub fn slow_sum_cancellable(
+            n: c_longlong,
+            cancel_flag: *const AtomicI32,
+            out: *mut c_longlong,
+        ) -> c_int;
+    }
+}
+
+// What `slow_sum` hands to C as the user pointer.
+struct ProgressState<F> {
+    on_progress: F,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+extern "C" fn progress_trampoline<F: FnMut(u8)>(percent: c_uint, user: *mut c_void) {
+    // SAFETY: C passes back the pointer `slow_sum` gave it, which points to
+    // a `ProgressState<F>` that outlives the call. Nothing else accesses it
+    // while C is running, so the `&mut` is unique.
+    let state = unsafe { &mut *user.cast::<ProgressState<F>>() };
+
+    if state.panic.is_some() {
+        return;
+    }
+
+    // C never reports more than 100.
+    let percent = percent.min(100) as u8;
+
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (state.on_progress)(percent))) {
+        state.panic = Some(payload);
+    }
+}
+
+/// Computes `0 + 1 + ... + (n - 1)` in C, wrapping around on overflow,
+/// calling `on_progress` with the percentage done as it goes.
+///
+/// `on_progress` is called with 0 first and 100 last, and the values in
+/// between never go down.
+///
+/// # Panics
+///
+/// Panics if `n` doesn't fit in a C `long long`. If `on_progress` panics, it
+/// isn't called again, and the panic is resumed once C has finished.
+pub fn slow_sum<F: FnMut(u8)>(n: u64, on_progress: F) -> i64 {
+    let n = c_longlong::try_from(n).expect("n does not fit in a C long long");
+
+    let mut state = ProgressState {
+        on_progress,
+        panic: None,
+    };
+
+    // SAFETY: `state` lives on this stack frame until C returns, and C
+    // doesn't keep the user pointer after that, so it never dangles. The
+    // trampoline is instantiated for exactly the closure type in `state`.
+    let sum = unsafe {
+        raw::slow_sum(
+            n,
+            Some(progress_trampoline::<F>),
+            (&mut state as *mut ProgressState<F>).cast(),
+        )
+    };
+
+    if let Some(payload) = state.panic {
+        panic::resume_unwind(payload);
+    }
+
+    sum
+}
+
+/// Error returned by [`CancellableTask::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskError {
+    /// A [`CancelToken`] cancelled the task.
+    Cancelled,
+    /// C returned a status code that isn't in `multiply.h`.
+    Unknown(i32),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Cancelled => write!(f, "the computation was cancelled"),
+            TaskError::Unknown(status) => write!(f, "unknown status code {}", status),
+        }
+    }
+}
+
+impl Error for TaskError {}
+
+/// A handle that cancels a [`CancellableTask`], from any thread.
+#[derive(Debug, Clone)]
+pub struct CancelToken(Arc<AtomicI32>);
+
+impl CancelToken {
+    /// Asks the task to stop. C notices the next time it checks the flag,
+    /// which it does before every addition. Cancelling a task that has
+    /// already finished does nothing.
+    pub fn cancel(&self) {
+        self.0.store(1, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](CancelToken::cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed) != 0
+    }
+}
+
+/// A `slow_sum_cancellable()` call that hasn't run yet.
+#[derive(Debug)]
+pub struct CancellableTask {
+    n: c_longlong,
+    flag: Arc<AtomicI32>,
+}
+
+impl CancellableTask {
+    /// Prepares to compute `0 + 1 + ... + (n - 1)`, wrapping around on
+    /// overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` doesn't fit in a C `long long`.
+    pub fn new(n: u64) -> Self {
+        CancellableTask {
+            n: c_longlong::try_from(n).expect("n does not fit in a C long long"),
+            flag: Arc::new(AtomicI32::new(0)),
+        }
+    }
+
+    /// Returns a token that cancels this task.
+    pub fn cancel_token(&self) -> CancelToken {
+        CancelToken(Arc::clone(&self.flag))
+    }
+
+    /// Runs the computation in C on this thread, until it finishes or a
+    /// [`CancelToken`] cancels it. A task cancelled before it starts returns
+    /// `Err(TaskError::Cancelled)` straight away.
+    pub fn run(self) -> Result<i64, TaskError> {
+        let mut out = MaybeUninit::<c_longlong>::uninit();
+
+        // SAFETY: `self.flag` keeps the atomic alive until this function
+        // returns, after C does, and C only accesses it atomically. `out` is
+        // valid for writing one `long long`.
+        let status =
+            unsafe { raw::slow_sum_cancellable(self.n, Arc::as_ptr(&self.flag), out.as_mut_ptr()) };
+
+        match status {
+            // SAFETY: C writes `*out` before returning `SLOW_SUM_OK`.
+            SLOW_SUM_OK => Ok(unsafe { out.assume_init() }),
+            SLOW_SUM_CANCELLED => Err(TaskError::Cancelled),
+            other => Err(TaskError::Unknown(other)),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Checking pointers returned by C for NULL, in one place.
+//!
+//! The `raw` declarations keep C's signatures, so a function returning
+//! `int *` returns `*mut c_int`, NULL included. [`PtrExt`] turns such a
+//! pointer into an `Option` or a `Result` straight away, so the safe
+//! wrappers only ever hold a [`NonNull`], and the check reads the same
+//! everywhere. Crate-internal, since callers only ever see the `Option`s
+//! and `Result`s the wrappers return.
+
+use std::ptr::NonNull;
+
+/// NULL checks for the pointers C returns.
+pub(crate) trait PtrExt<T> {
+    /// Returns `None` for NULL.
+    fn to_option(self) -> Option<NonNull<T>>;
+
+    /// Returns `err` for NULL, for functions where NULL means they failed,
+    /// usually to allocate what the caller would have owned.
+    fn owned_or<E>(self, err: E) -> Result<NonNull<T>, E>;
+}
+
+impl<T> PtrExt<T> for *mut T {
+    fn to_option(self) -> Option<NonNull<T>> {
+        NonNull::new(self)
+    }
+
+    fn owned_or<E>(self, err: E) -> Result<NonNull<T>, E> {
+        self.to_option().ok_or(err)
+    }
+}
+
+impl<T> PtrExt<T> for *const T {
+    fn to_option(self) -> Option<NonNull<T>> {
+        // `NonNull` doesn't say whether the memory may be written through,
+        // so dropping the `const` here permits nothing by itself.
+        NonNull::new(self.cast_mut())
+    }
+
+    fn owned_or<E>(self, err: E) -> Result<NonNull<T>, E> {
+        self.to_option().ok_or(err)
+    }
+}
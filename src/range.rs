@@ -0,0 +1,166 @@
+//! Owning an array that C allocated.
+//!
+//! Memory has to go back to the allocator it came from. [`make_range`] gets
+//! an array from C's `malloc`, so it must never be dropped as a Rust `Vec`
+//! or `Box<[i32]>`, which would hand it to Rust's allocator instead. The
+//! [`CIntBuffer`] wrapper keeps the pointer and the length together, lends
+//! the contents out as a `&[i32]`, and gives the memory back to C with
+//! `free_range()` when dropped.
+//!
+//! When the caller wants a `Vec` anyway, [`make_range_vec`] does the simpler
+//! thing: it copies the array into a `Vec`, which Rust allocates, and frees
+//! the C copy right away.
+//!
+//! The slice borrows the buffer, so it can't outlive it:
+//!
+//! ```compile_fail
+//! use rust_ffi_to_c::range::make_range;
+//!
+//! let slice: &[i32] = {
+//!     let buffer = make_range(1, 3).unwrap();
+//!     &buffer
+//! }; // buffer is freed here
+//! println!("{:?}", slice);
+//! ```
+
+use core::ffi::c_int;
+use std::alloc::{self, Layout};
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::ptr;
+use std::slice;
+
+use crate::arrays::c_size_t;
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use core::ffi::c_int;
+
+    use crate::arrays::c_size_t;
+
+    extern "C" {
+        pub fn make_range(start: c_int, count: c_int) -> *mut c_int;
+        pub fn make_range_with_len(n: c_int, out_len: *mut c_size_t) -> *mut c_int;
+        pub fn free_range(values: *mut c_int);
+    }
+}
+
+/// Error returned by [`make_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range has more elements than C's `int` can count, or its last
+    /// element is past `i32::MAX`.
+    TooLong,
+    /// C returned NULL for a non-empty range.
+    AllocationFailed,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::TooLong => write!(f, "range does not fit in a C int"),
+            RangeError::AllocationFailed => write!(f, "C failed to allocate the range"),
+        }
+    }
+}
+
+impl Error for RangeError {}
+
+/// An array of integers allocated by C, freed by C when dropped.
+pub struct CIntBuffer {
+    // NULL for an empty buffer, since C doesn't allocate those.
+    ptr: *mut c_int,
+    len: usize,
+}
+
+// SAFETY: the integers are plain heap memory owned by this value, which
+// only hands out shared slices of them, so it can be shared like a
+// `Box<[i32]>`, and `free_range()` may free them on any thread.
+unsafe impl Send for CIntBuffer {}
+unsafe impl Sync for CIntBuffer {}
+
+impl Deref for CIntBuffer {
+    type Target = [i32];
+
+    fn deref(&self) -> &[i32] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+
+        // SAFETY: C allocated `len` initialized integers at `ptr`, and they
+        // stay allocated until `self` is dropped, which the returned slice's
+        // lifetime (tied to `&self`) can't outlast.
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for CIntBuffer {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` came from `make_range` and hasn't been freed yet.
+        // `free_range(NULL)` does nothing, like `free`.
+        unsafe { raw::free_range(self.ptr) }
+    }
+}
+
+impl fmt::Debug for CIntBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Has C allocate `count` consecutive integers starting at `start`.
+///
+/// An empty range is not an error: C returns NULL for it, and this returns
+/// an empty buffer.
+pub fn make_range(start: i32, count: usize) -> Result<CIntBuffer, RangeError> {
+    if count == 0 {
+        return Ok(CIntBuffer {
+            ptr: ptr::null_mut(),
+            len: 0,
+        });
+    }
+
+    let c_count = c_int::try_from(count).map_err(|_| RangeError::TooLong)?;
+
+    // C computes `start + count - 1`, which must not overflow.
+    start.checked_add(c_count - 1).ok_or(RangeError::TooLong)?;
+
+    // SAFETY: `make_range` takes plain integers, and neither the count nor
+    // the last element overflows.
+    let ptr = unsafe { raw::make_range(start, c_count) }.owned_or(RangeError::AllocationFailed)?;
+
+    Ok(CIntBuffer {
+        ptr: ptr.as_ptr(),
+        len: count,
+    })
+}
+
+/// Has C allocate `0, 1, ..., n - 1`, and copies it into a `Vec`.
+///
+/// Returns an empty `Vec` if `n` isn't positive. Like any other allocation
+/// in Rust, aborts if C runs out of memory.
+pub fn make_range_vec(n: i32) -> Vec<i32> {
+    let mut len: c_size_t = 0;
+
+    // SAFETY: `len` is a valid place for C to write the length to.
+    let Some(ptr) = unsafe { raw::make_range_with_len(n, &mut len) }.to_option() else {
+        if n > 0 {
+            alloc::handle_alloc_error(Layout::array::<c_int>(n as usize).unwrap());
+        }
+        return Vec::new();
+    };
+    let ptr = ptr.as_ptr();
+
+    // SAFETY: C allocated `len` initialized integers at `ptr`, and nothing
+    // frees them before the copy below is done. The slice isn't used after
+    // that.
+    let values = unsafe { slice::from_raw_parts(ptr, len) };
+    let copy = values.to_vec();
+
+    // SAFETY: `ptr` came from `make_range_with_len` and is freed only once.
+    // `values` borrowed it, but isn't used anymore.
+    unsafe { raw::free_range(ptr) };
+
+    copy
+}
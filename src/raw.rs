@@ -0,0 +1,16 @@
+//! The raw `extern "C"` declarations behind [`crate::ffi`].
+//!
+//! These are the functions from `c_src/multiply.c` exactly as C declares
+//! them, for callers who want to make the calls themselves. Every call is
+//! `unsafe`, and nothing checks for overflow: prefer the wrappers in
+//! [`crate::ffi`] and [`crate::safe`].
+//!
+//! ```
+//! // SAFETY: the product fits in a `c_int`.
+//! let product = unsafe { rust_ffi_to_c::raw::multiply(6, 7) };
+//! assert_eq!(product, 42);
+//! ```
+
+pub use crate::ffi::raw::{
+    multiply, multiply_batch, multiply_call_count, multiply_f32, multiply_f64, multiply_set_verbose,
+};
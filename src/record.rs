@@ -0,0 +1,158 @@
+//! A C struct with an array inside it.
+//!
+//! POSIX is full of structs like `struct utsname` or `struct sockaddr_un`
+//! that keep a string in a fixed-size `char name[N]` field, rather than
+//! pointing to it. The array is part of the struct: it is stored inline,
+//! copied whenever the struct is, and in Rust it is an array field,
+//! `[c_char; N]`, not a pointer.
+//!
+//! Nothing guarantees that the array holds a terminated string, or that its
+//! bytes are UTF-8. [`Record::new`] only builds records whose name fits with
+//! its terminator, and [`Record::name_as_str`] reads the name back without
+//! trusting that it does.
+
+use core::ffi::{c_char, c_int};
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::mem::{offset_of, size_of};
+use std::slice;
+use std::str;
+
+mod raw {
+    use super::Record;
+    use core::ffi::{c_char, c_double, c_int};
+
+    extern "C" {
+        pub fn make_record(name: *const c_char, id: c_int, score: c_double) -> Record;
+        pub fn print_record(r: Record);
+    }
+}
+
+/// The size of [`Record::name`], `RECORD_NAME_LEN` in C, terminator
+/// included.
+pub const RECORD_NAME_LEN: usize = 64;
+
+/// A record laid out like `Record` in `c_src/record.c`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Record {
+    /// The name, terminated by a NUL byte, as C expects.
+    pub name: [c_char; RECORD_NAME_LEN],
+    /// The record's ID.
+    pub id: c_int,
+    /// The record's score.
+    pub score: f64,
+}
+
+// The name is first and takes exactly `RECORD_NAME_LEN` bytes. Where `score`
+// goes after `id` depends on the platform's alignment for `double`, which
+// `#[repr(C)]` follows just like the C compiler.
+const _: () = assert!(size_of::<[c_char; RECORD_NAME_LEN]>() == RECORD_NAME_LEN);
+const _: () = assert!(offset_of!(Record, id) == RECORD_NAME_LEN);
+
+/// Error returned by [`Record::new`] when the name and its terminator don't
+/// fit in [`RECORD_NAME_LEN`] bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NameTooLongError {
+    /// The length of the name, in bytes.
+    pub len: usize,
+}
+
+impl fmt::Display for NameTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "name is {} bytes long, but at most {} fit",
+            self.len,
+            RECORD_NAME_LEN - 1
+        )
+    }
+}
+
+impl Error for NameTooLongError {}
+
+impl Record {
+    /// Builds a record in Rust, copying `name` into the array and
+    /// terminating it.
+    ///
+    /// Fails if `name` is longer than `RECORD_NAME_LEN - 1` bytes. As in C,
+    /// a NUL byte inside `name` ends the name there.
+    pub fn new(name: &str, id: i32, score: f64) -> Result<Record, NameTooLongError> {
+        if name.len() >= RECORD_NAME_LEN {
+            return Err(NameTooLongError { len: name.len() });
+        }
+
+        // All zeroes, so the name is terminated wherever it ends.
+        let mut array = [0; RECORD_NAME_LEN];
+        for (dst, &src) in array.iter_mut().zip(name.as_bytes()) {
+            *dst = src as c_char;
+        }
+
+        Ok(Record {
+            name: array,
+            id,
+            score,
+        })
+    }
+
+    /// Returns the name, up to its terminator.
+    ///
+    /// The fields are public and C may fill them too, so this doesn't
+    /// assume much: without a terminator, the whole array is the name, and
+    /// if it isn't valid UTF-8, only the part before the first invalid byte
+    /// is returned.
+    pub fn name_as_str(&self) -> &str {
+        // SAFETY: `c_char` is `i8` or `u8`, both of which have the same size
+        // and alignment as `u8`, and every bit pattern is valid for both. The
+        // slice borrows `self`.
+        let bytes: &[u8] =
+            unsafe { slice::from_raw_parts(self.name.as_ptr().cast::<u8>(), RECORD_NAME_LEN) };
+
+        let name = match CStr::from_bytes_until_nul(bytes) {
+            Ok(name) => name.to_bytes(),
+            Err(_) => bytes,
+        };
+
+        match str::from_utf8(name) {
+            Ok(name) => name,
+            // The prefix up to `valid_up_to` is valid by definition.
+            Err(err) => str::from_utf8(&name[..err.valid_up_to()]).unwrap(),
+        }
+    }
+}
+
+impl fmt::Debug for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Record")
+            .field("name", &self.name_as_str())
+            .field("id", &self.id)
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
+/// Builds a record in C and returns it by value.
+///
+/// C cuts `name` off after `RECORD_NAME_LEN - 1` bytes instead of failing.
+pub fn make_record(name: &CStr, id: i32, score: f64) -> Record {
+    // SAFETY: `name` is a valid, terminated string for the duration of the
+    // call, and C copies it instead of keeping the pointer. `Record` matches
+    // the C struct, so it is returned by value in the right place.
+    unsafe { raw::make_record(name.as_ptr(), id, score) }
+}
+
+/// Has C print `record`.
+///
+/// # Panics
+///
+/// Panics if the name isn't terminated, which can only happen to a record
+/// whose fields were filled in by hand. C's `%s` would read past the end of
+/// the array.
+pub fn print_record(record: &Record) {
+    assert!(record.name.contains(&0), "record name is not terminated");
+
+    // SAFETY: `Record` matches the C struct, and its name is terminated. C
+    // gets a copy and only reads it.
+    unsafe { raw::print_record(*record) }
+}
@@ -0,0 +1,309 @@
+//! A ring buffer whose memory layout is shared by C and Rust.
+//!
+//! The opaque handles in `crate::calculator` hide their fields, so only C
+//! can touch them. `RingBuffer` in `c_src/multiply.h` is the opposite: its
+//! layout is the interface, a header followed by the values in place.
+//!
+//! ```text
+//! typedef struct {
+//!     size_t capacity;
+//!     size_t head;      // index of the oldest value
+//!     size_t len;       // number of values
+//!     int data[];       // capacity values
+//! } RingBuffer;
+//! ```
+//!
+//! [`RawRingBuffer`] mirrors it with `#[repr(C)]`, so both languages can
+//! read and write the same memory without copying it. That is what makes
+//! layouts like this one useful between processes: one process maps a
+//! shared memory region and writes a ring buffer into it, and another maps
+//! the same region and reads it, knowing nothing but the layout.
+//!
+//! [`RingBuffer`] owns one created by C's `rb_create()`, and goes through
+//! C's `rb_push()` and `rb_pop()`. [`RingBufferView`] only borrows the
+//! memory, wherever it came from, and reads and writes it directly from
+//! Rust, so the two can be mixed freely:
+//!
+//! ```
+//! use rust_ffi_to_c::ring_buffer::RingBuffer;
+//!
+//! let mut rb = RingBuffer::new(4).unwrap();
+//! assert!(rb.push(1)); // C writes it...
+//! assert_eq!(rb.view().pop(), Some(1)); // ...and Rust reads it.
+//! ```
+//!
+//! Neither synchronizes anything, so both need `&mut self` to change the
+//! buffer. Sharing one between processes at the same time would need
+//! atomic indices, which this example leaves out.
+
+use core::ffi::{c_int, c_void};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{offset_of, size_of, MaybeUninit};
+use std::ptr::{self, NonNull};
+
+pub use crate::calculator::NullHandleError;
+use crate::ptr_ext::PtrExt;
+
+/// The header of the C `RingBuffer`, followed in memory by its values.
+///
+/// `data` is C's flexible array member: it takes no space in the struct,
+/// and marks where the `capacity` values start.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawRingBuffer {
+    /// How many values fit.
+    pub capacity: usize,
+    /// The index in `data` of the oldest value.
+    pub head: usize,
+    /// How many values there are.
+    pub len: usize,
+    /// The start of the values.
+    pub data: [c_int; 0],
+}
+
+// Three `size_t`s with no padding, as the `_Static_assert` in
+// c_src/ring_buffer.c checks on the C side.
+const _: () = assert!(offset_of!(RawRingBuffer, data) == 3 * size_of::<usize>());
+
+const RB_OK: c_int = 0;
+
+mod raw {
+    use super::RawRingBuffer;
+    use crate::arrays::c_size_t;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn rb_create(capacity: c_size_t) -> *mut RawRingBuffer;
+        pub fn rb_push(rb: *mut RawRingBuffer, value: c_int) -> c_int;
+        pub fn rb_pop(rb: *mut RawRingBuffer, out: *mut c_int) -> c_int;
+        pub fn rb_destroy(rb: *mut RawRingBuffer);
+        pub fn rb_data_offset() -> c_size_t;
+    }
+}
+
+/// A ring buffer created by C, destroyed by C when dropped.
+pub struct RingBuffer(NonNull<RawRingBuffer>);
+
+// SAFETY: the buffer is heap memory only reachable through this handle, and
+// `ring_buffer.c` keeps no global state, so it may be used and freed on any
+// thread, one at a time.
+unsafe impl Send for RingBuffer {}
+
+impl RingBuffer {
+    /// Creates an empty ring buffer with room for `capacity` values.
+    ///
+    /// Fails if `capacity` is 0 or C can't allocate it.
+    pub fn new(capacity: usize) -> Result<Self, NullHandleError> {
+        // SAFETY: `rb_create` takes any size, and returns NULL for the ones
+        // it can't allocate.
+        let ptr = unsafe { raw::rb_create(capacity) };
+
+        ptr.owned_or(NullHandleError).map(RingBuffer)
+    }
+
+    /// Appends `value`, or returns `false` if the buffer is full.
+    pub fn push(&mut self, value: i32) -> bool {
+        // SAFETY: the buffer is valid until `drop`, and `&mut self` means
+        // nothing else is using it.
+        unsafe { raw::rb_push(self.0.as_ptr(), value) == RB_OK }
+    }
+
+    /// Removes and returns the oldest value, or `None` if the buffer is
+    /// empty.
+    pub fn pop(&mut self) -> Option<i32> {
+        let mut out = MaybeUninit::<c_int>::uninit();
+
+        // SAFETY: as in `push`. `out` is valid for writes, and only read if
+        // C says it wrote it.
+        unsafe {
+            if raw::rb_pop(self.0.as_ptr(), out.as_mut_ptr()) == RB_OK {
+                Some(out.assume_init())
+            } else {
+                None
+            }
+        }
+    }
+
+    /// How many values the buffer holds.
+    pub fn len(&self) -> usize {
+        // SAFETY: the buffer is valid until `drop`, and nothing changes it
+        // while it is `&`-borrowed.
+        unsafe { (*self.0.as_ptr()).len }
+    }
+
+    /// Whether the buffer holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many values fit.
+    pub fn capacity(&self) -> usize {
+        // SAFETY: as in `len`.
+        unsafe { (*self.0.as_ptr()).capacity }
+    }
+
+    /// Borrows the buffer's memory as a [`RingBufferView`], which reads and
+    /// writes it directly instead of calling C.
+    pub fn view(&mut self) -> RingBufferView<'_> {
+        // SAFETY: C initialized the header, allocated room for `capacity`
+        // values after it and wrote the `len` it holds. The memory stays
+        // valid, and only reachable through the view, for as long as `self`
+        // is borrowed.
+        unsafe { RingBufferView::from_raw(self.0.as_ptr().cast()) }
+            .expect("C returned a valid ring buffer")
+    }
+
+    /// The offset of the values from the start of the buffer, as C sees it,
+    /// `offsetof(RingBuffer, data)`.
+    pub fn c_data_offset() -> usize {
+        // SAFETY: `rb_data_offset` takes no arguments and only returns a
+        // value.
+        unsafe { raw::rb_data_offset() }
+    }
+}
+
+impl Drop for RingBuffer {
+    fn drop(&mut self) {
+        // SAFETY: the buffer came from `rb_create`, and `drop` runs once.
+        unsafe { raw::rb_destroy(self.0.as_ptr()) }
+    }
+}
+
+impl fmt::Debug for RingBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+/// A ring buffer in memory someone else owns, read and written in place.
+///
+/// It never frees the memory, and borrows it for `'a`.
+pub struct RingBufferView<'a> {
+    ptr: NonNull<RawRingBuffer>,
+    _memory: PhantomData<&'a mut RawRingBuffer>,
+}
+
+impl<'a> RingBufferView<'a> {
+    /// Borrows the ring buffer at `ptr`.
+    ///
+    /// Returns `None` if `ptr` is NULL or the header is inconsistent: a
+    /// capacity of 0, or a head or length past the capacity. Since the
+    /// memory may have been written by another program, the header is
+    /// checked before any value is touched.
+    ///
+    /// # Safety
+    ///
+    /// If `ptr` isn't NULL, it must be aligned for a `RawRingBuffer` and
+    /// point to an initialized header followed by room for at least
+    /// `capacity` values, all valid for reads and writes for `'a`, and the
+    /// `len` values from `head` on must be initialized. Nothing else may
+    /// access the memory during `'a`.
+    pub unsafe fn from_raw(ptr: *mut c_void) -> Option<Self> {
+        let ptr = ptr.cast::<RawRingBuffer>().to_option()?;
+
+        // SAFETY: the caller guarantees an initialized header.
+        let header = unsafe { ptr.as_ref() };
+        if header.capacity == 0 || header.head >= header.capacity || header.len > header.capacity {
+            return None;
+        }
+
+        Some(RingBufferView {
+            ptr,
+            _memory: PhantomData,
+        })
+    }
+
+    fn header(&self) -> &RawRingBuffer {
+        // SAFETY: `from_raw`'s caller guarantees the header is valid and
+        // ours alone for `'a`.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    fn slot(&mut self, index: usize) -> *mut c_int {
+        debug_assert!(index < self.header().capacity);
+
+        // SAFETY: `from_raw`'s caller guarantees `capacity` values after the
+        // header, and `index` is below it. `addr_of_mut!` doesn't create a
+        // reference to `data`, whose type says it has no elements.
+        unsafe {
+            ptr::addr_of_mut!((*self.ptr.as_ptr()).data)
+                .cast::<c_int>()
+                .add(index)
+        }
+    }
+
+    /// Appends `value`, or returns `false` if the buffer is full.
+    pub fn push(&mut self, value: i32) -> bool {
+        let RawRingBuffer {
+            capacity,
+            head,
+            len,
+            ..
+        } = *self.header();
+        if len == capacity {
+            return false;
+        }
+
+        let slot = self.slot((head + len) % capacity);
+        // SAFETY: `slot` is in bounds and nothing else is using it.
+        unsafe {
+            slot.write(value);
+            (*self.ptr.as_ptr()).len = len + 1;
+        }
+        true
+    }
+
+    /// Removes and returns the oldest value, or `None` if the buffer is
+    /// empty.
+    pub fn pop(&mut self) -> Option<i32> {
+        let RawRingBuffer {
+            capacity,
+            head,
+            len,
+            ..
+        } = *self.header();
+        if len == 0 {
+            return None;
+        }
+
+        let slot = self.slot(head);
+        // SAFETY: as in `push`. The slot at `head` holds one of the `len`
+        // values, which are initialized, whether `push` or `rb_push()` or
+        // someone else wrote it.
+        unsafe {
+            let value = slot.read();
+            let header = self.ptr.as_ptr();
+            (*header).head = (head + 1) % capacity;
+            (*header).len = len - 1;
+            Some(value)
+        }
+    }
+
+    /// How many values the buffer holds.
+    pub fn len(&self) -> usize {
+        self.header().len
+    }
+
+    /// Whether the buffer holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How many values fit.
+    pub fn capacity(&self) -> usize {
+        self.header().capacity
+    }
+}
+
+impl fmt::Debug for RingBufferView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBufferView")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,22 @@
+//! Calling C that calls back into Rust by name.
+//!
+//! `compute_with_rust()` in `c_src/compute.c` calls `rust_add()`, which is
+//! defined in [`crate::exported`]. So [`compute_with_rust`] goes from Rust to
+//! C and back to Rust, with the linker resolving C's reference to
+//! `rust_add` against the Rust object files.
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn compute_with_rust(a: c_int, b: c_int) -> c_int;
+    }
+}
+
+/// Returns `a + b`, wrapping on overflow, by way of C calling
+/// `exported::rust_add`.
+pub fn compute_with_rust(a: i32, b: i32) -> i32 {
+    // SAFETY: `compute_with_rust` takes and returns plain integers, and so
+    // does the Rust function it calls.
+    unsafe { raw::compute_with_rust(a, b) }
+}
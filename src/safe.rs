@@ -0,0 +1,106 @@
+//! Multiplying without overflowing.
+//!
+//! `multiply()` wraps around on overflow, since signed overflow would be
+//! undefined behavior in C, and a wrapped product looks like any other. So
+//! these wrappers either check the inputs in Rust before calling
+//! `multiply()`, or call `multiply_checked()`, which checks in C and reports
+//! overflow through a status code.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use crate::ffi;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn multiply_checked(a: c_int, b: c_int, out: *mut c_int) -> c_int;
+    }
+}
+
+// Status codes from `c_src/multiply.h`.
+const MULTIPLY_OK: c_int = 0;
+const MULTIPLY_OVERFLOW: c_int = 1;
+
+/// Error returned by [`safe_multiply`] and [`multiply_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowError {
+    /// The product does not fit in an `i32`.
+    Overflow,
+    /// C returned something other than the product Rust computed.
+    Mismatch {
+        /// The product computed in Rust.
+        expected: i32,
+        /// What C returned.
+        actual: i32,
+    },
+    /// C returned a status code that isn't in `multiply.h`.
+    Unknown(i32),
+    /// The thread the multiplication ran on panicked, see
+    /// `async_ffi::multiply_async`.
+    ThreadPanicked,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowError::Overflow => write!(f, "multiplication overflowed i32"),
+            OverflowError::Mismatch { expected, actual } => {
+                write!(f, "C returned {} but the product is {}", actual, expected)
+            }
+            OverflowError::Unknown(status) => write!(f, "unknown status code {}", status),
+            OverflowError::ThreadPanicked => write!(f, "the thread multiplying panicked"),
+        }
+    }
+}
+
+impl Error for OverflowError {}
+
+/// Multiplies two integers in C, refusing inputs that would overflow.
+///
+/// C's `multiply()` wraps around on overflow, so the check has to happen on
+/// the Rust side *before* the call: once C has wrapped, the result can't be
+/// told apart from a real product anymore.
+pub fn safe_multiply(a: i32, b: i32) -> Result<i32, OverflowError> {
+    let expected = a.checked_mul(b).ok_or(OverflowError::Overflow)?;
+
+    let actual = ffi::multiply(a, b);
+
+    if actual != expected {
+        return Err(OverflowError::Mismatch { expected, actual });
+    }
+
+    Ok(actual)
+}
+
+/// Multiplies two integers in C, or returns `None` if the product would
+/// overflow, like [`i32::checked_mul`].
+///
+/// As with [`safe_multiply`], the check happens before the call, so C is
+/// never called with inputs that overflow.
+pub fn checked_multiply(a: i32, b: i32) -> Option<i32> {
+    a.checked_mul(b)?;
+
+    Some(ffi::multiply(a, b))
+}
+
+/// Multiplies two integers in C, letting C detect the overflow.
+///
+/// Unlike [`safe_multiply`], the check happens on the C side, with
+/// `__builtin_mul_overflow` where the compiler has it.
+pub fn multiply_checked(a: i32, b: i32) -> Result<i32, OverflowError> {
+    let mut out = MaybeUninit::<c_int>::uninit();
+
+    // SAFETY: `out` is valid for writing one `c_int`.
+    let status = unsafe { raw::multiply_checked(a, b, out.as_mut_ptr()) };
+
+    match status {
+        // SAFETY: C writes `out` before returning `MULTIPLY_OK`.
+        MULTIPLY_OK => Ok(unsafe { out.assume_init() }),
+        MULTIPLY_OVERFLOW => Err(OverflowError::Overflow),
+        other => Err(OverflowError::Unknown(other)),
+    }
+}
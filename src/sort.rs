@@ -0,0 +1,66 @@
+//! Letting C sort a Rust buffer in place.
+//!
+//! A `&mut [i32]` is passed as a pointer and a length, like in
+//! `crate::arrays`, so C works on the slice's own memory without copying
+//! it. [`sort_slice_via_c`] calls `sort_ints()`, which hands the buffer to
+//! the C library's `qsort` with a comparator written in C.
+//!
+//! `qsort` takes its comparator as a function pointer,
+//! `int (*)(const void *, const void *)`. [`sort_slice_with_qsort`] calls
+//! `qsort` directly with a comparator written in Rust, which has to be a
+//! named `extern "C" fn`: a closure has no C calling convention, and one
+//! that captures state has nowhere to put it, since `qsort` has no user
+//! pointer to pass it through (compare `callbacks::for_each`).
+
+use core::ffi::{c_int, c_void};
+use std::mem;
+
+use crate::arrays::c_size_t;
+
+mod raw {
+    use super::c_size_t;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn sort_ints(arr: *mut c_int, len: c_size_t);
+    }
+}
+
+/// Sorts `s` in ascending order, in C.
+pub fn sort_slice_via_c(s: &mut [i32]) {
+    // SAFETY: `s` is valid for reading and writing `s.len()` integers, and
+    // borrowed mutably for the duration of the call.
+    unsafe { raw::sort_ints(s.as_mut_ptr(), s.len()) }
+}
+
+/// The comparator [`sort_slice_with_qsort`] gives `qsort`.
+///
+/// # Safety
+///
+/// `a` and `b` must point to valid `c_int`s, which `qsort` guarantees for
+/// the array it was given.
+unsafe extern "C" fn compare_ints(a: *const c_void, b: *const c_void) -> c_int {
+    // SAFETY: `qsort` passes pointers to elements of the array, which holds
+    // `c_int`s.
+    let (a, b) = unsafe { (*a.cast::<c_int>(), *b.cast::<c_int>()) };
+
+    // `Ordering` is -1, 0 or 1, just what `qsort` wants. It can't panic, so
+    // nothing can unwind into `qsort`.
+    a.cmp(&b) as c_int
+}
+
+/// Sorts `s` in ascending order with the C library's `qsort`, comparing in
+/// Rust.
+pub fn sort_slice_with_qsort(s: &mut [i32]) {
+    // SAFETY: `s` is valid for reading and writing `s.len()` elements of
+    // `size_of::<i32>()` bytes each, and `compare_ints` expects exactly that
+    // element type.
+    unsafe {
+        libc::qsort(
+            s.as_mut_ptr().cast(),
+            s.len(),
+            mem::size_of::<i32>(),
+            Some(compare_ints),
+        )
+    }
+}
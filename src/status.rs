@@ -0,0 +1,70 @@
+//! Mapping a C `enum` return value to a Rust enum.
+//!
+//! A C enum is just an integer: nothing stops C from returning a value
+//! outside the declared variants, whether by a bug, a newer library version
+//! with a new variant, or a cast. A Rust enum holding anything but one of
+//! its variants is undefined behavior, so the raw declaration returns a
+//! plain `c_int`, and [`StatusCode::try_from`] checks it before it becomes a
+//! `StatusCode`.
+
+use core::ffi::c_int;
+use std::error::Error;
+use std::fmt;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        // Declared as returning `c_int` rather than `StatusCode` on purpose,
+        // see the module documentation.
+        pub fn classify(n: c_int) -> c_int;
+    }
+}
+
+/// The `StatusCode` enum from `c_src/status.c`.
+///
+/// `#[repr(C)]` gives it the same size as the C enum, and the explicit
+/// discriminants the same values.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusCode {
+    /// `STATUS_NEGATIVE`, for values below zero.
+    Negative = -1,
+    /// `STATUS_ZERO`.
+    Zero = 0,
+    /// `STATUS_POSITIVE`, for values above zero.
+    Positive = 1,
+}
+
+/// Error returned when C produces a value that isn't a [`StatusCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownStatus(pub c_int);
+
+impl fmt::Display for UnknownStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown status code {}", self.0)
+    }
+}
+
+impl Error for UnknownStatus {}
+
+impl TryFrom<c_int> for StatusCode {
+    type Error = UnknownStatus;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(StatusCode::Negative),
+            0 => Ok(StatusCode::Zero),
+            1 => Ok(StatusCode::Positive),
+            other => Err(UnknownStatus(other)),
+        }
+    }
+}
+
+/// Asks C whether `n` is negative, zero or positive.
+pub fn classify(n: i32) -> Result<StatusCode, UnknownStatus> {
+    // SAFETY: `classify` takes and returns plain integers.
+    let status = unsafe { raw::classify(n) };
+
+    StatusCode::try_from(status)
+}
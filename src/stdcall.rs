@@ -0,0 +1,57 @@
+//! Calling a C function that uses Windows' `__stdcall` calling convention.
+//!
+//! A calling convention says how arguments and the return value travel
+//! between caller and callee, and who cleans up the stack afterwards. C
+//! compilers default to `__cdecl`, which `extern "C"` matches, but most of
+//! the Win32 API is declared `WINAPI`, which is `__stdcall`: on 32-bit x86
+//! the callee pops its own arguments. Declaring such a function with the
+//! wrong ABI compiles and links fine, and then corrupts the stack on every
+//! call.
+//!
+//! `c_src/stdcall.c` defines `multiply_stdcall()` as `__stdcall` and is only
+//! built for Windows. On 32-bit x86 Windows it is declared here with
+//! `extern "stdcall"`. Only that target has a separate stdcall ABI, and
+//! Rust rejects or warns about `extern "stdcall"` everywhere else, including
+//! 64-bit Windows, where the C compiler ignores `__stdcall` too. There it is
+//! declared with `extern "system"`, which means "the ABI the system's API
+//! uses": stdcall on 32-bit x86 Windows and C everywhere else. Declaring
+//! any `WINAPI` function with `extern "system"` is the usual shortcut, and
+//! what `windows-sys` and the standard library do.
+//!
+//! On other platforms `multiply_stdcall` doesn't exist, so calling it there
+//! fails to compile rather than at runtime. Code calling it needs the same
+//! `#[cfg]`:
+//!
+//! ```
+//! #[cfg(target_os = "windows")]
+//! {
+//!     use rust_ffi_to_c::stdcall;
+//!
+//!     assert_eq!(stdcall::multiply_stdcall(6, 7), 42);
+//! }
+//! ```
+
+#[cfg(target_os = "windows")]
+mod raw {
+    use core::ffi::c_int;
+
+    #[cfg(target_arch = "x86")]
+    extern "stdcall" {
+        pub fn multiply_stdcall(a: c_int, b: c_int) -> c_int;
+    }
+
+    #[cfg(not(target_arch = "x86"))]
+    extern "system" {
+        pub fn multiply_stdcall(a: c_int, b: c_int) -> c_int;
+    }
+}
+
+/// Multiplies two integers in C through a `__stdcall` function, wrapping
+/// around on overflow.
+#[cfg(target_os = "windows")]
+pub fn multiply_stdcall(a: i32, b: i32) -> i32 {
+    // SAFETY: `multiply_stdcall` takes plain integers, and is declared with
+    // the calling convention it is defined with. `c_int` is `i32` on
+    // Windows.
+    unsafe { raw::multiply_stdcall(a, b) }
+}
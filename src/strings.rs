@@ -0,0 +1,205 @@
+//! Passing strings to C and reading strings owned by C.
+//!
+//! Rust strings are not NUL-terminated and may contain NUL bytes, so they
+//! can't be handed to C as-is. Going in, a `&str` is copied into a
+//! [`CString`](std::ffi::CString), which adds the terminator. Coming out, a
+//! `*const c_char` is wrapped in a [`CStr`], which finds the terminator and
+//! lets us view the bytes without copying them. [`crate::convert`] has
+//! helpers for both.
+//!
+//! Whoever allocated a string has to free it. [`greet`] gets back memory that
+//! C allocated with `malloc`, so it copies the contents into a Rust `String`
+//! and hands the pointer back to C to free. Freeing it from Rust (for example
+//! by turning it into a `CString` with `CString::from_raw`) would release it
+//! with the wrong allocator. [`version`] gets back a pointer to a string
+//! literal, which nobody must free. [`describe_result`] avoids the question
+//! by having C write into a buffer Rust allocated.
+//!
+//! # The dangling pointer trap
+//!
+//! This compiles, but passes C a pointer to freed memory:
+//!
+//! ```ignore
+//! let ptr = CString::new("Ferris").unwrap().as_ptr();
+//! // The temporary CString was dropped at the end of the line above.
+//! unsafe { raw::greet(ptr) }; // use after free
+//! ```
+//!
+//! `as_ptr()` borrows the `CString` without tying the pointer's lifetime to
+//! it, so nothing stops the `CString` from being dropped first. Always bind
+//! the `CString` to a variable that outlives the call, as [`greet`] does.
+//! (Recent compilers warn about this pattern with
+//! `dangling_pointers_from_temporaries`, but only in the obvious cases.)
+
+use std::error::Error;
+use std::ffi::{CStr, NulError};
+use std::fmt;
+use std::ptr;
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+
+use crate::convert;
+use crate::ptr_ext::PtrExt;
+
+mod raw {
+    use crate::arrays::c_size_t;
+    use core::ffi::{c_char, c_int};
+
+    extern "C" {
+        pub fn greet(name: *const c_char) -> *mut c_char;
+        pub fn greet_free(greeting: *mut c_char);
+        pub fn get_version() -> *const c_char;
+        pub fn describe_result(value: c_int, buf: *mut c_char, buflen: c_size_t) -> c_int;
+    }
+}
+
+/// Error returned by [`greet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GreetError {
+    /// The name contains a NUL byte at the given position.
+    InteriorNul(usize),
+    /// C returned NULL because it couldn't allocate the greeting.
+    NullReturned,
+}
+
+impl fmt::Display for GreetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GreetError::InteriorNul(pos) => write!(f, "name contains a NUL byte at {}", pos),
+            GreetError::NullReturned => write!(f, "C failed to allocate the greeting"),
+        }
+    }
+}
+
+impl Error for GreetError {}
+
+impl From<NulError> for GreetError {
+    fn from(err: NulError) -> Self {
+        GreetError::InteriorNul(err.nul_position())
+    }
+}
+
+/// Asks C to build a greeting for `name`.
+///
+/// Names containing a NUL byte are rejected: C would see the string end at
+/// the first NUL, so `"Fer\0ris"` would silently turn into a greeting for
+/// `"Fer"`.
+pub fn greet(name: &str) -> Result<String, GreetError> {
+    let name = convert::to_cstring(name)?;
+
+    // SAFETY: `name` is a valid NUL-terminated string that stays alive until
+    // the end of this function, and `greet` only reads from it.
+    let ptr = unsafe { raw::greet(name.as_ptr()) }
+        .owned_or(GreetError::NullReturned)?
+        .as_ptr();
+
+    // SAFETY: `ptr` points to the NUL-terminated string C just wrote. It is
+    // copied out before being freed below. `convert::from_c_owned` isn't
+    // used because C wants it back through `greet_free`, not `free`.
+    let greeting = unsafe { convert::from_c_borrowed(ptr) };
+
+    // SAFETY: `ptr` came from `greet` and hasn't been freed yet. Nothing
+    // refers to it after this.
+    unsafe { raw::greet_free(ptr) };
+
+    Ok(greeting)
+}
+
+/// Returns the version string of the C library.
+///
+/// `crate::version` has the same string, cached, with a way to check it.
+pub fn version() -> Result<&'static str, Utf8Error> {
+    // SAFETY: `get_version` takes no arguments and always returns a pointer
+    // to a NUL-terminated string literal.
+    let ptr = unsafe { raw::get_version() };
+
+    // SAFETY: the pointer is non-null and NUL-terminated, and it points to a
+    // C string literal, which lives for the whole program. That is what makes
+    // the `'static` lifetime sound. It also means we must never free it: the
+    // memory belongs to C, not to us.
+    let version = unsafe { CStr::from_ptr(ptr) };
+
+    version.to_str()
+}
+
+/// The most [`describe_result`] will allocate for a description. Far more
+/// than C ever needs, but it keeps a misbehaving C function from making Rust
+/// allocate without bound.
+const DESCRIPTION_CAP: usize = 4096;
+
+/// Error returned by [`describe_result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescribeError {
+    /// C reported an error, with the given return value.
+    Failed(i32),
+    /// C kept asking for more than [`describe_result`] allows.
+    TooLong(usize),
+    /// The description isn't valid UTF-8.
+    InvalidUtf8(FromUtf8Error),
+}
+
+impl fmt::Display for DescribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DescribeError::Failed(code) => write!(f, "describe_result failed with {}", code),
+            DescribeError::TooLong(len) => write!(f, "description of {} bytes is too long", len),
+            DescribeError::InvalidUtf8(err) => write!(f, "description is not UTF-8: {}", err),
+        }
+    }
+}
+
+impl Error for DescribeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DescribeError::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Asks C to describe `value`, such as `"42 is positive and even"`.
+///
+/// C writes into a buffer Rust provides and returns how long the whole
+/// description is, like `snprintf`. So this first asks with an empty buffer
+/// to learn the length, then allocates that much and asks again.
+///
+/// The length can change between the two calls if what C writes depends on
+/// state that changed in the meantime, so the second call can still come
+/// up short. Its return value says so, and the loop just tries again with
+/// the new length, up to a cap.
+pub fn describe_result(value: i32) -> Result<String, DescribeError> {
+    // SAFETY: a NULL buffer with a length of 0 only asks for the length; C
+    // writes nothing.
+    let mut needed = unsafe { raw::describe_result(value, ptr::null_mut(), 0) };
+
+    loop {
+        let len = usize::try_from(needed).map_err(|_| DescribeError::Failed(needed))?;
+
+        // Nothing to describe, so no need to call C again.
+        if len == 0 {
+            return Ok(String::new());
+        }
+
+        if len > DESCRIPTION_CAP {
+            return Err(DescribeError::TooLong(len));
+        }
+
+        // One more byte for the NUL terminator, which C always writes.
+        let mut buf = vec![0u8; len + 1];
+
+        // SAFETY: `buf` is valid for writing `buf.len()` bytes, and C writes
+        // no more than that.
+        needed = unsafe { raw::describe_result(value, buf.as_mut_ptr().cast(), buf.len()) };
+
+        if needed >= 0 && needed as usize <= len {
+            // Only the first `needed` bytes are the description; drop the
+            // terminator and anything after it.
+            buf.truncate(needed as usize);
+            return String::from_utf8(buf).map_err(DescribeError::InvalidUtf8);
+        }
+
+        // Either an error, handled at the top of the loop, or the
+        // description grew since the first call: try again with the new
+        // length.
+    }
+}
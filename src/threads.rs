@@ -0,0 +1,91 @@
+//! Calling C from several threads at once.
+//!
+//! Every safe wrapper in this crate is an ordinary Rust function, so the
+//! compiler lets any thread call it, any number of times at once. Whether
+//! that is actually fine depends entirely on the C code, which the compiler
+//! can't see into. It has to be checked by reading it.
+//!
+//! `multiply()` is fine. It works on its arguments, and the little state it
+//! does touch is made for sharing: the call counter and the verbose flag
+//! are `_Atomic int`s, the log callback sits behind a lock, and `printf` is
+//! thread-safe, even if lines from different threads may interleave.
+//! [`multiply_in_parallel`] spreads the calls over threads:
+//!
+//! ```
+//! use rust_ffi_to_c::{ffi, threads};
+//!
+//! ffi::set_verbose(false);
+//! let pairs: Vec<_> = (0..100).map(|i| (i, 2)).collect();
+//! let products = threads::multiply_in_parallel(&pairs, 4);
+//! assert_eq!(products, (0..100).map(|i| i * 2).collect::<Vec<_>>());
+//! ```
+//!
+//! `bump_global()` in `crate::global_state` is not. It updates a plain
+//! `static int`, and two threads doing that at once is a data race, which
+//! is undefined behavior. Its binding looks just as harmless as
+//! `multiply()`'s, so the safe wrapper there, `GlobalCounter`, is the only
+//! thing standing between it and a second thread: there is only ever one,
+//! and it is `Send` but not `Sync`, so it can move to another thread but
+//! can't be used from two at once.
+//!
+//! ```compile_fail
+//! use std::thread;
+//!
+//! use rust_ffi_to_c::global_state::GlobalCounter;
+//!
+//! let counter = GlobalCounter::take().unwrap();
+//! thread::scope(|s| {
+//!     for _ in 0..4 {
+//!         s.spawn(|| counter.bump()); // `Cell<()>` cannot be shared between threads
+//!     }
+//! });
+//! ```
+//!
+//! # `Send` and `Sync` on the wrappers
+//!
+//! A wrapper holding a pointer to C memory is neither `Send` nor `Sync` by
+//! default, since the compiler knows nothing about the memory. Each one
+//! gets back what its C side allows, with a `SAFETY` comment saying why:
+//!
+//! | Wrapper | `Send` | `Sync` | Why |
+//! |---|---|---|---|
+//! | `range::CIntBuffer`, `cstr_ownership::OwnedCStr`, `alloc_string::CAllocatedString` | yes | yes | Plain heap memory, only read until it is freed, and `free()` works from any thread |
+//! | `calculator::Calculator`, `counter::Counter`, `ring_buffer::RingBuffer` | yes | no | A heap object with no global state, which C changes without locking, so only one thread at a time |
+//! | `calculator::SharedCalculator` | yes | yes | A `Calculator` behind a `Mutex` |
+//! | `global_state::GlobalCounter` | yes | no | Stands for state the whole process shares |
+//! | `context::CContext` | no | no | Left at the default, see its docs |
+
+use std::thread;
+
+use crate::ffi;
+
+/// Multiplies every pair in C, split across `threads` threads, and returns
+/// the products in order.
+///
+/// # Panics
+///
+/// Panics if `threads` is 0.
+pub fn multiply_in_parallel(pairs: &[(i32, i32)], threads: usize) -> Vec<i32> {
+    assert!(
+        threads > 0,
+        "multiply_in_parallel needs at least one thread"
+    );
+    if pairs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut products = vec![0; pairs.len()];
+    let chunk_len = pairs.len().div_ceil(threads);
+
+    thread::scope(|s| {
+        for (pairs, products) in pairs.chunks(chunk_len).zip(products.chunks_mut(chunk_len)) {
+            s.spawn(move || {
+                for (&(a, b), product) in pairs.iter().zip(products) {
+                    *product = ffi::multiply(a, b);
+                }
+            });
+        }
+    });
+
+    products
+}
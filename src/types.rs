@@ -0,0 +1,94 @@
+//! Passing structs between Rust and C.
+
+use core::ffi::{c_double, c_int};
+use std::mem::{align_of, size_of};
+
+mod raw {
+    use super::{IntPoint, Point};
+    use core::ffi::{c_double, c_int};
+
+    extern "C" {
+        pub fn distance(a: Point, b: Point) -> c_double;
+        pub fn point_scale(p: IntPoint, factor: c_int) -> IntPoint;
+        pub fn point_scale_inplace(p: *mut IntPoint, factor: c_int);
+        pub fn make_point(x: c_int, y: c_int) -> IntPoint;
+    }
+}
+
+/// A point in 2D space, laid out exactly like `Point` in `c_src/geometry.c`.
+///
+/// **`#[repr(C)]` is not optional here.** Without it the Rust compiler is
+/// free to reorder the fields or add padding however it likes, and C would
+/// read `x` and `y` from the wrong offsets. Nothing would fail to compile:
+/// the program would just compute garbage, or worse. Rust warns about
+/// `extern` declarations that use a non-`repr(C)` struct
+/// (`improper_ctypes`), but that warning is easy to silence and ignore.
+///
+/// Deriving traits doesn't change the layout, so `Clone`, `Copy`, `Debug`
+/// and `PartialEq` work as usual.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f64,
+    /// The vertical coordinate.
+    pub y: f64,
+}
+
+/// A point with integer coordinates, laid out like `IntPoint` in
+/// `c_src/geometry.c`.
+///
+/// The same warning as for [`Point`] applies, and it goes beyond field
+/// order: how a struct is returned by value (in registers or through a
+/// hidden pointer) depends on its layout, so without `#[repr(C)]` Rust could
+/// look for the return value of [`make_point`] in the wrong place.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntPoint {
+    /// The horizontal coordinate.
+    pub x: c_int,
+    /// The vertical coordinate.
+    pub y: c_int,
+}
+
+// `#[repr(C)]` makes Rust lay the structs out like a C compiler would, but it
+// can't check that the C definitions say the same thing. These assertions
+// fail the build if the layouts this code relies on ever change, instead of
+// letting C read the wrong bytes at runtime.
+const _: () = assert!(size_of::<Point>() == 2 * size_of::<c_double>());
+const _: () = assert!(align_of::<Point>() == align_of::<c_double>());
+const _: () = assert!(size_of::<IntPoint>() == 2 * size_of::<c_int>());
+const _: () = assert!(align_of::<IntPoint>() == align_of::<c_int>());
+
+/// Returns the distance between two points, computed in C.
+pub fn distance(a: Point, b: Point) -> f64 {
+    // SAFETY: `Point` is `#[repr(C)]` with the same fields as the C struct,
+    // and both are passed by value, so there are no pointers involved.
+    unsafe { raw::distance(a, b) }
+}
+
+/// Returns `p` with both coordinates multiplied by `factor`, computed in C.
+/// The struct is passed to C and returned from it by value.
+///
+/// Coordinates that overflow wrap around.
+pub fn point_scale(p: IntPoint, factor: i32) -> IntPoint {
+    // SAFETY: `IntPoint` is `#[repr(C)]` and matches the C struct, and it is
+    // passed and returned by value.
+    unsafe { raw::point_scale(p, factor) }
+}
+
+/// Multiplies both coordinates of `p` by `factor` in place, in C. The struct
+/// is passed to C as a pointer.
+///
+/// Coordinates that overflow wrap around.
+pub fn point_scale_inplace(p: &mut IntPoint, factor: i32) {
+    // SAFETY: a `&mut IntPoint` is a valid, aligned, exclusive pointer to an
+    // initialized `IntPoint` for the duration of the call.
+    unsafe { raw::point_scale_inplace(p, factor) }
+}
+
+/// Builds an `IntPoint` in C and returns it by value.
+pub fn make_point(x: i32, y: i32) -> IntPoint {
+    // SAFETY: `make_point` takes and returns plain values.
+    unsafe { raw::make_point(x, y) }
+}
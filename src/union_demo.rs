@@ -0,0 +1,241 @@
+//! Passing a C `union` to and from Rust.
+//!
+//! A Rust `union` with `#[repr(C)]` has the same layout as the C one: every
+//! field starts at offset 0, and the union is as large as its largest field.
+//! Nothing records which field was written last, so reading a field is
+//! `unsafe` in general: the bytes might not be a valid value of that type.
+//!
+//! [`IntFloat`] is a lucky case. Every 4-byte pattern is a valid `c_int`, a
+//! valid `f32` and a valid `[u8; 4]`, so each read just reinterprets the same
+//! bytes, and the accessors can be safe. A union with a `bool`, an enum or a
+//! reference among its fields would need a tag to say which field is valid;
+//! see `std::mem::transmute` and `f32::to_bits` for the safe ways to do what
+//! this union does in pure Rust.
+//!
+//! # Tagged unions
+//!
+//! [`TaggedValue`] is that case: a [`Value`] union next to a `tag` saying
+//! which field C wrote. Reading a field of `Value` stays `unsafe`, because
+//! the compiler can't see the tag, and reading the `float` when C wrote the
+//! `int` gives a meaningless number. For fields like `bool` or references,
+//! a wrong read is undefined behavior.
+//!
+//! [`Number`] is the safe version: a Rust enum has its own tag, which the
+//! compiler checks on every `match`. [`Number::try_from`] reads the field
+//! the C tag names, and is the only place that has to trust it:
+//!
+//! ```
+//! use rust_ffi_to_c::union_demo::{self, Number};
+//!
+//! let value = union_demo::tagged_from_int(42);
+//! assert_eq!(Number::try_from(value), Ok(Number::Int(42)));
+//! ```
+
+use core::ffi::{c_double, c_float, c_int};
+use std::error::Error;
+use std::fmt;
+use std::mem::{self, MaybeUninit};
+
+use crate::arrays::c_size_t;
+
+mod raw {
+    use super::{IntFloat, TaggedValue};
+    use crate::arrays::c_size_t;
+    use core::ffi::{c_double, c_float, c_int};
+
+    extern "C" {
+        pub fn int_float_from_int(i: c_int) -> IntFloat;
+        pub fn int_float_from_float(f: f32) -> IntFloat;
+        pub fn int_float_size() -> c_size_t;
+        pub fn tagged_from_int(i: c_int) -> TaggedValue;
+        pub fn tagged_from_float(f: c_float) -> TaggedValue;
+        pub fn tagged_as_double(value: TaggedValue, out: *mut c_double) -> c_int;
+    }
+}
+
+/// The `IntFloat` union from `c_src/union_demo.c`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union IntFloat {
+    /// The value as an `int`.
+    pub i: c_int,
+    /// The value as a `float`.
+    pub f: f32,
+    /// The raw bytes of the value, in memory order.
+    pub bytes: [u8; 4],
+}
+
+const _: () = assert!(mem::size_of::<IntFloat>() == 4);
+const _: () = assert!(mem::align_of::<IntFloat>() == mem::align_of::<c_int>());
+
+impl IntFloat {
+    /// Reads the union as an integer.
+    pub fn as_int(&self) -> i32 {
+        // SAFETY: every bit pattern is a valid `c_int`.
+        unsafe { self.i }
+    }
+
+    /// Reads the union as a float.
+    pub fn as_float(&self) -> f32 {
+        // SAFETY: every bit pattern is a valid `f32`, if possibly a NaN.
+        unsafe { self.f }
+    }
+
+    /// Reads the union as its raw bytes, in native byte order.
+    pub fn as_bytes(&self) -> [u8; 4] {
+        // SAFETY: every bit pattern is a valid `[u8; 4]`.
+        unsafe { self.bytes }
+    }
+}
+
+impl std::fmt::Debug for IntFloat {
+    // There's no way to know which field is "the" value, so show them all.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntFloat")
+            .field("i", &self.as_int())
+            .field("f", &self.as_float())
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+/// Has C build an `IntFloat` by writing its `int` field.
+pub fn from_int(i: i32) -> IntFloat {
+    // SAFETY: `int_float_from_int` takes and returns plain values.
+    unsafe { raw::int_float_from_int(i) }
+}
+
+/// Has C build an `IntFloat` by writing its `float` field.
+pub fn from_float(f: f32) -> IntFloat {
+    // SAFETY: `int_float_from_float` takes and returns plain values.
+    unsafe { raw::int_float_from_float(f) }
+}
+
+/// `sizeof(IntFloat)` as C sees it.
+pub fn c_size() -> c_size_t {
+    // SAFETY: `int_float_size` has no preconditions.
+    unsafe { raw::int_float_size() }
+}
+
+/// `VALUE_INT` from `c_src/multiply.h`: [`Value::i`] was written.
+pub const VALUE_INT: c_int = 0;
+/// `VALUE_FLOAT` from `c_src/multiply.h`: [`Value::f`] was written.
+pub const VALUE_FLOAT: c_int = 1;
+
+/// The `Value` union from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Value {
+    /// The value, if the tag is [`VALUE_INT`].
+    pub i: c_int,
+    /// The value, if the tag is [`VALUE_FLOAT`].
+    pub f: c_float,
+}
+
+/// The `TaggedValue` struct from `c_src/multiply.h`.
+///
+/// Both fields are public, so it can hold any tag, including ones C
+/// doesn't know.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TaggedValue {
+    /// [`VALUE_INT`] or [`VALUE_FLOAT`].
+    pub tag: c_int,
+    /// The field named by `tag`.
+    pub value: Value,
+}
+
+const _: () = assert!(mem::size_of::<Value>() == mem::size_of::<c_int>());
+const _: () = assert!(mem::size_of::<TaggedValue>() == 2 * mem::size_of::<c_int>());
+const _: () = assert!(mem::offset_of!(TaggedValue, value) == mem::size_of::<c_int>());
+
+impl fmt::Debug for TaggedValue {
+    // Only the field the tag names is meaningful to show.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Number::try_from(*self) {
+            Ok(number) => f.debug_tuple("TaggedValue").field(&number).finish(),
+            Err(err) => f.debug_tuple("TaggedValue").field(&err).finish(),
+        }
+    }
+}
+
+/// A [`TaggedValue`] as a Rust enum, which can't be read as the wrong
+/// variant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    /// Tagged [`VALUE_INT`].
+    Int(i32),
+    /// Tagged [`VALUE_FLOAT`].
+    Float(f32),
+}
+
+/// Error returned when a [`TaggedValue`] has a tag that isn't
+/// [`VALUE_INT`] or [`VALUE_FLOAT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownTag(pub c_int);
+
+impl fmt::Display for UnknownTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown union tag {}", self.0)
+    }
+}
+
+impl Error for UnknownTag {}
+
+impl TryFrom<TaggedValue> for Number {
+    type Error = UnknownTag;
+
+    fn try_from(value: TaggedValue) -> Result<Self, Self::Error> {
+        // SAFETY: each field is only read when the tag says it was the one
+        // written. Both are plain numbers, valid for any bits, so a wrong
+        // tag from C would give a wrong number but not undefined behavior.
+        match value.tag {
+            VALUE_INT => Ok(Number::Int(unsafe { value.value.i })),
+            VALUE_FLOAT => Ok(Number::Float(unsafe { value.value.f })),
+            other => Err(UnknownTag(other)),
+        }
+    }
+}
+
+impl From<Number> for TaggedValue {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Int(i) => TaggedValue {
+                tag: VALUE_INT,
+                value: Value { i },
+            },
+            Number::Float(f) => TaggedValue {
+                tag: VALUE_FLOAT,
+                value: Value { f },
+            },
+        }
+    }
+}
+
+/// Has C build a [`TaggedValue`] holding an `int`.
+pub fn tagged_from_int(i: i32) -> TaggedValue {
+    // SAFETY: `tagged_from_int` takes and returns plain values.
+    unsafe { raw::tagged_from_int(i) }
+}
+
+/// Has C build a [`TaggedValue`] holding a `float`.
+pub fn tagged_from_float(f: f32) -> TaggedValue {
+    // SAFETY: `tagged_from_float` takes and returns plain values.
+    unsafe { raw::tagged_from_float(f) }
+}
+
+/// Has C read `value` according to its tag, as a `double`.
+pub fn tagged_as_f64(value: TaggedValue) -> Result<f64, UnknownTag> {
+    let mut out = MaybeUninit::<c_double>::uninit();
+
+    // SAFETY: `out` is valid for writing one `double`. C only reads the
+    // field the tag names, and rejects unknown tags.
+    let status = unsafe { raw::tagged_as_double(value, out.as_mut_ptr()) };
+
+    if status != 0 {
+        return Err(UnknownTag(value.tag));
+    }
+
+    // SAFETY: C returned 0, so it has written `out`.
+    Ok(unsafe { out.assume_init() })
+}
@@ -0,0 +1,115 @@
+//! Calling a variadic C function.
+//!
+//! Declaring and calling a C function that takes `...` works on stable Rust:
+//! the declaration ends in `...` like the C prototype does. The compiler
+//! can't check the variadic arguments against anything, so they have to be
+//! exactly the types C reads with `va_arg`. C's default argument promotions
+//! apply too: Rust refuses to pass an `f32`, `i8` or `i16` through `...`,
+//! since C would read a `double` or an `int` there instead.
+//!
+//! The number of arguments in a call is fixed when the code is compiled, so
+//! there is no way to spread a slice over `...`. [`sum_ints`] gets around
+//! that with a `match` on the length, and gives up past four values. When
+//! the C API can be changed, passing a pointer and a length is simpler and
+//! works for any size; see [`crate::arrays::sum`].
+//!
+//! # Calling it directly
+//!
+//! The declaration and a call look like this. Each variadic argument is
+//! spelled out as a `c_int`, because a bare integer literal would be
+//! inferred as `i32`, which only happens to be the same type:
+//!
+//! ```
+//! # extern crate rust_ffi_to_c;
+//! use core::ffi::c_int;
+//!
+//! extern "C" {
+//!     fn sum_ints(count: c_int, ...) -> c_int;
+//! }
+//!
+//! // SAFETY: exactly `count` `c_int`s follow the count.
+//! let sum = unsafe { sum_ints(3, 1 as c_int, 2 as c_int, 3 as c_int) };
+//! assert_eq!(sum, 6);
+//! ```
+//!
+//! Nothing stops a call from passing the wrong number of arguments, or a
+//! `c_long` or a `f64` where C reads an `int`. Either is undefined behavior:
+//! `va_arg` reads whatever happens to be in the register or stack slot. The
+//! one mistake the compiler does catch is a type that C would have
+//! promoted:
+//!
+//! ```compile_fail,E0617
+//! # extern crate rust_ffi_to_c;
+//! use core::ffi::c_int;
+//!
+//! extern "C" {
+//!     fn sum_ints(count: c_int, ...) -> c_int;
+//! }
+//!
+//! // error: can't pass `f32` to variadic function, cast to `c_double`
+//! unsafe { sum_ints(1, 1.0f32) };
+//! ```
+//!
+//! # Why it can't be generic
+//!
+//! A wrapper like `fn sum<T>(values: &[T])` would need to know, for every
+//! `T`, what C reads in its place. The C side fixes that with `va_arg(args,
+//! int)` and nothing in the signature says so. A generic wrapper can't see
+//! that line, so it can't check `T` against it. A trait that says "this type
+//! goes through `...` unchanged" only moves the question: the wrapper would
+//! still have to match what the C function reads. [`sum_ints`] has the only
+//! honest signature, taking exactly the type C reads.
+//!
+//! *Defining* a variadic function in Rust needs the unstable `c_variadic`
+//! feature. With the `nightly` feature and a nightly compiler, this module
+//! also has [`sum_ints_rust`], a Rust version of `sum_ints`.
+
+#[cfg(feature = "nightly")]
+use core::ffi::c_int;
+
+mod raw {
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn sum_ints(count: c_int, ...) -> c_int;
+    }
+}
+
+/// Has C sum up to four integers, passing them as separate variadic
+/// arguments.
+///
+/// The sum wraps around on overflow, like [`i32::wrapping_add`]. Returns
+/// `None` for more than four values.
+pub fn sum_ints(values: &[i32]) -> Option<i32> {
+    // SAFETY: each call passes exactly `count` `c_int`s after the count,
+    // which is what `sum_ints` reads.
+    let sum = unsafe {
+        match *values {
+            [] => raw::sum_ints(0),
+            [a] => raw::sum_ints(1, a),
+            [a, b] => raw::sum_ints(2, a, b),
+            [a, b, c] => raw::sum_ints(3, a, b, c),
+            [a, b, c, d] => raw::sum_ints(4, a, b, c, d),
+            _ => return None,
+        }
+    };
+
+    Some(sum)
+}
+
+/// Sums `count` `int`s passed after it, like `sum_ints` in C.
+///
+/// # Safety
+///
+/// Exactly `count` `c_int`s must follow `count`.
+#[cfg(feature = "nightly")]
+pub unsafe extern "C" fn sum_ints_rust(count: c_int, mut args: ...) -> c_int {
+    let mut total: c_int = 0;
+
+    for _ in 0..count {
+        // SAFETY: the caller passed at least `count` `c_int`s.
+        total = total.wrapping_add(unsafe { args.next_arg::<c_int>() });
+    }
+
+    total
+}
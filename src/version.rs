@@ -0,0 +1,136 @@
+//! Checking the version of the C library at runtime.
+//!
+//! `lib_version()` in `c_src/multiply.c` returns a string like `"1.2.0"`
+//! with static storage duration, so [`version`] can hand it out as a
+//! `&'static str`. It is converted once and cached in a `OnceLock`.
+//!
+//! The version matters most when the library isn't compiled along with the
+//! crate: a shared library on disk, or one installed on the system, can be
+//! older than the code calling it. [`require_version`] fails instead of
+//! letting the program find out through a missing symbol or changed
+//! behavior. With the `dynamic` feature, `DynamicMultiply::require_version`
+//! does the same for a library loaded at runtime.
+//!
+//! ```
+//! use rust_ffi_to_c::version;
+//!
+//! version::require_version((1, 0, 0)).unwrap();
+//! assert!(version::require_version((99, 0, 0)).is_err());
+//! ```
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::sync::OnceLock;
+
+mod raw {
+    use core::ffi::c_char;
+
+    extern "C" {
+        pub fn lib_version() -> *const c_char;
+    }
+}
+
+/// A `(major, minor, patch)` version.
+pub type Version = (u32, u32, u32);
+
+/// Error returned by [`parse_version`] and [`require_version`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionError {
+    /// The version string isn't `MAJOR.MINOR.PATCH`.
+    Malformed(String),
+    /// The library is older than required.
+    TooOld {
+        /// The library's version.
+        found: Version,
+        /// The oldest version that would do.
+        required: Version,
+    },
+    /// The library doesn't report a version at all, which means it predates
+    /// `lib_version()`.
+    Missing,
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionError::Malformed(version) => {
+                write!(f, "`{}` is not a MAJOR.MINOR.PATCH version", version)
+            }
+            VersionError::TooOld { found, required } => write!(
+                f,
+                "libmultiply {}.{}.{} is too old, {}.{}.{} or newer is required",
+                found.0, found.1, found.2, required.0, required.1, required.2
+            ),
+            VersionError::Missing => write!(f, "libmultiply doesn't report its version"),
+        }
+    }
+}
+
+impl Error for VersionError {}
+
+/// The version of the linked C library, as reported by `lib_version()`.
+///
+/// A string that isn't valid UTF-8 has the invalid bytes replaced with
+/// U+FFFD, and then fails to parse in [`require_version`].
+pub fn version() -> &'static str {
+    static VERSION: OnceLock<Cow<'static, str>> = OnceLock::new();
+
+    VERSION.get_or_init(|| {
+        // SAFETY: `lib_version` returns a pointer to a string literal, which
+        // is never NULL, is NUL-terminated and lives for the whole program.
+        let version: &'static CStr = unsafe { CStr::from_ptr(raw::lib_version()) };
+
+        // Borrows the C string when it is valid UTF-8, which it always is in
+        // practice, so nothing is copied.
+        version.to_string_lossy()
+    })
+}
+
+/// Parses a `MAJOR.MINOR.PATCH` version string.
+///
+/// Each part must be a decimal number. Nothing may come before or after
+/// them, not even whitespace or a pre-release suffix like `-rc1`.
+pub fn parse_version(version: &str) -> Result<Version, VersionError> {
+    let malformed = || VersionError::Malformed(version.to_owned());
+    let part = |part: Option<&str>| -> Result<u32, VersionError> {
+        let part = part.ok_or_else(malformed)?;
+        if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(malformed());
+        }
+        part.parse().map_err(|_| malformed())
+    };
+
+    let mut parts = version.split('.');
+    let parsed = (
+        part(parts.next())?,
+        part(parts.next())?,
+        part(parts.next())?,
+    );
+
+    if parts.next().is_some() {
+        return Err(malformed());
+    }
+
+    Ok(parsed)
+}
+
+/// Checks that the `version` string is `min` or newer.
+pub fn check_version(version: &str, min: Version) -> Result<(), VersionError> {
+    let found = parse_version(version)?;
+
+    if found < min {
+        return Err(VersionError::TooOld {
+            found,
+            required: min,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the linked C library is `min` or newer.
+pub fn require_version(min: Version) -> Result<(), VersionError> {
+    check_version(version(), min)
+}
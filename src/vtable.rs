@@ -0,0 +1,99 @@
+//! A C struct of function pointers used as a vtable.
+//!
+//! SQLite's `sqlite3_io_methods`, GTK's class structs and most plugin APIs
+//! share one pattern: a struct whose fields are function pointers, which C
+//! calls through without knowing who filled them in. Both directions work:
+//!
+//! - [`c_calculator`] returns a vtable filled in by C. Rust calls through it
+//!   with the [`Calc`] trait, like any other Rust type.
+//! - [`make_rust_calculator`] fills one in with `extern "C"` Rust functions,
+//!   and [`apply_add`]/[`apply_sub`] hand it to C, which calls back into
+//!   Rust.
+//!
+//! The C type is called `CalculatorVTable` rather than `Calculator`, which
+//! already names the opaque object in `crate::calculator`.
+//!
+//! The fields are plain `extern "C" fn` pointers, so a vtable must never
+//! hold a NULL one, which C code can easily produce. For a C API where
+//! some entries are optional, declare those fields as
+//! `Option<extern "C" fn(..)>` instead, as `crate::callbacks` explains.
+
+use core::ffi::c_int;
+
+mod raw {
+    use super::CalculatorVTable;
+    use core::ffi::c_int;
+
+    extern "C" {
+        pub fn c_calculator() -> *const CalculatorVTable;
+        pub fn apply_add(calc: *const CalculatorVTable, a: c_int, b: c_int) -> c_int;
+        pub fn apply_sub(calc: *const CalculatorVTable, a: c_int, b: c_int) -> c_int;
+    }
+}
+
+/// The `CalculatorVTable` struct from `c_src/multiply.h`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CalculatorVTable {
+    /// Returns `a + b`.
+    pub add: extern "C" fn(c_int, c_int) -> c_int,
+    /// Returns `a - b`.
+    pub sub: extern "C" fn(c_int, c_int) -> c_int,
+}
+
+/// A calculator, whichever language implements it.
+pub trait Calc {
+    /// Returns `a + b`.
+    fn add(&self, a: i32, b: i32) -> i32;
+    /// Returns `a - b`.
+    fn sub(&self, a: i32, b: i32) -> i32;
+}
+
+impl Calc for CalculatorVTable {
+    fn add(&self, a: i32, b: i32) -> i32 {
+        (self.add)(a, b)
+    }
+
+    fn sub(&self, a: i32, b: i32) -> i32 {
+        (self.sub)(a, b)
+    }
+}
+
+// Wrapping, so they can't panic, which would abort the process when C
+// calls them.
+extern "C" fn rust_add(a: c_int, b: c_int) -> c_int {
+    a.wrapping_add(b)
+}
+
+extern "C" fn rust_sub(a: c_int, b: c_int) -> c_int {
+    a.wrapping_sub(b)
+}
+
+/// Returns a vtable filled in with Rust functions, for C to call.
+pub fn make_rust_calculator() -> CalculatorVTable {
+    CalculatorVTable {
+        add: rust_add,
+        sub: rust_sub,
+    }
+}
+
+/// Returns the vtable C fills in with its own functions.
+pub fn c_calculator() -> &'static CalculatorVTable {
+    // SAFETY: C returns a pointer to a `static const` struct, which is never
+    // NULL, lives for the whole program and is never written, and whose
+    // fields are all non-NULL.
+    unsafe { &*raw::c_calculator() }
+}
+
+/// Has C call `calc.add`.
+pub fn apply_add(calc: &CalculatorVTable, a: i32, b: i32) -> i32 {
+    // SAFETY: `calc` is a valid vtable for the duration of the call, and its
+    // function pointers are valid by construction.
+    unsafe { raw::apply_add(calc, a, b) }
+}
+
+/// Has C call `calc.sub`.
+pub fn apply_sub(calc: &CalculatorVTable, a: i32, b: i32) -> i32 {
+    // SAFETY: as for `apply_add`.
+    unsafe { raw::apply_sub(calc, a, b) }
+}
@@ -0,0 +1,80 @@
+//! C allocating through Rust's global allocator.
+//!
+//! Installing the hooks can't be undone, and the first half of the test
+//! needs them not installed yet, so it runs as one test in a binary of its
+//! own.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rust_ffi_to_c::allocator::{self, ALIGN};
+use rust_ffi_to_c::counter::Counter;
+use rust_ffi_to_c::range;
+
+// A length no other allocation in this binary is likely to share, so the
+// allocator below can pick out the ones C makes for it.
+const LEN: usize = 12345;
+
+fn is_range(layout: Layout) -> bool {
+    let size = LEN * 4;
+    layout.align() == ALIGN && layout.size() > size && layout.size() <= size + 64
+}
+
+struct CountingAllocator;
+
+static RANGE_ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static RANGE_DEALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if is_range(layout) {
+            RANGE_ALLOCS.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if is_range(layout) {
+            RANGE_DEALLOCS.fetch_add(1, Ordering::SeqCst);
+        }
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn counts() -> (usize, usize) {
+    (
+        RANGE_ALLOCS.load(Ordering::SeqCst),
+        RANGE_DEALLOCS.load(Ordering::SeqCst),
+    )
+}
+
+#[test]
+fn falls_back_to_malloc_until_installed() {
+    // Before installation, C uses malloc() and free().
+    let before = range::make_range(0, LEN).unwrap();
+    assert_eq!(before[LEN - 1], LEN as i32 - 1);
+    drop(range::make_range(0, LEN).unwrap());
+    assert_eq!(counts(), (0, 0));
+
+    allocator::install_rust_allocator();
+    allocator::install_rust_allocator();
+
+    let after = range::make_range(0, LEN).unwrap();
+    assert_eq!(after[LEN - 1], LEN as i32 - 1);
+    assert_eq!(counts(), (1, 0));
+    drop(after);
+    assert_eq!(counts(), (1, 1));
+
+    // Allocated with malloc(), so freed with free() rather than handed to
+    // Rust's allocator.
+    drop(before);
+    assert_eq!(counts(), (1, 1));
+
+    // Other C objects go through the hooks too.
+    let mut counter = Counter::new().unwrap();
+    counter.increment();
+    assert_eq!(counter.value(), 1);
+}
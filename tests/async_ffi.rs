@@ -0,0 +1,65 @@
+//! Tests for the async wrappers, only built with the `tokio` feature.
+
+#![cfg(feature = "tokio")]
+
+use std::time::Duration;
+
+use rust_ffi_to_c::async_ffi;
+use rust_ffi_to_c::ffi;
+use rust_ffi_to_c::progress::{CancellableTask, TaskError};
+use rust_ffi_to_c::safe::OverflowError;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_multiplies() {
+    ffi::set_verbose(false);
+
+    let tasks: Vec<_> = (0..100)
+        .map(|i| tokio::spawn(async_ffi::multiply_async(i, i + 1)))
+        .collect();
+
+    for (i, task) in (0..100).zip(tasks) {
+        assert_eq!(task.await.unwrap(), Ok(i * (i + 1)));
+    }
+}
+
+#[tokio::test]
+async fn multiply_async_reports_overflow() {
+    ffi::set_verbose(false);
+
+    assert_eq!(
+        async_ffi::multiply_async(i32::MAX, 2).await,
+        Err(OverflowError::Overflow)
+    );
+}
+
+#[tokio::test]
+async fn run_cancellable_finishes() {
+    let task = CancellableTask::new(1000);
+    assert_eq!(async_ffi::run_cancellable(task).await, Ok(999 * 1000 / 2));
+}
+
+#[tokio::test]
+async fn dropping_the_future_cancels_c() {
+    // Would take many minutes to finish.
+    let task = CancellableTask::new(1 << 40);
+    let token = task.cancel_token();
+
+    let timed_out =
+        tokio::time::timeout(Duration::from_millis(10), async_ffi::run_cancellable(task)).await;
+
+    assert!(timed_out.is_err());
+    assert!(token.is_cancelled());
+    // If C ignored the flag, the test would hang here, where the runtime
+    // waits for its blocking threads to finish.
+}
+
+#[tokio::test]
+async fn cancelled_before_starting() {
+    let task = CancellableTask::new(1 << 40);
+    task.cancel_token().cancel();
+
+    assert_eq!(
+        async_ffi::run_cancellable(task).await,
+        Err(TaskError::Cancelled)
+    );
+}
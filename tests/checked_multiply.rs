@@ -0,0 +1,20 @@
+//! Checks that `checked_multiply` never calls C with inputs that overflow.
+//!
+//! It counts calls to C's `multiply()`, so it is the only test in this file:
+//! tests in the same file run in parallel, and any of them calling
+//! `multiply()` would throw the count off.
+
+use rust_ffi_to_c::{ffi, safe};
+
+#[test]
+fn overflow_never_reaches_c() {
+    ffi::set_verbose(false);
+
+    let before = ffi::call_count();
+    assert_eq!(safe::checked_multiply(i32::MAX, 2), None);
+    assert_eq!(safe::checked_multiply(i32::MIN, -1), None);
+    assert_eq!(ffi::call_count(), before);
+
+    assert_eq!(safe::checked_multiply(6, 7), Some(42));
+    assert_eq!(ffi::call_count(), before + 1);
+}
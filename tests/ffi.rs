@@ -0,0 +1,762 @@
+//! Tests for the safe wrappers around the C functions in `c_src/`.
+//!
+//! Like `tests/ffi_integration.rs`, this links the library the binary uses,
+//! through the link lines `build.rs` prints for it.
+
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::thread;
+
+use proptest::prelude::*;
+
+use rust_ffi_to_c::alloc_string::CAllocatedString;
+use rust_ffi_to_c::arithmetic::{self, DivideError};
+use rust_ffi_to_c::calculator::{Calculator, SharedCalculator};
+use rust_ffi_to_c::context::CContext;
+use rust_ffi_to_c::counter::Counter;
+use rust_ffi_to_c::cstr_ownership::OwnedCStr;
+use rust_ffi_to_c::errors::{self, DivError};
+use rust_ffi_to_c::ffi::{self, FfiConversionError};
+use rust_ffi_to_c::outparam::{self, NegativeInputError};
+use rust_ffi_to_c::range::CIntBuffer;
+use rust_ffi_to_c::ring_buffer::RingBuffer;
+use rust_ffi_to_c::safe::{self, OverflowError};
+use rust_ffi_to_c::status::{self, StatusCode, UnknownStatus};
+use rust_ffi_to_c::strings::{self, GreetError};
+use rust_ffi_to_c::types::{self, IntPoint, Point};
+use rust_ffi_to_c::{
+    alloc_string, arrays, callbacks, convert, exported, range, roundtrip, threads,
+};
+
+// A few values on either side of zero and at the edges of `i32`.
+const SAMPLES: [i32; 9] = [i32::MIN, -46341, -7, -1, 0, 1, 7, 46341, i32::MAX];
+
+#[test]
+fn safe_multiply_identities() {
+    ffi::set_verbose(false);
+
+    for n in [i32::MIN, -7, 0, 7, i32::MAX] {
+        assert_eq!(safe::safe_multiply(n, 1), Ok(n));
+        assert_eq!(safe::safe_multiply(1, n), Ok(n));
+        assert_eq!(safe::safe_multiply(n, 0), Ok(0));
+        assert_eq!(safe::safe_multiply(0, n), Ok(0));
+    }
+
+    assert_eq!(safe::safe_multiply(0, 7), Ok(0));
+    assert_eq!(safe::safe_multiply(1, -7), Ok(-7));
+    assert_eq!(safe::safe_multiply(-1, -7), Ok(7));
+    assert_eq!(
+        safe::safe_multiply(i32::MAX, 2),
+        Err(OverflowError::Overflow)
+    );
+    assert_eq!(
+        safe::safe_multiply(i32::MIN, -1),
+        Err(OverflowError::Overflow)
+    );
+}
+
+#[test]
+fn safe_multiply_agrees_with_checked_mul() {
+    ffi::set_verbose(false);
+
+    for a in SAMPLES {
+        for b in SAMPLES {
+            assert_eq!(safe::safe_multiply(a, b).ok(), a.checked_mul(b));
+            assert_eq!(safe::multiply_checked(a, b).ok(), a.checked_mul(b));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn safe_multiply_agrees_with_checked_mul_on_any_pair(a: i32, b: i32) {
+        ffi::set_verbose(false);
+
+        prop_assert_eq!(safe::safe_multiply(a, b).ok(), a.checked_mul(b));
+    }
+
+    // Products that fit, which random pairs of `i32`s rarely are.
+    #[test]
+    fn safe_multiply_agrees_with_checked_mul_in_range(
+        a in -46340..=46340,
+        b in -46340..=46340,
+    ) {
+        ffi::set_verbose(false);
+
+        prop_assert_eq!(safe::safe_multiply(a, b), Ok(a * b));
+    }
+}
+
+#[test]
+fn multiply_at_the_edges_of_i32() {
+    ffi::set_verbose(false);
+
+    assert_eq!(ffi::multiply(i32::MAX, 1), i32::MAX);
+    assert_eq!(ffi::multiply(i32::MIN, 1), i32::MIN);
+    assert_eq!(ffi::multiply(i32::MAX, -1), -i32::MAX);
+    assert_eq!(ffi::multiply(46341, 46340), 46341 * 46340);
+}
+
+#[test]
+fn try_multiply_accepts_i32_range() {
+    ffi::set_verbose(false);
+
+    // `c_int` is `i32` here, so every `i32` converts.
+    assert_eq!(ffi::try_multiply(i32::MAX, 1), Ok(i32::MAX));
+    assert_eq!(ffi::try_multiply(i32::MIN, 1), Ok(i32::MIN));
+    assert_eq!(ffi::try_multiply(1, i32::MAX), Ok(i32::MAX));
+    assert_eq!(
+        ffi::try_multiply(i32::MAX, -1),
+        Ok(ffi::multiply(i32::MAX, -1))
+    );
+}
+
+#[test]
+fn conversion_error_message() {
+    assert_eq!(
+        FfiConversionError.to_string(),
+        "value does not fit in a C int on this platform"
+    );
+}
+
+#[test]
+fn divide() {
+    assert_eq!(arithmetic::divide(10, 2), Ok(5));
+    assert_eq!(arithmetic::divide(7, 7), Ok(1));
+    assert_eq!(arithmetic::divide(0, 3), Ok(0));
+    assert_eq!(arithmetic::divide(-7, 2), Ok(-3));
+    assert_eq!(arithmetic::divide(10, 0), Err(DivideError::DivisionByZero));
+    assert_eq!(arithmetic::divide(i32::MIN, -1), Err(DivideError::Overflow));
+}
+
+#[test]
+fn divide_error_is_a_std_error() {
+    let err: Box<dyn std::error::Error> = Box::new(DivideError::DivisionByZero);
+    assert_eq!(err.to_string(), "division by zero");
+    assert_eq!(DivideError::Unknown(9).to_string(), "unknown status code 9");
+}
+
+#[test]
+fn divide_errno() {
+    assert_eq!(arithmetic::divide_errno(10, 2).unwrap(), 5);
+    assert_eq!(arithmetic::divide_errno(10, -2).unwrap(), -5);
+    // C rounds towards zero, like Rust.
+    assert_eq!(arithmetic::divide_errno(-7, 2).unwrap(), -3);
+    assert_eq!(arithmetic::divide_errno(-7, -2).unwrap(), 3);
+    // A legitimate `i32::MIN` result isn't mistaken for an error.
+    assert_eq!(arithmetic::divide_errno(i32::MIN, 1).unwrap(), i32::MIN);
+
+    let err = arithmetic::divide_errno(7, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let os_error = err.get_ref().unwrap().downcast_ref::<io::Error>();
+    assert_eq!(os_error.unwrap().raw_os_error(), Some(libc::EDOM));
+}
+
+#[cfg(unix)]
+#[test]
+fn divide_errno_ignores_an_earlier_errno() {
+    // Closing an invalid descriptor fails and leaves EBADF in errno.
+    // SAFETY: -1 is never an open descriptor, so nothing gets closed.
+    assert_eq!(unsafe { libc::close(-1) }, -1);
+
+    // INT_MIN is a real quotient here, and the stale EBADF must not turn it
+    // into an error.
+    assert_eq!(arithmetic::divide_errno(i32::MIN, 1).unwrap(), i32::MIN);
+}
+
+#[test]
+fn safe_divide() {
+    assert_eq!(errors::safe_divide(9, 3).unwrap(), 3);
+    assert_eq!(errors::safe_divide(-9, 2).unwrap(), -4);
+    assert_eq!(errors::safe_divide(0, 5).unwrap(), 0);
+    assert!(matches!(
+        errors::safe_divide(9, 0),
+        Err(DivError::DivideByZero)
+    ));
+    assert!(matches!(
+        errors::safe_divide(i32::MIN, -1),
+        Err(DivError::Overflow)
+    ));
+}
+
+#[test]
+fn safe_divide_error_message() {
+    let err = errors::safe_divide(9, 0).unwrap_err();
+    assert_eq!(err.to_string(), "division by zero");
+    assert!(std::error::Error::source(&err).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn safe_divide_clears_an_earlier_errno() {
+    // Closing an invalid descriptor fails and leaves EBADF in errno.
+    // SAFETY: -1 is never an open descriptor, so nothing gets closed.
+    assert_eq!(unsafe { libc::close(-1) }, -1);
+
+    assert_eq!(errors::safe_divide(8, 2).unwrap(), 4);
+    assert!(matches!(
+        errors::safe_divide(8, 0),
+        Err(DivError::DivideByZero)
+    ));
+}
+
+#[test]
+fn greet() {
+    assert_eq!(strings::greet("Ferris").unwrap(), "Hello, Ferris!");
+    assert_eq!(strings::greet("Grüße").unwrap(), "Hello, Grüße!");
+    assert_eq!(strings::greet("").unwrap(), "Hello, !");
+    assert_eq!(
+        strings::greet("Fer\0ris").unwrap_err(),
+        GreetError::InteriorNul(3)
+    );
+    assert_eq!(
+        strings::greet("\0").unwrap_err(),
+        GreetError::InteriorNul(0)
+    );
+    assert_eq!(
+        GreetError::InteriorNul(3).to_string(),
+        "name contains a NUL byte at 3"
+    );
+}
+
+#[test]
+fn to_cstring_rejects_interior_nul() {
+    assert_eq!(convert::to_cstring("Ferris").unwrap().as_bytes(), b"Ferris");
+    assert_eq!(
+        convert::to_cstring("Fer\0ris").unwrap_err().nul_position(),
+        3
+    );
+}
+
+#[test]
+fn from_c_handles_null() {
+    // SAFETY: NULL is allowed.
+    unsafe {
+        assert_eq!(convert::from_c_borrowed(ptr::null()), "");
+        assert_eq!(convert::from_c_owned(ptr::null_mut()), "");
+    }
+}
+
+#[test]
+fn from_c_owned_copies_and_frees() {
+    // SAFETY: `strdup` returns a `malloc`ed copy, which is owned here and
+    // handed to `from_c_owned`. It isn't NULL, since the copy is tiny.
+    let s = unsafe { convert::from_c_owned(libc::strdup(c"Ferris".as_ptr())) };
+    assert_eq!(s, "Ferris");
+}
+
+#[test]
+fn version() {
+    assert_eq!(strings::version(), Ok("1.2.0"));
+}
+
+#[test]
+fn struct_fields_round_trip() {
+    let a = Point { x: 1.0, y: 2.0 };
+    let b = Point { x: 4.0, y: 6.0 };
+    assert_eq!(types::distance(a, b), 5.0);
+
+    assert_eq!(types::make_point(2, -3), IntPoint { x: 2, y: -3 });
+}
+
+#[test]
+fn point_scale_variants_agree() {
+    let points = [
+        IntPoint { x: 3, y: -4 },
+        IntPoint { x: 0, y: 0 },
+        IntPoint {
+            x: i32::MAX,
+            y: i32::MIN,
+        },
+    ];
+
+    for p in points {
+        for factor in [-2, -1, 0, 1, 10, i32::MAX] {
+            let mut q = p;
+            types::point_scale_inplace(&mut q, factor);
+
+            assert_eq!(types::point_scale(p, factor), q);
+            assert_eq!(
+                q,
+                IntPoint {
+                    x: p.x.wrapping_mul(factor),
+                    y: p.y.wrapping_mul(factor),
+                }
+            );
+        }
+    }
+}
+
+#[test]
+fn point_scale_leaves_the_original_alone() {
+    let p = IntPoint { x: 3, y: -4 };
+
+    assert_eq!(types::point_scale(p, 10), IntPoint { x: 30, y: -40 });
+    assert_eq!(p, IntPoint { x: 3, y: -4 });
+}
+
+#[test]
+fn call_callback() {
+    assert_eq!(
+        callbacks::call_callback(Some(exported::rust_callback), 21),
+        Some(42)
+    );
+    assert_eq!(callbacks::call_callback(None, 21), None);
+}
+
+#[test]
+fn apply_twice() {
+    assert_eq!(callbacks::apply_twice(callbacks::double, 3), 12);
+    assert_eq!(callbacks::apply_twice_with(|x| x + 10, 3), 23);
+}
+
+// Any `extern "C" fn` with the right signature will do, not just the ones
+// the library defines.
+extern "C" fn add_five(x: core::ffi::c_int) -> core::ffi::c_int {
+    x + 5
+}
+
+fn triple(x: i32) -> i32 {
+    x * 3
+}
+
+#[test]
+fn apply_twice_with_an_extern_c_fn_defined_here() {
+    assert_eq!(callbacks::apply_twice(add_five, 1), 11);
+    assert_eq!(callbacks::apply_twice(callbacks::double, -3), -12);
+}
+
+#[test]
+fn apply_twice_with_goes_through_the_trampoline() {
+    assert_eq!(callbacks::apply_twice_with(triple, 2), 18);
+    assert_eq!(callbacks::apply_twice_with(|x| x - 1, 10), 8);
+    // A callback calling `apply_twice_with` itself gets its own function
+    // back afterwards: (0 + 1 + 1) + 1 + 1.
+    assert_eq!(
+        callbacks::apply_twice_with(|x| callbacks::apply_twice_with(|y| y + 1, x), 0),
+        4
+    );
+}
+
+#[test]
+fn for_each_collects_values() {
+    let mut seen = Vec::new();
+    callbacks::for_each(&[3, -1, 4, 1, -5], |value| seen.push(value));
+    assert_eq!(seen, [3, -1, 4, 1, -5]);
+}
+
+#[test]
+fn for_each_over_nothing_never_calls_the_closure() {
+    callbacks::for_each(&[], |_| panic!("called for an empty slice"));
+}
+
+#[test]
+fn for_each_closure_mutates_state() {
+    let mut total = 0;
+    let mut calls = 0;
+    callbacks::for_each(&[10, 20, 30], |value| {
+        total += value;
+        calls += 1;
+    });
+    assert_eq!((total, calls), (60, 3));
+}
+
+#[test]
+fn for_each_resumes_panics() {
+    let mut seen = Vec::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        callbacks::for_each(&[1, 2, 3], |value| {
+            seen.push(value);
+            if value == 2 {
+                panic!("boom");
+            }
+        })
+    }));
+
+    // The panic only resumes once C has returned, and 3 is skipped.
+    assert_eq!(result.unwrap_err().downcast_ref(), Some(&"boom"));
+    assert_eq!(seen, [1, 2]);
+}
+
+#[test]
+fn sum_array() {
+    assert_eq!(arrays::sum_array(&[1, 2, 3, 4]), 10);
+    assert_eq!(arrays::sum_array(&[-5, 5]), 0);
+    // An empty slice still has a non-null, dangling pointer, which C never
+    // reads.
+    assert_eq!(arrays::sum_array(&[]), 0);
+    assert_eq!(arrays::sum_array(&Vec::new()), 0);
+}
+
+#[test]
+fn sum_array_wraps_around() {
+    assert_eq!(arrays::sum_array(&[i32::MAX, 1]), i32::MIN);
+    assert_eq!(arrays::sum_array(&[i32::MIN, -1]), i32::MAX);
+}
+
+#[test]
+fn sum() {
+    assert_eq!(arrays::sum(&[1, 2, 3, 4]), 10);
+    assert_eq!(arrays::sum(&[]), 0);
+    assert_eq!(arrays::sum(&Vec::new()), 0);
+    // More than fits in an `i32`, which a `long long` holds easily.
+    assert_eq!(arrays::sum(&[i32::MAX; 4]), 4 * i64::from(i32::MAX));
+}
+
+#[test]
+fn sum_part_of_a_vec() {
+    // C gets a pointer into the middle of the `Vec` and a length, not a copy
+    // of the whole thing.
+    let values: Vec<i32> = (0..100).collect();
+    assert_eq!(arrays::sum(&values[10..20]), (10..20).sum::<i64>());
+}
+
+#[test]
+fn sum_large_vec() {
+    let values: Vec<i32> = (0..4_000_000).collect();
+    let expected: i64 = values.iter().map(|&value| i64::from(value)).sum();
+
+    assert_eq!(arrays::sum(&values), expected);
+}
+
+#[test]
+fn reverse_slice() {
+    let mut values = [1, 2, 3, 4, 5];
+    arrays::reverse_slice(&mut values);
+    assert_eq!(values, [5, 4, 3, 2, 1]);
+}
+
+// Small enough that no sum of up to 100 of them overflows an `i32`.
+fn summable() -> impl Strategy<Value = Vec<i32>> {
+    prop::collection::vec(-1_000_000..=1_000_000, 1..100)
+}
+
+proptest! {
+    #[test]
+    fn sum_slice_agrees_with_iter_sum(values in summable()) {
+        prop_assert_eq!(arrays::sum_slice(&values), values.iter().sum::<i32>());
+    }
+
+    #[test]
+    fn reverse_slice_is_its_own_inverse(original in prop::collection::vec(any::<i32>(), 1..100)) {
+        let mut values = original.clone();
+        arrays::reverse_slice(&mut values);
+
+        let mut expected = original.clone();
+        expected.reverse();
+        prop_assert_eq!(&values, &expected);
+
+        arrays::reverse_slice(&mut values);
+        prop_assert_eq!(values, original);
+    }
+}
+
+#[test]
+#[should_panic(expected = "empty slice")]
+fn sum_slice_rejects_empty() {
+    arrays::sum_slice(&[]);
+}
+
+#[test]
+#[should_panic(expected = "empty slice")]
+fn reverse_slice_rejects_empty() {
+    arrays::reverse_slice(&mut []);
+}
+
+#[test]
+fn context_sequence_depends_on_seed() {
+    let a = CContext::new(42);
+    let b = CContext::new(42);
+    let c = CContext::new(7);
+
+    let seq = |ctx: &CContext| (0..5).map(|_| ctx.value()).collect::<Vec<_>>();
+    assert_eq!(seq(&a), seq(&b));
+    assert_ne!(seq(&a), seq(&c));
+}
+
+#[test]
+fn calculator_accumulates() {
+    let mut calc = Calculator::new().unwrap();
+    assert_eq!(calc.result(), 0);
+    for value in [10, 20, 12] {
+        calc.accumulate(value);
+    }
+    assert_eq!(calc.result(), 42);
+    // Dropping `calc` frees it in C.
+}
+
+#[test]
+fn calculator_total_wraps_around() {
+    let mut calc = Calculator::new().unwrap();
+    calc.accumulate(i32::MAX);
+    calc.accumulate(1);
+    assert_eq!(calc.result(), i32::MIN);
+}
+
+#[test]
+fn calculators_are_independent() {
+    let mut a = Calculator::new().unwrap();
+    let b = Calculator::new().unwrap();
+    a.accumulate(7);
+    assert_eq!((a.result(), b.result()), (7, 0));
+}
+
+#[test]
+fn shared_calculator_is_exact_across_threads() {
+    const THREADS: i32 = 8;
+    const OPS: i32 = 10_000;
+
+    let calc = SharedCalculator::new().unwrap();
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for _ in 0..OPS {
+                    calc.accumulate(1);
+                }
+            });
+        }
+    });
+
+    assert_eq!(calc.result(), THREADS * OPS);
+}
+
+#[test]
+fn multiply_from_many_threads() {
+    ffi::set_verbose(false);
+    let before = ffi::call_count();
+
+    thread::scope(|s| {
+        for t in 0..8 {
+            s.spawn(move || {
+                for i in 0..1000 {
+                    assert_eq!(ffi::multiply(t, i), t * i);
+                }
+            });
+        }
+    });
+
+    // Other tests may call `multiply()` at the same time, but the atomic
+    // counter never loses one of these calls.
+    assert!(ffi::call_count() - before >= 8 * 1000);
+}
+
+#[test]
+fn multiply_in_parallel_keeps_the_order() {
+    ffi::set_verbose(false);
+    let pairs: Vec<_> = (-50..50).map(|i| (i, i + 1)).collect();
+    let expected: Vec<_> = pairs.iter().map(|&(a, b)| a * b).collect();
+
+    for threads in [1, 3, 8, 200] {
+        assert_eq!(threads::multiply_in_parallel(&pairs, threads), expected);
+    }
+    assert_eq!(threads::multiply_in_parallel(&[], 4), Vec::<i32>::new());
+}
+
+#[test]
+#[should_panic(expected = "at least one thread")]
+fn multiply_in_parallel_needs_a_thread() {
+    threads::multiply_in_parallel(&[(1, 2)], 0);
+}
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn wrappers_that_own_c_memory_can_change_threads() {
+    assert_send::<CIntBuffer>();
+    assert_sync::<CIntBuffer>();
+    assert_send::<OwnedCStr>();
+    assert_sync::<OwnedCStr>();
+    assert_send::<CAllocatedString>();
+    assert_sync::<CAllocatedString>();
+    assert_send::<Calculator>();
+    assert_send::<Counter>();
+    assert_send::<RingBuffer>();
+    assert_send::<SharedCalculator>();
+    assert_sync::<SharedCalculator>();
+
+    // Moved to another thread, used and freed there.
+    let values = range::make_range(0, 3).unwrap();
+    let mut counter = Counter::new().unwrap();
+    thread::spawn(move || {
+        counter.increment();
+        assert_eq!(counter.value(), 1);
+        assert_eq!(*values, [0, 1, 2]);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn classify() {
+    assert_eq!(status::classify(i32::MIN), Ok(StatusCode::Negative));
+    assert_eq!(status::classify(-5), Ok(StatusCode::Negative));
+    assert_eq!(status::classify(0), Ok(StatusCode::Zero));
+    assert_eq!(status::classify(5), Ok(StatusCode::Positive));
+    assert_eq!(status::classify(i32::MAX), Ok(StatusCode::Positive));
+}
+
+#[test]
+fn status_round_trips_through_c_int() {
+    for code in [StatusCode::Negative, StatusCode::Zero, StatusCode::Positive] {
+        assert_eq!(StatusCode::try_from(code as core::ffi::c_int), Ok(code));
+    }
+}
+
+#[test]
+fn status_rejects_unknown_values() {
+    assert_eq!(StatusCode::try_from(2), Err(UnknownStatus(2)));
+    assert_eq!(UnknownStatus(2).to_string(), "unknown status code 2");
+}
+
+#[test]
+fn round_trip_matches_addition() {
+    for (a, b) in [(40, 2), (0, 0), (-5, 5), (i32::MAX, 1), (i32::MIN, -1)] {
+        assert_eq!(roundtrip::compute_with_rust(a, b), a.wrapping_add(b));
+    }
+}
+
+#[test]
+fn round_trip_matches_calling_rust_add_directly() {
+    for (a, b) in [(1, 2), (i32::MAX, i32::MAX), (-100, 58)] {
+        assert_eq!(roundtrip::compute_with_rust(a, b), exported::rust_add(a, b));
+    }
+}
+
+#[test]
+fn safe_sqrt() {
+    assert_eq!(outparam::safe_sqrt(0.0), Ok(0.0));
+    assert_eq!(outparam::safe_sqrt(4.0), Ok(2.0));
+    assert_eq!(outparam::safe_sqrt(-1.0), Err(NegativeInputError(-1.0)));
+    assert_eq!(outparam::safe_sqrt(f64::INFINITY), Ok(f64::INFINITY));
+    assert!(outparam::safe_sqrt(f64::NEG_INFINITY).is_err());
+    // -0.0 isn't below zero, and its square root is -0.0.
+    assert!(outparam::safe_sqrt(-0.0).unwrap().is_sign_negative());
+    assert_eq!(
+        NegativeInputError(-1.0).to_string(),
+        "cannot take the square root of -1"
+    );
+}
+
+#[test]
+fn rust_divide_returns_quotient() {
+    let mut out = 0;
+    // SAFETY: `out` is a valid `&mut c_int`.
+    let status = unsafe { exported::rust_divide(-7, 2, &mut out) };
+
+    assert_eq!(status, exported::RUST_DIVIDE_OK);
+    assert_eq!(out, -3);
+}
+
+#[test]
+fn rust_divide_catches_panics() {
+    let mut out = 99;
+
+    // Both panic inside `rust_divide`. Reaching the asserts at all shows the
+    // panic didn't unwind out of it and abort the test binary.
+    for (a, b) in [(1, 0), (i32::MIN, -1)] {
+        // SAFETY: `out` is a valid `&mut c_int`.
+        let status = unsafe { exported::rust_divide(a, b, &mut out) };
+        assert_eq!(status, exported::RUST_DIVIDE_PANICKED);
+    }
+    assert_eq!(out, 99);
+}
+
+#[test]
+fn rust_divide_rejects_null() {
+    // SAFETY: `out` is deliberately NULL, which `rust_divide` checks for.
+    let status = unsafe { exported::rust_divide(1, 1, std::ptr::null_mut()) };
+    assert_eq!(status, exported::RUST_DIVIDE_NULL_POINTER);
+}
+
+extern "C" fn panics_on_negative(x: core::ffi::c_int) -> core::ffi::c_int {
+    rust_ffi_to_c::ffi_safe!(-1, {
+        assert!(x >= 0, "negative input");
+        x
+    })
+}
+
+#[test]
+fn ffi_safe_returns_sentinel_on_panic() {
+    assert_eq!(panics_on_negative(5), 5);
+    assert_eq!(panics_on_negative(-5), -1);
+}
+
+#[test]
+fn rust_add_wraps() {
+    assert_eq!(exported::rust_add(40, 2), 42);
+    assert_eq!(exported::rust_add(i32::MAX, 1), i32::MIN);
+}
+
+#[test]
+fn multiply_batch() {
+    let a = [0, -3, 7, i32::MAX];
+    let b = [5, 4, -7, 2];
+    let mut out = [0; 4];
+
+    ffi::multiply_batch(&a, &b, &mut out);
+    assert_eq!(out, [0, -12, -49, i32::MAX.wrapping_mul(2)]);
+
+    ffi::multiply_batch(&[], &[], &mut []);
+}
+
+#[test]
+#[should_panic(expected = "same length")]
+fn multiply_batch_rejects_mismatched_lengths() {
+    ffi::multiply_batch(&[1, 2], &[3], &mut [0, 0]);
+}
+
+#[test]
+fn for_each_drops_closure_after_call() {
+    let shared = std::rc::Rc::new(());
+    let captured = std::rc::Rc::clone(&shared);
+
+    callbacks::for_each(&[7, -1, 0], move |_| {
+        assert_eq!(std::rc::Rc::strong_count(&captured), 2);
+    });
+
+    // The closure, and the `Rc` it captured, are gone once C returns.
+    assert_eq!(std::rc::Rc::strong_count(&shared), 1);
+}
+
+#[test]
+fn describe_result() {
+    assert_eq!(
+        strings::describe_result(42).unwrap(),
+        "42 is positive and even"
+    );
+    assert_eq!(strings::describe_result(0).unwrap(), "0 is zero and even");
+    assert_eq!(
+        strings::describe_result(-7).unwrap(),
+        "-7 is negative and odd"
+    );
+}
+
+#[test]
+fn describe_result_longer_than_small_buffers() {
+    let description = strings::describe_result(i32::MIN).unwrap();
+
+    assert_eq!(description, "-2147483648 is negative and even");
+    assert!(description.len() > 16);
+}
+
+#[test]
+fn get_message() {
+    let message = alloc_string::get_message().unwrap();
+
+    assert_eq!(message.as_str(), "hello from C");
+    assert_eq!(
+        format!("{:?}", message),
+        r#"CAllocatedString("hello from C")"#
+    );
+}
+
+#[test]
+fn get_message_allocates_a_new_string_each_time() {
+    let a = alloc_string::get_message().unwrap();
+    let b = alloc_string::get_message().unwrap();
+
+    assert_ne!(a.as_str().as_ptr(), b.as_str().as_ptr());
+    assert_eq!(a.as_str(), b.as_str());
+}
@@ -0,0 +1,1098 @@
+//! Integration tests calling the C code through the crate's safe wrappers.
+//!
+//! Integration tests are compiled as separate crates that link against
+//! `rust_ffi_to_c`. The `cargo:rustc-link-*` lines from `build.rs` apply to
+//! the library, and Cargo passes them on to everything that links it, so
+//! these tests get the C library without any link flags of their own. If a
+//! change to `build.rs` or `c_src/` breaks linking, these fail to build.
+
+use std::mem::offset_of;
+use std::ptr::{self, NonNull};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use proptest::prelude::*;
+
+use rust_ffi_to_c::c_result::{self, CResult, CRESULT_ERR, CRESULT_OK};
+use rust_ffi_to_c::calculate::{self, CalcError, Op, UnknownOp};
+use rust_ffi_to_c::color::{self, Color, UnknownColor};
+use rust_ffi_to_c::counter::Counter;
+use rust_ffi_to_c::cstr_ownership;
+use rust_ffi_to_c::linked_list::{CLinkedList, CList};
+use rust_ffi_to_c::matrix::{DimensionError, Matrix};
+use rust_ffi_to_c::nullable::Config;
+use rust_ffi_to_c::progress::{CancellableTask, TaskError};
+use rust_ffi_to_c::record::{self, NameTooLongError, Record, RECORD_NAME_LEN};
+use rust_ffi_to_c::ring_buffer::{RawRingBuffer, RingBuffer, RingBufferView};
+use rust_ffi_to_c::safe::{self, OverflowError};
+use rust_ffi_to_c::union_demo::{Number, TaggedValue, UnknownTag};
+use rust_ffi_to_c::version::VersionError;
+use rust_ffi_to_c::vtable::{self, Calc, CalculatorVTable};
+use rust_ffi_to_c::{
+    booleans, exported, ffi, matrix, nullable, numeric, progress, range, sort, union_demo, varargs,
+    version,
+};
+
+// `multiply()` prints every call, which only clutters the test output.
+fn quiet() {
+    ffi::set_verbose(false);
+}
+
+#[test]
+fn multiply() {
+    quiet();
+
+    let before = ffi::call_count();
+    assert_eq!(ffi::multiply(5000, 5), 25000);
+    // Other tests may call `multiply()` at the same time.
+    assert!(ffi::call_count() > before);
+}
+
+#[test]
+fn multiply_by_zero() {
+    quiet();
+    assert_eq!(ffi::multiply(0, 7), 0);
+    assert_eq!(ffi::multiply(7, 0), 0);
+}
+
+#[test]
+fn multiply_negative_operands() {
+    quiet();
+    assert_eq!(ffi::multiply(-6, 7), -42);
+    assert_eq!(ffi::multiply(6, -7), -42);
+    assert_eq!(ffi::multiply(-6, -7), 42);
+}
+
+// With `--features bindgen`, `ffi::multiply` goes through the generated
+// declarations instead, and these same tests check that they behave like
+// the hand-written ones.
+#[test]
+fn multiply_agrees_with_rust() {
+    quiet();
+    for (a, b) in [(1, 1), (-1, 1), (123, -456), (46340, 46340)] {
+        assert_eq!(ffi::multiply(a, b), a * b);
+    }
+}
+
+#[test]
+fn multiply_wraps_on_overflow() {
+    quiet();
+    for (a, b) in [
+        (i32::MAX, 2),
+        (i32::MIN, -1),
+        (i32::MIN, i32::MIN),
+        (65536, 65536),
+    ] {
+        assert_eq!(ffi::multiply(a, b), a.wrapping_mul(b));
+        assert_eq!(ffi::try_multiply(a, b), Ok(a.wrapping_mul(b)));
+    }
+}
+
+#[test]
+fn multiply_checked_boundaries() {
+    assert_eq!(safe::multiply_checked(i32::MAX, 1), Ok(i32::MAX));
+    assert_eq!(safe::multiply_checked(3, 4), Ok(12));
+    assert_eq!(safe::multiply_checked(-6, 7), Ok(-42));
+    assert_eq!(
+        safe::multiply_checked(i32::MIN, -1),
+        Err(OverflowError::Overflow)
+    );
+    assert_eq!(
+        safe::multiply_checked(i32::MAX, 2),
+        Err(OverflowError::Overflow)
+    );
+}
+
+#[test]
+fn multiply_f64_special_values() {
+    assert_eq!(ffi::multiply_f64(2.5, -4.0), -10.0);
+    assert!(ffi::multiply_f64(f64::NAN, 1.0).is_nan());
+    assert!(ffi::multiply_f64(2.0, f64::NAN).is_nan());
+    assert!(ffi::multiply_f64(f64::INFINITY, 0.0).is_nan());
+    assert_eq!(ffi::multiply_f64(f64::INFINITY, -2.0), f64::NEG_INFINITY);
+    assert_eq!(ffi::multiply_f64(f64::MAX, 2.0), f64::INFINITY);
+
+    // -0.0 == 0.0, so compare the bits to check the sign survives.
+    assert_eq!(ffi::multiply_f64(-0.0, 5.0).to_bits(), (-0.0f64).to_bits());
+    assert_eq!(ffi::multiply_f64(0.0, -5.0).to_bits(), (-0.0f64).to_bits());
+    assert_eq!(ffi::multiply_f64(-0.0, -5.0).to_bits(), 0.0f64.to_bits());
+}
+
+#[test]
+fn multiply_f32_special_values() {
+    assert_eq!(ffi::multiply_f32(1.5, 2.0), 3.0);
+    assert!(ffi::multiply_f32(f32::NAN, 1.0).is_nan());
+    assert!(ffi::multiply_f32(2.0, f32::NAN).is_nan());
+    assert!(ffi::multiply_f32(f32::INFINITY, 0.0).is_nan());
+    assert_eq!(ffi::multiply_f32(f32::NEG_INFINITY, -3.0), f32::INFINITY);
+    assert_eq!(ffi::multiply_f32(f32::MAX, 2.0), f32::INFINITY);
+
+    assert_eq!(ffi::multiply_f32(0.0, -1.0).to_bits(), (-0.0f32).to_bits());
+    assert_eq!(ffi::multiply_f32(-0.0, -5.0).to_bits(), 0.0f32.to_bits());
+}
+
+#[test]
+fn calculate_each_op() {
+    assert_eq!(calculate::calculate(Op::Add, 40, 2), Ok(42));
+    assert_eq!(calculate::calculate(Op::Sub, 40, 2), Ok(38));
+    assert_eq!(calculate::calculate(Op::Mul, 40, 2), Ok(80));
+    assert_eq!(calculate::calculate(Op::Div, 40, 2), Ok(20));
+}
+
+#[test]
+fn calculate_errors() {
+    assert_eq!(
+        calculate::calculate(Op::Div, 1, 0),
+        Err(CalcError::DivisionByZero)
+    );
+    assert_eq!(
+        calculate::calculate(Op::Mul, i32::MAX, 2),
+        Err(CalcError::Overflow)
+    );
+}
+
+#[test]
+fn calculate_rejects_out_of_range_op() {
+    assert_eq!(calculate::calculate_raw(4, 1, 2), Err(CalcError::UnknownOp));
+    assert_eq!(
+        calculate::calculate_raw(-1, 1, 2),
+        Err(CalcError::UnknownOp)
+    );
+}
+
+#[test]
+fn c_result_layout_matches_c() {
+    assert_eq!(std::mem::size_of::<CResult>(), c_result::c_size());
+}
+
+#[test]
+fn c_result_success() {
+    assert_eq!(c_result::try_multiply(6, 7), Ok(42));
+    assert_eq!(c_result::try_multiply(i32::MIN, 1), Ok(i32::MIN));
+}
+
+#[test]
+fn c_result_error() {
+    assert_eq!(
+        c_result::try_multiply(i32::MAX, 2),
+        Err(CalcError::Overflow)
+    );
+
+    let err = CResult {
+        tag: CRESULT_ERR,
+        value: 0,
+        errcode: 1,
+    };
+    assert_eq!(Result::from(err), Err(CalcError::DivisionByZero));
+}
+
+#[test]
+fn c_result_unknown_tag_is_corrupt() {
+    for tag in [2, -1, i32::MAX] {
+        let raw = CResult {
+            tag,
+            value: 42,
+            errcode: 0,
+        };
+        assert_eq!(Result::from(raw), Err(CalcError::Corrupt(tag)));
+    }
+
+    let ok = CResult {
+        tag: CRESULT_OK,
+        value: 42,
+        errcode: 99,
+    };
+    assert_eq!(Result::<i32, CalcError>::from(ok), Ok(42));
+}
+
+#[test]
+fn op_from_c_int() {
+    assert_eq!(Op::try_from(3), Ok(Op::Div));
+    assert_eq!(Op::try_from(4), Err(UnknownOp(4)));
+    assert_eq!(Op::from_symbol('*'), Some(Op::Mul));
+    assert_eq!(Op::from_symbol('%'), None);
+    assert_eq!(Op::from_symbol('×'), None);
+}
+
+#[test]
+fn counter_counts() {
+    let mut counter = Counter::new().unwrap();
+    assert_eq!(counter.value(), 0);
+
+    for _ in 0..5 {
+        counter.increment();
+    }
+    assert_eq!(counter.value(), 5);
+}
+
+#[test]
+fn counters_are_independent() {
+    let mut a = Counter::new().unwrap();
+    let b = Counter::new().unwrap();
+
+    a.increment();
+    assert_eq!((a.value(), b.value()), (1, 0));
+}
+
+#[test]
+fn static_string_is_borrowed() {
+    let borrowed = cstr_ownership::static_string();
+    assert_eq!(borrowed.to_str(), Ok("I live in C's static memory"));
+}
+
+#[test]
+fn heap_string_is_owned() {
+    // Each copy is freed once when dropped; under a leak checker, none of
+    // them leak.
+    for _ in 0..100 {
+        let owned = cstr_ownership::heap_string("Ferris").unwrap().unwrap();
+        assert_eq!(owned.to_bytes(), b"Ferris");
+    }
+
+    assert!(cstr_ownership::heap_string("Fer\0ris").is_err());
+}
+
+#[test]
+fn tagged_union_int_round_trip() {
+    let value = union_demo::tagged_from_int(-42);
+
+    assert_eq!(value.tag, union_demo::VALUE_INT);
+    assert_eq!(Number::try_from(value), Ok(Number::Int(-42)));
+    assert_eq!(union_demo::tagged_as_f64(value), Ok(-42.0));
+    assert_eq!(format!("{:?}", value), "TaggedValue(Int(-42))");
+}
+
+#[test]
+fn tagged_union_float_round_trip() {
+    let value = union_demo::tagged_from_float(0.5);
+    assert_eq!(Number::try_from(value), Ok(Number::Float(0.5)));
+
+    // Built in Rust, read in C.
+    let value = TaggedValue::from(Number::Float(-1.25));
+    assert_eq!(union_demo::tagged_as_f64(value), Ok(-1.25));
+}
+
+#[test]
+fn tagged_union_rejects_unknown_tag() {
+    let mut value = union_demo::tagged_from_int(7);
+    value.tag = 2;
+
+    assert_eq!(Number::try_from(value), Err(UnknownTag(2)));
+    assert_eq!(union_demo::tagged_as_f64(value), Err(UnknownTag(2)));
+}
+
+#[test]
+fn union_bytes_match_rust() {
+    assert_eq!(
+        std::mem::size_of::<union_demo::IntFloat>(),
+        union_demo::c_size()
+    );
+    assert_eq!(union_demo::c_size(), 4);
+
+    let one = union_demo::from_float(1.0);
+    assert_eq!(one.as_float(), 1.0);
+    assert_eq!(one.as_int() as u32, 0x3f80_0000);
+    assert_eq!(one.as_int() as u32, 1.0f32.to_bits());
+    assert_eq!(one.as_bytes(), 1.0f32.to_ne_bytes());
+
+    for i in [0, 1, -1, 0x1234_5678, i32::MIN, i32::MAX] {
+        let value = union_demo::from_int(i);
+        assert_eq!(value.as_int(), i);
+        assert_eq!(value.as_bytes(), i.to_ne_bytes());
+        assert_eq!(value.as_float().to_bits(), i as u32);
+    }
+}
+
+#[test]
+fn make_range_iterates() {
+    let values = range::make_range(-2, 5).unwrap();
+    assert_eq!(
+        values.iter().copied().collect::<Vec<_>>(),
+        [-2, -1, 0, 1, 2]
+    );
+    assert_eq!(values.len(), 5);
+}
+
+#[test]
+fn make_range_empty() {
+    let values = range::make_range(10, 0).unwrap();
+    assert!(values.is_empty());
+    assert_eq!(values.iter().next(), None);
+}
+
+#[test]
+fn make_range_vec_copies() {
+    // Leaks nothing: run under LeakSanitizer or valgrind to check that C's
+    // copy is freed.
+    assert_eq!(range::make_range_vec(5), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn make_range_vec_empty() {
+    assert_eq!(range::make_range_vec(0), Vec::<i32>::new());
+    assert_eq!(range::make_range_vec(-3), Vec::<i32>::new());
+}
+
+#[test]
+fn make_range_too_long() {
+    assert_eq!(
+        range::make_range(i32::MAX, 2).unwrap_err(),
+        range::RangeError::TooLong
+    );
+    assert_eq!(range::make_range(i32::MAX, 1).unwrap()[..], [i32::MAX]);
+}
+
+#[test]
+fn sum_ints() {
+    assert_eq!(varargs::sum_ints(&[1, 2, 3]), Some(6));
+    assert_eq!(varargs::sum_ints(&[10, 20, 30]), Some(60));
+    assert_eq!(varargs::sum_ints(&[]), Some(0));
+    assert_eq!(varargs::sum_ints(&[7]), Some(7));
+    assert_eq!(varargs::sum_ints(&[7, -2]), Some(5));
+    assert_eq!(varargs::sum_ints(&[1, 2, 3, 4]), Some(10));
+    assert_eq!(varargs::sum_ints(&[1, 2, 3, 4, 5]), None);
+}
+
+#[test]
+fn sum_ints_wraps_on_overflow() {
+    assert_eq!(varargs::sum_ints(&[i32::MAX, 1]), Some(i32::MIN));
+    assert_eq!(varargs::sum_ints(&[i32::MIN, -1]), Some(i32::MAX));
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn sum_ints_rust() {
+    // SAFETY: three `c_int`s follow the count.
+    assert_eq!(unsafe { varargs::sum_ints_rust(3, 10, 20, 30) }, 60);
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_multiply() {
+    use rust_ffi_to_c::dynamic::{default_path, DynamicMultiply};
+
+    let library = DynamicMultiply::load(&default_path()).unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(library.multiply(6, 7), 42);
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_version_matches_linked_library() {
+    use rust_ffi_to_c::dynamic::{default_path, DynamicMultiply};
+
+    let library = DynamicMultiply::load(&default_path()).unwrap();
+    assert_eq!(library.version().as_deref(), Some(version::version()));
+    assert_eq!(library.require_version((1, 2, 0)), Ok(()));
+    assert!(library.require_version((2, 0, 0)).is_err());
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_missing_library() {
+    use rust_ffi_to_c::dynamic::{DynamicError, DynamicMultiply};
+
+    let err = DynamicMultiply::load("does/not/exist.so".as_ref())
+        .err()
+        .unwrap();
+    assert!(matches!(err, DynamicError::LibraryNotFound { .. }));
+    assert!(err
+        .to_string()
+        .starts_with("failed to load does/not/exist.so: "));
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn scale_fractional_values() {
+    assert_eq!(numeric::scale(2.5, 0.5), 1.25);
+    assert_eq!(numeric::scale(0.1, 3.0), 0.1 * 3.0);
+    assert_eq!(numeric::scale(-7.25, -0.25), 1.8125);
+    assert_eq!(numeric::scale(1.0, 0.0), 0.0);
+}
+
+#[test]
+fn scale_propagates_nan() {
+    assert!(numeric::scale(f64::NAN, 2.0).is_nan());
+    assert!(numeric::scale(2.0, f64::NAN).is_nan());
+    // Infinity times zero is NaN in C as in Rust.
+    assert!(numeric::scale(f64::INFINITY, 0.0).is_nan());
+}
+
+fn identity(n: usize) -> Vec<f64> {
+    Matrix::identity(n).as_slice().to_vec()
+}
+
+#[test]
+fn mat_mul_1x1() {
+    assert_eq!(matrix::mat_mul(&[3.0], &[-2.5], 1), [-7.5]);
+}
+
+#[test]
+fn mat_mul_2x2() {
+    let a = [1.0, 2.0, 3.0, 4.0];
+    let b = [5.0, 6.0, 7.0, 8.0];
+
+    assert_eq!(matrix::mat_mul(&a, &b, 2), [19.0, 22.0, 43.0, 50.0]);
+    // Matrix multiplication doesn't commute.
+    assert_eq!(matrix::mat_mul(&b, &a, 2), [23.0, 34.0, 31.0, 46.0]);
+}
+
+#[test]
+fn mat_mul_4x4() {
+    let a: Vec<f64> = (0..16).map(f64::from).collect();
+    let b: Vec<f64> = (0..16).map(|x| f64::from(x % 3)).collect();
+
+    let mut expected = vec![0.0; 16];
+    for i in 0..4 {
+        for j in 0..4 {
+            expected[i * 4 + j] = (0..4).map(|k| a[i * 4 + k] * b[k * 4 + j]).sum();
+        }
+    }
+
+    assert_eq!(matrix::mat_mul(&a, &b, 4), expected);
+}
+
+#[test]
+fn mat_mul_identity() {
+    let a: Vec<f64> = (0..9).map(|x| f64::from(x) * 0.5).collect();
+
+    assert_eq!(matrix::mat_mul(&a, &identity(3), 3), a);
+    assert_eq!(matrix::mat_mul(&identity(3), &a, 3), a);
+}
+
+#[test]
+fn mat_mul_empty() {
+    assert!(matrix::mat_mul(&[], &[], 0).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "mat_mul needs two 2x2 matrices")]
+fn mat_mul_rejects_wrong_size() {
+    matrix::mat_mul(&[1.0, 2.0, 3.0], &identity(2), 2);
+}
+
+#[test]
+fn linked_list_iterates_in_order() {
+    let list = CLinkedList::new(&[1, 2, 3]).unwrap();
+    assert_eq!(list.collect::<Vec<_>>(), [1, 2, 3]);
+}
+
+#[test]
+fn linked_list_empty() {
+    let mut list = CLinkedList::new(&[]).unwrap();
+    assert_eq!(list.next(), None);
+}
+
+#[test]
+fn linked_list_dropped_part_way() {
+    // The remaining nodes are still freed. Run under valgrind to check.
+    let mut list = CLinkedList::new(&[4, 5, 6]).unwrap();
+    assert_eq!(list.next(), Some(4));
+    assert_eq!(format!("{:?}", list), "[5, 6]");
+}
+
+#[test]
+fn clist_matches_range() {
+    for n in [0, 1, 2, 10, 1000] {
+        let list = CList::new(n).unwrap();
+        assert_eq!(list.iter().collect::<Vec<_>>(), (0..n).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn clist_empty() {
+    for n in [0, -1, i32::MIN] {
+        let list = CList::new(n).unwrap();
+        assert_eq!(list.iter().next(), None);
+        assert_eq!(format!("{:?}", list), "[]");
+    }
+}
+
+#[test]
+fn clist_iterates_twice() {
+    let list = CList::new(4).unwrap();
+
+    let first: Vec<i32> = (&list).into_iter().collect();
+    let mut second = Vec::new();
+    for value in &list {
+        second.push(value);
+    }
+
+    assert_eq!(first, [0, 1, 2, 3]);
+    assert_eq!(first, second);
+    assert_eq!(format!("{:?}", list), "[0, 1, 2, 3]");
+}
+
+#[test]
+fn clist_iterator_outlives_collect() {
+    let list = CList::new(5).unwrap();
+    let mut iter = list.iter();
+
+    // Take part of the list, then keep using the iterator after the
+    // collected values are gone, and drop it before the list. Under
+    // valgrind, any read of a freed node shows up here.
+    let head: Vec<i32> = iter.by_ref().take(2).collect();
+    drop(head);
+    let resumed = iter.clone();
+    assert_eq!(iter.collect::<Vec<_>>(), [2, 3, 4]);
+    assert_eq!(resumed.sum::<i32>(), 9);
+
+    drop(list);
+}
+
+fn matmul_reference(a: &Matrix, b: &Matrix) -> Vec<f64> {
+    let n = a.n();
+    let (a, b) = (a.as_slice(), b.as_slice());
+
+    (0..n * n)
+        .map(|index| {
+            let (i, j) = (index / n, index % n);
+            (0..n).map(|k| a[i * n + k] * b[k * n + j]).sum()
+        })
+        .collect()
+}
+
+#[test]
+fn matrix_matmul_matches_reference() {
+    for n in [0, 1, 2, 5, 16] {
+        let a = Matrix::new(n, (0..n * n).map(|x| x as f64 * 0.5).collect()).unwrap();
+        let b = Matrix::new(n, (0..n * n).map(|x| (x % 7) as f64 - 3.0).collect()).unwrap();
+
+        let product = a.matmul(&b).unwrap();
+        assert_eq!(product.n(), n);
+        assert_eq!(product.as_slice(), matmul_reference(&a, &b), "n = {}", n);
+    }
+}
+
+#[test]
+fn matrix_matmul_identity() {
+    let a = Matrix::new(3, (1..=9).map(f64::from).collect()).unwrap();
+
+    assert_eq!(a.matmul(&Matrix::identity(3)).unwrap(), a);
+    assert_eq!(Matrix::identity(3).matmul(&a).unwrap(), a);
+}
+
+#[test]
+fn matrix_dimension_errors() {
+    assert_eq!(
+        Matrix::new(2, vec![1.0; 3]),
+        Err(DimensionError::WrongLength { n: 2, len: 3 })
+    );
+    assert_eq!(
+        Matrix::identity(2).matmul(&Matrix::identity(3)),
+        Err(DimensionError::Mismatch { left: 2, right: 3 })
+    );
+}
+
+#[test]
+fn next_color_cycles() {
+    let mut color = Color::Red;
+    let mut seen = Vec::new();
+
+    for _ in 0..3 {
+        color = color::next_color(color).unwrap();
+        seen.push(color);
+    }
+
+    assert_eq!(seen, [Color::Green, Color::Blue, Color::Red]);
+}
+
+#[test]
+fn next_color_rejects_unknown_values() {
+    assert_eq!(color::next_color_raw(2), Ok(Color::Red));
+    assert_eq!(color::next_color_raw(3), Err(UnknownColor(3)));
+    assert_eq!(color::next_color_raw(-1), Err(UnknownColor(-1)));
+}
+
+// A narrow range for some inputs, so there are duplicates, and the full range
+// for the rest, so there are extremes.
+fn unsorted() -> impl Strategy<Value = Vec<i32>> {
+    prop_oneof![
+        prop::collection::vec(-8..8, 0..64),
+        prop::collection::vec(any::<i32>(), 0..64),
+    ]
+}
+
+fn check_sorted(input: &[i32], output: &[i32]) -> Result<(), TestCaseError> {
+    prop_assert!(
+        output.windows(2).all(|pair| pair[0] <= pair[1]),
+        "{:?}",
+        output
+    );
+
+    // Same multiset of values: sorting the input in Rust gives the same
+    // result.
+    let mut expected = input.to_vec();
+    expected.sort_unstable();
+    prop_assert_eq!(output, expected);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn sort_slice_via_c_sorts(input in unsorted()) {
+        let mut output = input.clone();
+        sort::sort_slice_via_c(&mut output);
+        check_sorted(&input, &output)?;
+    }
+
+    #[test]
+    fn sort_slice_with_qsort_sorts(input in unsorted()) {
+        let mut output = input.clone();
+        sort::sort_slice_with_qsort(&mut output);
+        check_sorted(&input, &output)?;
+    }
+}
+
+#[test]
+fn sort_extremes_and_empty() {
+    let mut values = [i32::MAX, i32::MIN, 0, i32::MIN, -1];
+    sort::sort_slice_via_c(&mut values);
+    assert_eq!(values, [i32::MIN, i32::MIN, -1, 0, i32::MAX]);
+
+    sort::sort_slice_via_c(&mut []);
+    sort::sort_slice_with_qsort(&mut []);
+}
+
+#[test]
+fn find_first_found() {
+    assert_eq!(nullable::find_first(&[3, 1, 4, 1, 5], 4), Some(2));
+}
+
+#[test]
+fn find_first_returns_first_match() {
+    assert_eq!(nullable::find_first(&[3, 1, 4, 1, 5], 1), Some(1));
+}
+
+#[test]
+fn find_first_not_found() {
+    assert_eq!(nullable::find_first(&[3, 1, 4, 1, 5], 9), None);
+}
+
+#[test]
+fn find_first_empty() {
+    assert_eq!(nullable::find_first(&[], 0), None);
+}
+
+#[test]
+fn find_first_first_and_last_element() {
+    let values = [7, 8, 9];
+    assert_eq!(nullable::find_first(&values, 7), Some(0));
+    assert_eq!(nullable::find_first(&values, 9), Some(2));
+}
+
+#[test]
+fn maybe_value_non_null() {
+    assert_eq!(nullable::maybe_value(false), Some(42));
+}
+
+#[test]
+fn maybe_value_null() {
+    assert_eq!(nullable::maybe_value(true), None);
+}
+
+#[test]
+fn slow_sum_reports_progress() {
+    let mut reports = Vec::new();
+    let sum = progress::slow_sum(1000, |percent| reports.push(percent));
+
+    assert_eq!(sum, 999 * 1000 / 2);
+    assert_eq!(reports.first(), Some(&0));
+    assert_eq!(reports.last(), Some(&100));
+    assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn slow_sum_of_nothing_still_finishes() {
+    let mut reports = Vec::new();
+    assert_eq!(progress::slow_sum(0, |percent| reports.push(percent)), 0);
+    assert_eq!(reports, [0, 100]);
+}
+
+#[test]
+#[should_panic(expected = "progress bar broke")]
+fn slow_sum_resumes_a_panic_after_c_returns() {
+    progress::slow_sum(1000, |percent| {
+        if percent == 50 {
+            panic!("progress bar broke");
+        }
+    });
+}
+
+#[test]
+fn record_new_round_trips_the_name() {
+    let record = Record::new("Ferris", 7, 1.5).unwrap();
+    assert_eq!(record.name_as_str(), "Ferris");
+    assert_eq!((record.id, record.score), (7, 1.5));
+}
+
+#[test]
+fn record_new_checks_the_length() {
+    let longest = "x".repeat(RECORD_NAME_LEN - 1);
+    assert_eq!(
+        Record::new(&longest, 0, 0.0).unwrap().name_as_str(),
+        longest
+    );
+
+    let too_long = "x".repeat(RECORD_NAME_LEN);
+    assert_eq!(
+        Record::new(&too_long, 0, 0.0).unwrap_err(),
+        NameTooLongError {
+            len: RECORD_NAME_LEN
+        }
+    );
+}
+
+#[test]
+fn make_record_truncates_in_c() {
+    let record = record::make_record(c"Corro", 2, 87.25);
+    assert_eq!(record.name_as_str(), "Corro");
+    assert_eq!((record.id, record.score), (2, 87.25));
+
+    let long = std::ffi::CString::new("y".repeat(100)).unwrap();
+    let record = record::make_record(&long, 3, 0.0);
+    assert_eq!(record.name_as_str(), "y".repeat(RECORD_NAME_LEN - 1));
+}
+
+#[test]
+fn name_as_str_handles_unterminated_and_invalid_names() {
+    let mut record = Record::new("", 0, 0.0).unwrap();
+
+    record.name = [b'z' as _; RECORD_NAME_LEN];
+    assert_eq!(record.name_as_str(), "z".repeat(RECORD_NAME_LEN));
+
+    record.name[..4].copy_from_slice(&[b'o' as _, b'k' as _, 0xff_u8 as _, 0]);
+    assert_eq!(record.name_as_str(), "ok");
+}
+
+#[test]
+fn cancellable_task_finishes_without_cancel() {
+    assert_eq!(CancellableTask::new(1000).run(), Ok(999 * 1000 / 2));
+}
+
+#[test]
+fn cancellable_task_stops_when_cancelled() {
+    // Would take many minutes to finish.
+    let task = CancellableTask::new(1 << 40);
+    let token = task.cancel_token();
+
+    let start = Instant::now();
+    let worker = thread::spawn(move || task.run());
+    thread::sleep(Duration::from_millis(5));
+    token.cancel();
+
+    assert_eq!(worker.join().unwrap(), Err(TaskError::Cancelled));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn all_positive_returns_c_bools() {
+    assert!(booleans::all_positive(&[1, 2, 3]));
+    assert!(!booleans::all_positive(&[1, 0, 3]));
+    assert!(!booleans::all_positive(&[-1]));
+    assert!(booleans::all_positive(&[]));
+}
+
+#[test]
+fn set_flags_round_trips() {
+    let mut flags = [false, true, false];
+
+    booleans::set_flags(&mut flags, true);
+    assert_eq!(flags, [true; 3]);
+    // The bytes C wrote are exactly 1, as Rust requires.
+    assert!(flags.iter().all(|&flag| flag as u8 == 1));
+
+    booleans::set_flags(&mut flags, false);
+    assert_eq!(flags, [false; 3]);
+    assert!(flags.iter().all(|&flag| flag as u8 == 0));
+}
+
+#[test]
+fn configure_with_and_without_config() {
+    let cfg = Config {
+        retries: 1,
+        timeout_ms: 250,
+    };
+    assert_eq!(nullable::configure(Some(&cfg)), 500);
+    // The defaults: three retries of 100 ms after the first try.
+    assert_eq!(nullable::configure(None), 400);
+
+    // Overflows wrap around in C, rather than being undefined.
+    let cfg = Config {
+        retries: i32::MAX,
+        timeout_ms: i32::MAX,
+    };
+    assert_eq!(nullable::configure(Some(&cfg)), i32::MIN);
+}
+
+#[test]
+fn rust_configure_matches_c() {
+    for (retries, timeout_ms) in [(4, 30), (i32::MAX, 2), (i32::MAX, i32::MAX), (-1, i32::MIN)] {
+        let cfg = Config {
+            retries,
+            timeout_ms,
+        };
+
+        // SAFETY: a pointer to a `Config` that outlives the call.
+        unsafe {
+            assert_eq!(
+                exported::rust_configure(Some(NonNull::from(&cfg))),
+                nullable::configure(Some(&cfg))
+            );
+        }
+    }
+
+    // SAFETY: NULL is allowed.
+    unsafe {
+        assert_eq!(exported::rust_configure(None), nullable::configure(None));
+    }
+}
+
+#[test]
+fn vtable_from_c() {
+    let calc = vtable::c_calculator();
+
+    assert_eq!(calc.add(40, 2), 42);
+    assert_eq!(calc.sub(40, 2), 38);
+    assert_eq!(calc.add(i32::MAX, 1), i32::MIN);
+}
+
+#[test]
+fn vtable_from_rust_called_by_c() {
+    let calc = vtable::make_rust_calculator();
+
+    assert_eq!(vtable::apply_add(&calc, 40, 2), 42);
+    assert_eq!(vtable::apply_sub(&calc, 40, 2), 38);
+    assert_eq!(vtable::apply_sub(&calc, i32::MIN, 1), i32::MAX);
+}
+
+#[test]
+fn vtable_both_sides_agree() {
+    let c = *vtable::c_calculator();
+    let rust = vtable::make_rust_calculator();
+
+    for (a, b) in [(0, 0), (-7, 3), (i32::MAX, i32::MAX), (i32::MIN, 1)] {
+        assert_eq!(c.add(a, b), rust.add(a, b));
+        assert_eq!(c.sub(a, b), rust.sub(a, b));
+        assert_eq!(vtable::apply_add(&c, a, b), vtable::apply_add(&rust, a, b));
+    }
+}
+
+#[test]
+fn vtable_with_custom_function() {
+    extern "C" fn saturating_add(a: i32, b: i32) -> i32 {
+        a.saturating_add(b)
+    }
+
+    let calc = CalculatorVTable {
+        add: saturating_add,
+        ..vtable::make_rust_calculator()
+    };
+
+    assert_eq!(vtable::apply_add(&calc, i32::MAX, 1), i32::MAX);
+}
+
+#[test]
+fn lib_version() {
+    assert_eq!(version::version(), "1.2.0");
+    // Cached, so both calls return the same string.
+    assert_eq!(version::version().as_ptr(), version::version().as_ptr());
+}
+
+#[test]
+fn parse_well_formed_versions() {
+    assert_eq!(version::parse_version("1.2.0"), Ok((1, 2, 0)));
+    assert_eq!(version::parse_version("0.0.0"), Ok((0, 0, 0)));
+    assert_eq!(version::parse_version("10.20.30"), Ok((10, 20, 30)));
+    assert_eq!(
+        version::parse_version("4294967295.0.1"),
+        Ok((u32::MAX, 0, 1))
+    );
+}
+
+#[test]
+fn parse_malformed_versions() {
+    for bad in [
+        "",
+        "1",
+        "1.2",
+        "1.2.3.4",
+        "1..3",
+        ".1.2",
+        "1.2.",
+        "v1.2.3",
+        "1.2.3-rc1",
+        " 1.2.3",
+        "1.+2.3",
+        "1.2.x",
+        "4294967296.0.0",
+    ] {
+        assert_eq!(
+            version::parse_version(bad),
+            Err(VersionError::Malformed(bad.to_owned())),
+            "version: {:?}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn require_version() {
+    assert_eq!(version::require_version((1, 2, 0)), Ok(()));
+    assert_eq!(version::require_version((1, 1, 9)), Ok(()));
+    assert_eq!(version::require_version((0, 9, 0)), Ok(()));
+    assert_eq!(
+        version::require_version((1, 2, 1)),
+        Err(VersionError::TooOld {
+            found: (1, 2, 0),
+            required: (1, 2, 1)
+        })
+    );
+    assert!(version::require_version((2, 0, 0)).is_err());
+}
+
+#[test]
+fn check_version_rejects_malformed_library_version() {
+    assert_eq!(
+        version::check_version("1.2", (1, 0, 0)),
+        Err(VersionError::Malformed("1.2".into()))
+    );
+}
+
+#[test]
+fn ring_buffer_layout_matches_c() {
+    assert_eq!(offset_of!(RawRingBuffer, data), RingBuffer::c_data_offset());
+}
+
+#[test]
+fn ring_buffer_rejects_zero_capacity() {
+    assert!(RingBuffer::new(0).is_err());
+}
+
+#[test]
+fn ring_buffer_full_and_empty() {
+    let mut rb = RingBuffer::new(3).unwrap();
+    assert!(rb.is_empty());
+    assert_eq!(rb.pop(), None);
+
+    assert!(rb.push(1));
+    assert!(rb.push(2));
+    assert!(rb.push(3));
+    assert!(!rb.push(4));
+    assert_eq!((rb.len(), rb.capacity()), (3, 3));
+
+    assert_eq!(rb.pop(), Some(1));
+    assert_eq!(rb.pop(), Some(2));
+    assert_eq!(rb.pop(), Some(3));
+    assert_eq!(rb.pop(), None);
+}
+
+#[test]
+fn ring_buffer_wraps_around() {
+    let mut rb = RingBuffer::new(3).unwrap();
+
+    // Each round starts one slot further along, so the values wrap past
+    // the end of `data` in all but the first.
+    for round in 0..10 {
+        assert!(rb.push(round));
+        assert!(rb.push(round + 100));
+        assert_eq!(rb.pop(), Some(round));
+        assert_eq!(rb.pop(), Some(round + 100));
+    }
+
+    for value in 0..3 {
+        assert!(rb.push(value));
+    }
+    assert_eq!(rb.pop(), Some(0));
+    assert!(rb.push(3));
+    assert!(!rb.push(4));
+    assert_eq!(rb.pop(), Some(1));
+    assert_eq!(rb.pop(), Some(2));
+    assert_eq!(rb.pop(), Some(3));
+}
+
+#[test]
+fn ring_buffer_view_shares_cs_memory() {
+    let mut rb = RingBuffer::new(2).unwrap();
+
+    // Written by C, read in place by Rust, and the other way around, with
+    // the values wrapping past the end each time.
+    for round in 0..5 {
+        assert!(rb.push(round));
+        assert!(rb.push(round + 1));
+        let mut view = rb.view();
+        assert!(!view.push(-1));
+        assert_eq!(view.pop(), Some(round));
+        assert_eq!(view.pop(), Some(round + 1));
+        assert_eq!(view.pop(), None);
+
+        assert!(view.push(round * 10));
+        assert_eq!(rb.pop(), Some(round * 10));
+    }
+    assert!(rb.is_empty());
+}
+
+// A ring buffer laid out by Rust, standing in for memory another process
+// owns.
+#[repr(C)]
+struct ForeignRingBuffer {
+    header: RawRingBuffer,
+    data: [i32; 4],
+}
+
+fn foreign(capacity: usize, head: usize, len: usize) -> ForeignRingBuffer {
+    ForeignRingBuffer {
+        header: RawRingBuffer {
+            capacity,
+            head,
+            len,
+            data: [],
+        },
+        data: [0; 4],
+    }
+}
+
+#[test]
+fn ring_buffer_view_over_foreign_memory() {
+    let mut memory = foreign(4, 3, 0);
+
+    {
+        // SAFETY: a consistent header followed by 4 values, used only
+        // through the view until the end of this block.
+        let ptr = ptr::addr_of_mut!(memory).cast();
+        let mut view = unsafe { RingBufferView::from_raw(ptr) }.unwrap();
+        for value in 1..=4 {
+            assert!(view.push(value));
+        }
+        assert!(!view.push(5));
+        assert_eq!(view.pop(), Some(1));
+    }
+
+    // The values wrapped around from the last slot to the first.
+    assert_eq!(memory.data, [2, 3, 4, 1]);
+    assert_eq!((memory.header.head, memory.header.len), (0, 3));
+}
+
+#[test]
+fn ring_buffer_view_rejects_bad_headers() {
+    // SAFETY: NULL is always rejected.
+    assert!(unsafe { RingBufferView::from_raw(ptr::null_mut()) }.is_none());
+
+    for (capacity, head, len) in [(0, 0, 0), (4, 4, 0), (4, 0, 5)] {
+        let mut memory = foreign(capacity, head, len);
+        // SAFETY: the header is initialized and followed by 4 values, and is
+        // rejected before any of them is read.
+        let view = unsafe { RingBufferView::from_raw(ptr::addr_of_mut!(memory).cast()) };
+        assert!(view.is_none(), "{:?}", (capacity, head, len));
+    }
+}
+
+#[test]
+#[cfg(target_os = "windows")]
+fn multiply_stdcall() {
+    use rust_ffi_to_c::stdcall;
+
+    assert_eq!(stdcall::multiply_stdcall(6, 7), 42);
+    assert_eq!(stdcall::multiply_stdcall(-6, 7), -42);
+    assert_eq!(stdcall::multiply_stdcall(i32::MAX, 2), -2);
+}
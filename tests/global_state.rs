@@ -0,0 +1,17 @@
+use rust_ffi_to_c::global_state::GlobalCounter;
+
+#[test]
+fn bump_and_read_back() {
+    let counter = GlobalCounter::take().unwrap();
+    // Only one handle exists at a time.
+    assert!(GlobalCounter::take().is_none());
+
+    let before = counter.read();
+    for _ in 0..3 {
+        counter.bump();
+    }
+    assert_eq!(counter.read(), before + 3);
+
+    drop(counter);
+    assert!(GlobalCounter::take().is_some());
+}
@@ -0,0 +1,26 @@
+use rust_ffi_to_c::ffi;
+use rust_ffi_to_c::globals::{self, CallCountToken};
+
+// The only test in this file, so nothing else calls `multiply()` while it
+// counts.
+#[test]
+fn call_count_through_both_paths() {
+    let mut token = CallCountToken::take().unwrap();
+    // Only one token exists at a time.
+    assert!(CallCountToken::take().is_none());
+
+    ffi::set_verbose(false);
+    globals::reset_direct(&mut token);
+    for _ in 0..3 {
+        ffi::multiply(6, 7);
+    }
+
+    assert_eq!(globals::call_count(&token), 3);
+    assert_eq!(globals::call_count_direct(&token), 3);
+
+    globals::increment(&mut token);
+    assert_eq!(globals::call_count(&token), 4);
+
+    drop(token);
+    assert!(CallCountToken::take().is_some());
+}
@@ -0,0 +1,45 @@
+//! The C library in `c_src/library.c` can only be initialized once at a
+//! time, so every test here shares one guard, as the module docs describe.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::thread;
+
+use rust_ffi_to_c::library::{self, LibraryGuard};
+
+fn library_guard() -> &'static LibraryGuard {
+    static GUARD: OnceLock<LibraryGuard> = OnceLock::new();
+    GUARD.get_or_init(|| LibraryGuard::new().expect("library_init failed"))
+}
+
+#[test]
+fn ids_are_unique_across_threads() {
+    let guard = library_guard();
+
+    let ids: Vec<i32> = thread::scope(|s| {
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                s.spawn(|| {
+                    (0..100)
+                        .map(|_| library::next_id(guard))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    assert_eq!(ids.len(), 800);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 800);
+}
+
+#[test]
+fn second_guard_is_refused() {
+    library_guard();
+
+    assert!(library::is_initialized());
+    assert_eq!(LibraryGuard::new().unwrap_err().code, -1);
+}
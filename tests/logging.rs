@@ -0,0 +1,94 @@
+//! C's log messages reaching a `log::Log` implementation.
+//!
+//! The logger and C's callback are both global, so they get a test binary
+//! of their own. Every test installs the same ones and looks for its own
+//! records among everybody's.
+
+use core::ffi::{c_char, c_int};
+use std::sync::{Mutex, Once};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rust_ffi_to_c::{arithmetic, ffi, logging};
+
+extern "C" {
+    fn c_log(level: c_int, fmt: *const c_char, ...);
+}
+
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        self.records.lock().unwrap().push((
+            record.level(),
+            record.target().to_owned(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+fn setup() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+        ffi::set_verbose(false);
+        logging::install_c_logging();
+    });
+}
+
+fn logged(level: Level, msg: &str) -> bool {
+    LOGGER
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|record| *record == (level, "libmultiply".to_owned(), msg.to_owned()))
+}
+
+#[test]
+fn multiply_logs_at_debug_level() {
+    setup();
+
+    assert_eq!(ffi::multiply(6, 7), 42);
+    assert!(logged(Level::Debug, "multiply(6, 7)"));
+}
+
+#[test]
+fn divide_logs_at_debug_level() {
+    setup();
+
+    assert_eq!(arithmetic::divide(84, 2), Ok(42));
+    assert!(logged(Level::Debug, "divide(84, 2)"));
+}
+
+#[test]
+fn invalid_utf8_is_replaced() {
+    setup();
+
+    // SAFETY: the format takes one string, and both are NUL-terminated.
+    unsafe { c_log(3, c"%s".as_ptr(), c"bad \xff byte".as_ptr()) };
+    assert!(logged(Level::Info, "bad \u{fffd} byte"));
+}
+
+#[test]
+fn levels_match_the_log_crate() {
+    assert_eq!(logging::level_from_c(1), Level::Error);
+    assert_eq!(logging::level_from_c(2), Level::Warn);
+    assert_eq!(logging::level_from_c(3), Level::Info);
+    assert_eq!(logging::level_from_c(4), Level::Debug);
+    assert_eq!(logging::level_from_c(5), Level::Trace);
+    assert_eq!(logging::level_from_c(0), Level::Error);
+    assert_eq!(logging::level_from_c(42), Level::Error);
+}
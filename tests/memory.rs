@@ -0,0 +1,189 @@
+//! Allocating in C and freeing in Rust, over and over, for a memory checker
+//! to watch.
+//!
+//! These tests pass without one, since a leak or a double free rarely shows
+//! up otherwise. Their point is to run every drop path of the wrappers that
+//! own C memory, so that AddressSanitizer or valgrind can catch a leak, a
+//! double free, a use after free or memory freed by the wrong allocator.
+//! With a nightly toolchain, both the Rust and the C side can be
+//! instrumented:
+//!
+//! ```text
+//! CFLAGS=-fsanitize=address RUSTFLAGS=-Zsanitizer=address \
+//!     cargo +nightly test --test memory --target x86_64-unknown-linux-gnu
+//! ```
+//!
+//! `--target` keeps the sanitizer flags away from build scripts and
+//! proc-macros. Without nightly, the same tests run under valgrind, which
+//! fails them on any definite leak:
+//!
+//! ```text
+//! CARGO_TARGET_X86_64_UNKNOWN_LINUX_GNU_RUNNER="valgrind --error-exitcode=1 --leak-check=full --errors-for-leak-kinds=definite" \
+//!     cargo test --test memory
+//! ```
+//!
+//! Nothing here uses `mem::forget` or leaks on purpose, so both report zero
+//! leaks when the wrappers are right.
+
+use std::panic;
+
+use rust_ffi_to_c::alloc_string;
+use rust_ffi_to_c::calculator::Calculator;
+use rust_ffi_to_c::context::CContext;
+use rust_ffi_to_c::counter::Counter;
+use rust_ffi_to_c::cstr_ownership;
+use rust_ffi_to_c::linked_list::CList;
+use rust_ffi_to_c::range;
+use rust_ffi_to_c::ring_buffer::RingBuffer;
+use rust_ffi_to_c::strings::{self, GreetError};
+
+// Enough rounds that a leak in any one of them adds up to something a
+// checker can't mistake for a one-off allocation.
+const ROUNDS: usize = 1000;
+
+#[test]
+fn greet_frees_every_greeting() {
+    for i in 0..ROUNDS {
+        let name = format!("Ferris {}", i);
+        assert_eq!(strings::greet(&name).unwrap(), format!("Hello, {}!", name));
+    }
+}
+
+#[test]
+fn greet_allocates_nothing_for_a_rejected_name() {
+    for _ in 0..ROUNDS {
+        assert_eq!(strings::greet("Fer\0ris"), Err(GreetError::InteriorNul(3)));
+    }
+}
+
+#[test]
+fn range_is_freed_when_dropped() {
+    for count in 0..ROUNDS {
+        let values = range::make_range(-1, count).unwrap();
+        assert_eq!(values.len(), count);
+    }
+}
+
+#[test]
+fn range_is_freed_after_being_moved() {
+    let buffers: Vec<_> = (1..=ROUNDS)
+        .map(|count| range::make_range(0, count).unwrap())
+        .collect();
+    let total: usize = buffers.iter().map(|values| values.len()).sum();
+    assert_eq!(total, ROUNDS * (ROUNDS + 1) / 2);
+
+    // Dropping the `Vec` drops every buffer in it.
+    drop(buffers);
+}
+
+#[test]
+fn range_is_freed_while_unwinding() {
+    let result = panic::catch_unwind(|| {
+        let values = range::make_range(0, 10).unwrap();
+        assert_eq!(values[9], 9);
+        panic!("dropped during unwinding");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn range_vec_frees_c_copy() {
+    for n in 0..100 {
+        assert_eq!(range::make_range_vec(n).len(), n as usize);
+    }
+}
+
+#[test]
+fn counter_is_freed_when_dropped() {
+    for _ in 0..ROUNDS {
+        let mut counter = Counter::new().unwrap();
+        counter.increment();
+        assert_eq!(counter.value(), 1);
+    }
+}
+
+#[test]
+fn counter_is_freed_once_after_being_moved() {
+    let counters: Vec<_> = (0..ROUNDS).map(|_| Counter::new().unwrap()).collect();
+    let mut moved = counters;
+    moved.truncate(ROUNDS / 2);
+    for counter in &mut moved {
+        counter.increment();
+    }
+    assert!(moved.iter().all(|counter| counter.value() == 1));
+}
+
+#[test]
+fn counter_is_freed_while_unwinding() {
+    let result = panic::catch_unwind(|| {
+        let _counter = Counter::new().unwrap();
+        panic!("dropped during unwinding");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn calculator_is_freed_when_dropped() {
+    for round in 0..ROUNDS as i32 {
+        let mut calc = Calculator::new().unwrap();
+        for value in [round, 2, -1] {
+            calc.accumulate(value);
+        }
+        assert_eq!(calc.result(), round + 1);
+    }
+}
+
+#[test]
+fn context_is_destroyed_when_dropped() {
+    for seed in 0..ROUNDS as i32 {
+        let ctx = CContext::new(seed);
+        assert!((0..=0x7fff).contains(&ctx.value()));
+    }
+}
+
+// A moved `CContext` is owned by exactly one binding at a time, so however
+// often it changes hands, `ctx_destroy()` runs once for it. A double free
+// here is what valgrind and AddressSanitizer would report.
+#[test]
+fn context_is_destroyed_once_after_being_moved() {
+    fn pass_through(ctx: CContext) -> CContext {
+        ctx
+    }
+
+    let contexts: Vec<_> = (0..ROUNDS as i32).map(CContext::new).collect();
+    let mut moved: Vec<_> = contexts.into_iter().map(pass_through).collect();
+    moved.truncate(ROUNDS / 2);
+    let first = moved.swap_remove(0);
+    assert_eq!(first.value(), CContext::new(0).value());
+}
+
+#[test]
+fn context_is_destroyed_while_unwinding() {
+    let result = panic::catch_unwind(|| {
+        let ctx = CContext::new(1);
+        ctx.value();
+        panic!("dropped during unwinding");
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn other_c_allocations_are_freed() {
+    for i in 0..ROUNDS {
+        let owned = cstr_ownership::heap_string(&i.to_string())
+            .unwrap()
+            .unwrap();
+        assert_eq!(owned.to_str(), Ok(i.to_string().as_str()));
+
+        let message = alloc_string::get_message().unwrap();
+        assert!(!message.as_str().is_empty());
+    }
+
+    let list = CList::new(100).unwrap();
+    assert_eq!(list.iter().count(), 100);
+
+    for capacity in 1..100 {
+        let mut rb = RingBuffer::new(capacity).unwrap();
+        assert!(rb.push(1));
+    }
+}
@@ -0,0 +1,45 @@
+//! Development tasks for the workspace.
+//!
+//! `include/rust_exports.h` declares the functions Rust exports to C in
+//! `src/exported.rs`. It is generated with cbindgen and committed, so the C
+//! code in `c_src/` can include it and the C compiler checks every call
+//! against the Rust signature. Regenerate it with:
+//!
+//! ```text
+//! cargo xtask gen-header
+//! ```
+//!
+//! `tests/header.rs` fails when the committed header and the Rust code have
+//! drifted apart.
+
+use std::path::{Path, PathBuf};
+
+/// Where the header goes, relative to the workspace root.
+pub const HEADER: &str = "include/rust_exports.h";
+
+/// The workspace root, where `Cargo.toml`, `cbindgen.toml` and `src/` of
+/// rust-ffi-to-c are.
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is inside the workspace")
+        .to_path_buf()
+}
+
+/// Generates the header from `src/exported.rs` with the settings in
+/// `cbindgen.toml`.
+///
+/// Only `src/exported.rs` is scanned. `rust_configure` takes a `Config`
+/// that `src/nullable.rs` mirrors from `c_src/multiply.h`, so the header
+/// includes `multiply.h` for it instead of defining it a second time.
+pub fn generate_header() -> cbindgen::Bindings {
+    let root = workspace_root();
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml"))
+        .expect("failed to read cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_src(root.join("src").join("exported.rs"))
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the header")
+}
@@ -0,0 +1,26 @@
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: cargo xtask gen-header";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["gen-header"] => {
+            let path = xtask::workspace_root().join(xtask::HEADER);
+            if xtask::generate_header().write_to_file(&path) {
+                println!(
+                    "wrote {}; commit it along with the Rust change",
+                    xtask::HEADER
+                );
+            } else {
+                println!("{} is up to date", xtask::HEADER);
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::fs;
+use std::path::Path;
+
+// Regenerates the header into a scratch directory and compares it with the
+// committed one, so a change to an exported signature that isn't reflected
+// in include/rust_exports.h fails `cargo test`.
+#[test]
+fn committed_header_is_up_to_date() {
+    let fresh = Path::new(env!("CARGO_TARGET_TMPDIR")).join("rust_exports.h");
+    xtask::generate_header().write_to_file(&fresh);
+
+    let committed = fs::read_to_string(xtask::workspace_root().join(xtask::HEADER))
+        .expect("failed to read the committed header");
+    let fresh = fs::read_to_string(&fresh).expect("failed to read the generated header");
+
+    assert!(
+        committed == fresh,
+        "{} is out of date. Regenerate it with `cargo xtask gen-header` and commit the result.",
+        xtask::HEADER
+    );
+}